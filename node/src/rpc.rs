@@ -8,7 +8,8 @@
 use std::sync::Arc;
 
 use polket_runtime::{
-	opaque::Block, AccountId, Balance, BlockNumber, Hash, Index, ObjectId, VFEDetail,
+	opaque::Block, AccountId, Balance, BlockNumber, DailyTraining, Hash, Index, ObjectId, TeamSummary,
+	VFEDetail,
 };
 use sc_client_api::AuxStore;
 use sc_consensus_babe::{BabeApi, Epoch};
@@ -83,7 +84,17 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
-	C::Api: pallet_vfe_rpc::VFERuntimeApi<Block, AccountId, ObjectId, ObjectId, VFEDetail, Balance>,
+	C::Api: pallet_vfe_rpc::VFERuntimeApi<
+		Block,
+		AccountId,
+		ObjectId,
+		ObjectId,
+		VFEDetail,
+		DailyTraining,
+		TeamSummary,
+		Balance,
+		BlockNumber,
+	>,
 	C::Api: pallet_currencies_rpc::CurrenciesRuntimeApi<Block, AccountId, ObjectId, Balance>,
 	P: TransactionPool + Sync + Send + 'static,
 	SC: SelectChain<Block> + 'static,
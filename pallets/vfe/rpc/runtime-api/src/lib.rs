@@ -10,18 +10,38 @@ use codec::Codec;
 use sp_std::prelude::Vec;
 
 sp_api::decl_runtime_apis! {
-	pub trait VfeApi<AccountId, BrandId, ItemId, VFEDetail, Balance> where
+	/// The version 2 adds the upgrade completion, the emission state, the training history, the
+	/// leaderboards and the teams.
+	#[api_version(2)]
+	pub trait VfeApi<AccountId, BrandId, ItemId, VFEDetail, DailyTraining, TeamSummary, Balance, BlockNumber> where
 		AccountId: Codec,
 		BrandId: Codec,
 		ItemId: Codec,
 		VFEDetail: Codec,
+		DailyTraining: Codec,
+		TeamSummary: Codec,
 		Balance: Codec,
+		BlockNumber: Codec,
 	{
 		fn get_vfe_details_by_address(account: AccountId, brand_id: BrandId) -> Vec<VFEDetail>;
 
 	 	fn get_charging_costs(brand_id: BrandId, item: ItemId, charge_num: u16) -> Balance;
 
 	 	fn get_level_up_costs(who: AccountId, brand_id: BrandId, item: ItemId) -> Balance;
+
+		fn get_upgrade_completion(brand_id: BrandId, item: ItemId) -> Option<BlockNumber>;
+
+		fn get_emission_state() -> (u32, Balance, Balance);
+
+		fn get_training_history(who: AccountId) -> Vec<DailyTraining>;
+
+		fn get_sport_leaderboard(sport_type: u16) -> Vec<(AccountId, u64)>;
+
+		fn get_brand_leaderboard(brand_id: BrandId) -> Vec<(AccountId, u64)>;
+
+		fn get_team(team_id: u32) -> Option<TeamSummary>;
+
+		fn get_user_team(who: AccountId) -> Option<TeamSummary>;
 	}
 
 }
@@ -13,7 +13,18 @@ use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 pub use pallet_vfe_rpc_runtime_api::VfeApi as VFERuntimeApi;
 
 #[rpc(client, server)]
-pub trait VfeApi<BlockHash, AccountId, BrandId, ItemId, VFEDetail, Balance> {
+pub trait VfeApi<
+	BlockHash,
+	AccountId,
+	BrandId,
+	ItemId,
+	VFEDetail,
+	DailyTraining,
+	TeamSummary,
+	Balance,
+	BlockNumber,
+>
+{
 	#[method(name = "vfe_getVFEDetailsByAddress")]
 	fn get_vfe_details_by_address(
 		&self,
@@ -39,6 +50,48 @@ pub trait VfeApi<BlockHash, AccountId, BrandId, ItemId, VFEDetail, Balance> {
 		item: ItemId,
 		at: Option<BlockHash>,
 	) -> RpcResult<Balance>;
+
+	#[method(name = "vfe_getUpgradeCompletion")]
+	fn get_upgrade_completion(
+		&self,
+		brand_id: BrandId,
+		item: ItemId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<BlockNumber>>;
+
+	#[method(name = "vfe_getEmissionState")]
+	fn get_emission_state(&self, at: Option<BlockHash>) -> RpcResult<(u32, Balance, Balance)>;
+
+	#[method(name = "vfe_getTrainingHistory")]
+	fn get_training_history(
+		&self,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<DailyTraining>>;
+
+	#[method(name = "vfe_getSportLeaderboard")]
+	fn get_sport_leaderboard(
+		&self,
+		sport_type: u16,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(AccountId, u64)>>;
+
+	#[method(name = "vfe_getBrandLeaderboard")]
+	fn get_brand_leaderboard(
+		&self,
+		brand_id: BrandId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(AccountId, u64)>>;
+
+	#[method(name = "vfe_getTeam")]
+	fn get_team(&self, team_id: u32, at: Option<BlockHash>) -> RpcResult<Option<TeamSummary>>;
+
+	#[method(name = "vfe_getUserTeam")]
+	fn get_user_team(
+		&self,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<TeamSummary>>;
 }
 
 /// Provides RPC methods to query vfe detail.
@@ -68,18 +121,51 @@ impl From<Error> for i32 {
 }
 
 #[async_trait]
-impl<C, Block, AccountId, BrandId, ItemId, VFEDetail, Balance>
-	VfeApiServer<<Block as BlockT>::Hash, AccountId, BrandId, ItemId, VFEDetail, Balance>
-	for Vfe<C, Block>
+impl<
+		C,
+		Block,
+		AccountId,
+		BrandId,
+		ItemId,
+		VFEDetail,
+		DailyTraining,
+		TeamSummary,
+		Balance,
+		BlockNumber,
+	>
+	VfeApiServer<
+		<Block as BlockT>::Hash,
+		AccountId,
+		BrandId,
+		ItemId,
+		VFEDetail,
+		DailyTraining,
+		TeamSummary,
+		Balance,
+		BlockNumber,
+	> for Vfe<C, Block>
 where
 	Block: BlockT,
 	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
-	C::Api: VFERuntimeApi<Block, AccountId, BrandId, ItemId, VFEDetail, Balance>,
+	C::Api: VFERuntimeApi<
+		Block,
+		AccountId,
+		BrandId,
+		ItemId,
+		VFEDetail,
+		DailyTraining,
+		TeamSummary,
+		Balance,
+		BlockNumber,
+	>,
 	AccountId: Codec,
 	BrandId: Codec,
 	ItemId: Codec,
 	VFEDetail: Codec,
+	DailyTraining: Codec,
+	TeamSummary: Codec,
 	Balance: Codec,
+	BlockNumber: Codec,
 {
 	fn get_vfe_details_by_address(
 		&self,
@@ -139,4 +225,130 @@ where
 			.into()
 		})
 	}
+
+	fn get_upgrade_completion(
+		&self,
+		brand_id: BrandId,
+		item: ItemId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<BlockNumber>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_upgrade_completion(&at, brand_id, item).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to get value.",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
+
+	fn get_emission_state(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<(u32, Balance, Balance)> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_emission_state(&at).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to get value.",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
+
+	fn get_training_history(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<DailyTraining>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_training_history(&at, who).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to get value.",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
+
+	fn get_sport_leaderboard(
+		&self,
+		sport_type: u16,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(AccountId, u64)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_sport_leaderboard(&at, sport_type).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to get value.",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
+
+	fn get_brand_leaderboard(
+		&self,
+		brand_id: BrandId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(AccountId, u64)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_brand_leaderboard(&at, brand_id).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to get value.",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
+
+	fn get_team(
+		&self,
+		team_id: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<TeamSummary>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_team(&at, team_id).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to get value.",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
+
+	fn get_user_team(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<TeamSummary>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_user_team(&at, who).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to get value.",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
 }
@@ -55,6 +55,8 @@ where
 		destination: &T::AccountId,
 	) -> DispatchResult {
 		let from = Self::owner(collection, item).ok_or(Error::<T>::VFENotExist)?;
+		// settle the finished upgrade first
+		Self::try_complete_level_up(collection, item)?;
 		Self::check_vfe_can_transfer(collection, item)?;
 		<pallet_uniques::Pallet<T, T::UniquesInstance> as NFTTransfer<T::AccountId>>::transfer(
 			collection,
@@ -49,7 +49,7 @@ use sp_runtime::{
 		AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedDiv, CheckedSub, Hash, One,
 		Saturating, StaticLookup, Zero,
 	},
-	ModuleError, Permill, SaturatedConversion,
+	FixedPointNumber, FixedU128, ModuleError, PerThing, Permill, SaturatedConversion,
 };
 use sp_std::{
 	borrow::ToOwned,
@@ -67,6 +67,7 @@ mod mock;
 mod tests;
 
 pub mod impl_nonfungibles;
+pub mod migrations;
 pub mod types;
 
 type BalanceOf<T> =
@@ -128,11 +129,11 @@ pub mod pallet {
 		#[pallet::constant]
 		type VFEBrandId: Get<Self::Hash>;
 
-		/// Fees for unbinding VFE
+		/// Fees for unbinding VFE, default of the unbind settings
 		#[pallet::constant]
 		type UnbindFee: Get<BalanceOf<Self>>;
 
-		/// Units of Incentive Tokens Rewarded or Costed
+		/// Units of Incentive Tokens Rewarded or Costed, default of the economic parameters
 		#[pallet::constant]
 		type CostUnit: Get<BalanceOf<Self>>;
 
@@ -144,19 +145,19 @@ pub mod pallet {
 		#[pallet::constant]
 		type DailyEarnedResetDuration: Get<Self::BlockNumber>;
 
-		/// level up cost factor
+		/// level up cost factor, default of the economic parameters
 		#[pallet::constant]
 		type LevelUpCostFactor: Get<BalanceOf<Self>>;
 
-		/// init energy when new user created
+		/// init energy when new user created, default of the economic parameters
 		#[pallet::constant]
 		type InitEnergy: Get<u16>;
 
-		/// init earning cap of daily when new user created
+		/// init earning cap of daily when new user created, default of the economic parameters
 		#[pallet::constant]
 		type InitEarningCap: Get<u16>;
 
-		/// ratio of each energy recovery
+		/// ratio of each energy recovery, default of the economic parameters
 		#[pallet::constant]
 		type EnergyRecoveryRatio: Get<Permill>;
 
@@ -167,13 +168,156 @@ pub mod pallet {
 		#[pallet::constant]
 		type ReportValidityPeriod: Get<u32>;
 
-		/// Profit ratio of minting fee to VFE owner
+		/// Profit ratio of minting fee to VFE owner, default of the economic parameters
 		#[pallet::constant]
 		type UserVFEMintedProfitRatio: Get<Permill>;
+
+		/// The origin which may register sport types and tune the parameters of the game.
+		type GovernanceOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin which may clear the quarantined devices besides the producer.
+		type AuditOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin of auditors who may challenge the training reports.
+		type ChallengeOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+
+		/// How many blocks the training report can be challenged after it is accepted, the
+		/// rewards are held until then. Zero means the rewards are paid immediately.
+		#[pallet::constant]
+		type ChallengeWindow: Get<Self::BlockNumber>;
+
+		/// How many days of the daily training aggregates are kept for each user and sport
+		#[pallet::constant]
+		type MaxHistoryDays: Get<u32>;
+
+		/// The max number of the top ranks kept in each leaderboard
+		#[pallet::constant]
+		type LeaderboardSize: Get<u32>;
+
+		/// The max number of the unsettled challenges of each brand
+		#[pallet::constant]
+		type MaxBrandChallenges: Get<u32>;
+
+		/// The max number of the members of each team
+		#[pallet::constant]
+		type MaxTeamMembers: Get<u32>;
+
+		/// How many blocks of each period of the team goal
+		#[pallet::constant]
+		type TeamGoalPeriod: Get<Self::BlockNumber>;
+
+		/// The training volume each member adds to the team goal of the period
+		#[pallet::constant]
+		type TeamGoalPerMember: Get<u64>;
+
+		/// The bonus on the rewards of the members in the period after the team reaches the goal
+		#[pallet::constant]
+		type TeamBonus: Get<Permill>;
+
+		/// The share of the training rewards of the referee paid to the referrer
+		#[pallet::constant]
+		type ReferralShare: Get<Permill>;
+
+		/// How many blocks after the referee is created the referral rewards are paid
+		#[pallet::constant]
+		type ReferralPeriod: Get<Self::BlockNumber>;
+
+		/// The max referral rewards paid to each referrer
+		#[pallet::constant]
+		type MaxReferralRewards: Get<BalanceOf<Self>>;
+
+		/// The ratio of the caps of the staked VFE's level added to the owner for each rarity
+		#[pallet::constant]
+		type StakingBoostRatio: Get<Permill>;
+
+		/// How many blocks the unstaked VFE is locked before it can be withdrawn
+		#[pallet::constant]
+		type StakingUnbondingPeriod: Get<Self::BlockNumber>;
+
+		/// How many blocks to upgrade each level of VFE
+		#[pallet::constant]
+		type LevelUpDurationPerLevel: Get<Self::BlockNumber>;
+
+		/// The incentive tokens burned to speed up the upgrading VFE for each remaining block
+		#[pallet::constant]
+		type LevelUpSpeedUpCost: Get<BalanceOf<Self>>;
+
+		/// How many blocks the parents can not breed again after fusion
+		#[pallet::constant]
+		type BreedCooldown: Get<Self::BlockNumber>;
+
+		/// The maximum number of times a VFE can breed
+		#[pallet::constant]
+		type MaxBreedCount: Get<u16>;
+
+		/// The incentive tokens burned to fuse VFEs, multiplied by the breeding times of parents
+		#[pallet::constant]
+		type FusionCost: Get<BalanceOf<Self>>;
+
+		/// The wear of VFE by consuming one energy, which is divided by the durable of VFE
+		#[pallet::constant]
+		type WearPerEnergy: Get<Permill>;
+
+		/// The rewards are reduced in proportion when the condition of VFE drops below it
+		#[pallet::constant]
+		type WearPenaltyThreshold: Get<Permill>;
+
+		/// The incentive tokens burned to repair one per mille of wear for each level of VFE
+		#[pallet::constant]
+		type RepairCost: Get<BalanceOf<Self>>;
+
+		/// The blocks of an emission period, the budget of incentive token is reset every period
+		#[pallet::constant]
+		type EmissionPeriod: Get<Self::BlockNumber>;
+
+		/// The emission budget of incentive token per period at the start
+		#[pallet::constant]
+		type InitialEmission: Get<BalanceOf<Self>>;
+
+		/// The emission budget halves every this many periods, zero means never
+		#[pallet::constant]
+		type HalvingPeriods: Get<u32>;
+
+		/// The gems or equipment which can be socketed into VFE
+		type Gems: Inspect<Self::AccountId, CollectionId = Self::CollectionId, ItemId = Self::ItemId>
+			+ Create<Self::AccountId>
+			+ Mutate<Self::AccountId>
+			+ NFTTransfer<Self::AccountId>;
+
+		/// The gem collection-id parent key
+		#[pallet::constant]
+		type GemId: Get<Self::Hash>;
+
+		/// The maximum number of sockets of VFE
+		#[pallet::constant]
+		type MaxSockets: Get<u32>;
+
+		/// VFE unlocks a socket every this many levels
+		#[pallet::constant]
+		type LevelsPerSocket: Get<u16>;
+
+		/// The incentive tokens burned to remove a gem from VFE
+		#[pallet::constant]
+		type UnsocketFee: Get<BalanceOf<Self>>;
+
+		/// The maximum of each ability of a gem
+		#[pallet::constant]
+		type MaxGemAbility: Get<u16>;
+
+		/// The treasury account which receives the fees
+		type TreasuryAccount: Get<Self::AccountId>;
+
+		/// The maximum length of the Merkle proof of the device batch
+		#[pallet::constant]
+		type MaxMerkleProofLength: Get<u32>;
 	}
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub (super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
@@ -248,6 +392,379 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn get_sport_types)]
+	/// Record the sport types registered by governance, the built-in sport types are used if it
+	/// is not set.
+	pub type SportTypes<T: Config> =
+		StorageMap<_, Twox64Concat, SportId, SportTypeInfo, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_producer_cert_keys)]
+	/// Record the certificate public key of the producer, which signs the device certificates
+	/// for lazy registration.
+	pub type ProducerCertKeys<T: Config> =
+		StorageMap<_, Twox64Concat, T::ObjectId, DeviceKey, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_device_batches)]
+	/// Record the device batches committed by the Merkle root of the device keys
+	pub type DeviceBatches<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CollectionId,
+		Identity,
+		T::Hash,
+		DeviceBatch<T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_unbind_config)]
+	/// Record the unbind settings set by governance, the fee of `Config` is charged in the
+	/// incentive token and burned if it is not set.
+	pub type UnbindConfig<T: Config> =
+		StorageValue<_, UnbindSettings<AssetIdOf<T>, BalanceOf<T>, T::BlockNumber>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_vfe_bind_cooldowns)]
+	/// Record the block until which the VFE can not be bound again after unbinding
+	pub type VFEBindCooldowns<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CollectionId,
+		Twox64Concat,
+		T::ItemId,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_device_bind_cooldowns)]
+	/// Record the block until which the device can not be bound again after unbinding
+	pub type DeviceBindCooldowns<T: Config> =
+		StorageMap<_, Blake2_128Concat, DeviceKey, T::BlockNumber, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_upgrading_vfes)]
+	/// Record the block when the upgrading VFE completes
+	pub type UpgradingVFEs<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CollectionId,
+		Twox64Concat,
+		T::ItemId,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_vfe_breeds)]
+	/// Record the breeding information of VFE
+	pub type VFEBreeds<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CollectionId,
+		Twox64Concat,
+		T::ItemId,
+		BreedInfo<T::BlockNumber>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_fusion_allowances)]
+	/// Record the number of VFEs the brand owner allows to be created by fusion
+	pub type FusionAllowances<T: Config> =
+		StorageMap<_, Twox64Concat, T::CollectionId, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_vfe_wear)]
+	/// Record the wear of VFE accumulated from training, the rest is the condition of VFE
+	pub type VFEWear<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CollectionId,
+		Twox64Concat,
+		T::ItemId,
+		Permill,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_gem_details)]
+	/// Record the abilities of the gems
+	pub type GemDetails<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CollectionId,
+		Twox64Concat,
+		T::ItemId,
+		GemDetail,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_vfe_sockets)]
+	/// Record the gems socketed into VFE, which are held by the pallet and move with the VFE
+	pub type VFESockets<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CollectionId,
+		Twox64Concat,
+		T::ItemId,
+		BoundedVec<(T::CollectionId, T::ItemId), T::MaxSockets>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_brand_reward_configs)]
+	/// Record the reward settings of VFE brand, the rewards are paid from the brand pool
+	pub type BrandRewardConfigs<T: Config> =
+		StorageMap<_, Twox64Concat, T::CollectionId, BrandRewardConfig<AssetIdOf<T>>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_emission)]
+	/// Record the emission of incentive token in the latest period
+	pub type Emission<T: Config> = StorageValue<_, EmissionState<BalanceOf<T>>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_plausibility_config)]
+	/// Record the plausibility rules set by governance, the statistical checks of training
+	/// reports are disabled if it is not set.
+	pub type PlausibilityConfig<T: Config> = StorageValue<_, PlausibilityRules, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_device_daily_reports)]
+	/// Record the day and the number of reports of the device in the day
+	pub type DeviceDailyReports<T: Config> =
+		StorageMap<_, Blake2_128Concat, DeviceKey, (u32, u16), ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_device_suspicion)]
+	/// Record the suspicion score of the device raised by the implausible reports
+	pub type DeviceSuspicion<T: Config> =
+		StorageMap<_, Blake2_128Concat, DeviceKey, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_quarantined_devices)]
+	/// Record the block when the device is quarantined
+	pub type QuarantinedDevices<T: Config> =
+		StorageMap<_, Blake2_128Concat, DeviceKey, T::BlockNumber, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_held_rewards)]
+	/// Record the training rewards of the quarantined device held for the VFE owner, keyed by
+	/// the device and the report time
+	pub type HeldRewards<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		DeviceKey,
+		Twox64Concat,
+		u32,
+		HeldReport<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_pending_reports)]
+	/// Record the rewards of the training reports in the challenge window, keyed by the device
+	/// and the report time
+	pub type PendingReports<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		DeviceKey,
+		Twox64Concat,
+		u32,
+		PendingReport<T::AccountId, T::CollectionId, T::ItemId, BalanceOf<T>, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_training_history)]
+	/// Record the daily training aggregates of the user in the sport, ordered by day, the days
+	/// older than `MaxHistoryDays` are pruned
+	pub type TrainingHistory<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		SportId,
+		BoundedVec<DailyTraining<BalanceOf<T>>, T::MaxHistoryDays>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_leaderboards)]
+	/// Record the settings of the leaderboards
+	pub type Leaderboards<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		LeaderboardKey<T::CollectionId>,
+		LeaderboardInfo<AssetIdOf<T>, T::BlockNumber, T::LeaderboardSize>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_leaderboard_standings)]
+	/// Record the top ranks of the leaderboard in the latest period
+	pub type LeaderboardStandings<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		LeaderboardKey<T::CollectionId>,
+		LeaderboardRanks<T::AccountId, T::LeaderboardSize>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_ended_leaderboard_standings)]
+	/// Record the top ranks of the ended periods of the leaderboard which are not settled
+	pub type EndedLeaderboardStandings<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		LeaderboardKey<T::CollectionId>,
+		Twox64Concat,
+		u32,
+		EndedLeaderboardRanks<T::AccountId, BalanceOf<T>, T::LeaderboardSize>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_leaderboard_reserved_prizes)]
+	/// Record the prizes in the prize pool reserved by the ended periods which are not settled
+	pub type LeaderboardReservedPrizes<T: Config> =
+		StorageMap<_, Blake2_128Concat, LeaderboardKey<T::CollectionId>, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_leaderboard_scores)]
+	/// Record the period and the training volume of the user in the leaderboard
+	pub type LeaderboardScores<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		LeaderboardKey<T::CollectionId>,
+		Blake2_128Concat,
+		T::AccountId,
+		(u32, u64),
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_next_brand_challenge_id)]
+	/// The id of the next brand challenge
+	pub type NextBrandChallengeId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_brand_challenges)]
+	/// Record the brand challenges
+	pub type BrandChallenges<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u32,
+		BrandChallenge<T::CollectionId, AssetIdOf<T>, BalanceOf<T>, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_unsettled_brand_challenges)]
+	/// Record the unsettled challenges of the brand
+	pub type UnsettledBrandChallenges<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::CollectionId,
+		BoundedVec<u32, T::MaxBrandChallenges>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_challenge_entries)]
+	/// Record the participation of the users in the brand challenge
+	pub type ChallengeEntries<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		u32,
+		Blake2_128Concat,
+		T::AccountId,
+		ChallengeEntry<T::ItemId>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_next_team_id)]
+	/// The id of the next team
+	pub type NextTeamId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_teams)]
+	/// Record the teams
+	pub type Teams<T: Config> =
+		StorageMap<_, Twox64Concat, u32, Team<T::AccountId, T::MaxTeamMembers>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_user_team)]
+	/// Record the team of the user
+	pub type UserTeam<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_team_invitations)]
+	/// Record the users invited by the team captain
+	pub type TeamInvitations<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, u32, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_team_progress)]
+	/// Record the training progress of the team
+	pub type TeamProgresses<T: Config> = StorageMap<_, Twox64Concat, u32, TeamProgress, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_referrer)]
+	/// Record the referrer of the user
+	pub type Referrers<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_referral_earnings)]
+	/// Record the referral rewards paid to the referrer
+	pub type ReferralEarnings<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_vfe_loans)]
+	/// Record the VFEs listed for lending
+	pub type VFELoans<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CollectionId,
+		Twox64Concat,
+		T::ItemId,
+		VFELoan<T::AccountId, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_staked_vfes)]
+	/// Record the VFEs staked by the owners
+	pub type StakedVFEs<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::CollectionId,
+		Twox64Concat,
+		T::ItemId,
+		StakedVFE<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_staking_boosts)]
+	/// Record the boosts of the daily caps of the user from the staked VFEs
+	pub type StakingBoosts<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, StakingBoost<BalanceOf<T>>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_economic_params)]
+	/// Record the economic parameters set by governance, the defaults of `Config` are used if it
+	/// is not set.
+	pub type EconomicParams<T: Config> =
+		StorageValue<_, EconomicParameters<BalanceOf<T>>, OptionQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn get_vfe_approvals)]
 	/// Record the allowed minting information of VFE brand
@@ -279,11 +796,14 @@ pub mod pallet {
 			new_owner: T::AccountId,
 		},
 
+		/// The certificate public key of producer is set.
+		ProducerCertKeySet { producer_id: T::ObjectId, cert_key: Option<DeviceKey> },
+
 		/// Created device type class.
 		VFEBrandCreated {
 			who: T::AccountId,
 			brand_id: T::CollectionId,
-			sport_type: SportType,
+			sport_type: SportId,
 			rarity: VFERarity,
 			note: Vec<u8>,
 		},
@@ -299,6 +819,46 @@ pub mod pallet {
 		/// deregister device.
 		DeviceDeregistered { operator: T::AccountId, device_key: DeviceKey },
 
+		/// A device is voided, the VFE bound to it is unbound and the locked minting fee of a
+		/// registered device is refunded to the producer owner.
+		DeviceVoided {
+			operator: Option<T::AccountId>,
+			device_key: DeviceKey,
+			brand_id: T::CollectionId,
+			item_id: Option<T::ItemId>,
+			refund: Option<(AssetIdOf<T>, BalanceOf<T>)>,
+		},
+
+		/// A batch of devices is committed by the Merkle root.
+		DeviceBatchCommitted {
+			operator: T::AccountId,
+			producer_id: T::ObjectId,
+			brand_id: T::CollectionId,
+			root: T::Hash,
+			count: u32,
+		},
+
+		/// A device of the batch is claimed at binding time.
+		DeviceBatchClaimed { device_key: DeviceKey, brand_id: T::CollectionId, root: T::Hash },
+
+		/// A device batch is closed, the unclaimed devices are refunded.
+		DeviceBatchClosed {
+			operator: T::AccountId,
+			producer_id: T::ObjectId,
+			brand_id: T::CollectionId,
+			root: T::Hash,
+			unclaimed: u32,
+		},
+
+		/// A device is replaced by a new key, the old key is voided.
+		DeviceReplaced {
+			operator: T::AccountId,
+			old_device_key: DeviceKey,
+			new_device_key: DeviceKey,
+			brand_id: T::CollectionId,
+			item_id: Option<T::ItemId>,
+		},
+
 		/// Create VFE.
 		VFECreated {
 			owner: T::AccountId,
@@ -340,7 +900,7 @@ pub mod pallet {
 			owner: T::AccountId,
 			brand_id: T::CollectionId,
 			item_id: T::ItemId,
-			sport_type: SportType,
+			sport_type: SportId,
 			training_time: u32,
 			training_duration: u16,
 			training_count: u16,
@@ -388,6 +948,308 @@ pub mod pallet {
 
 		/// the VFE ability is increased.
 		VFEAbilityIncreased { brand_id: T::CollectionId, item_id: T::ItemId },
+
+		/// A new sport type is registered.
+		SportTypeRegistered { sport_type: SportId, info: SportTypeInfo },
+
+		/// The parameters of sport type are updated.
+		SportTypeUpdated { sport_type: SportId, info: SportTypeInfo },
+
+		/// The unbind settings are updated.
+		UnbindSettingsUpdated {
+			settings: UnbindSettings<AssetIdOf<T>, BalanceOf<T>, T::BlockNumber>,
+		},
+
+		/// The fee of unbinding device is paid.
+		UnbindFeePaid {
+			who: T::AccountId,
+			asset_id: AssetIdOf<T>,
+			fee: BalanceOf<T>,
+			destination: FeeDestination,
+		},
+
+		/// The VFE starts upgrading.
+		VFEUpgradeStarted {
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			level_up: u16,
+			completion: T::BlockNumber,
+		},
+
+		/// The upgrading VFE is sped up.
+		VFEUpgradeSpedUp { brand_id: T::CollectionId, item_id: T::ItemId, cost: BalanceOf<T> },
+
+		/// The VFE completes upgrading.
+		VFEUpgradeCompleted { brand_id: T::CollectionId, item_id: T::ItemId, level: u16 },
+
+		/// The fusion allowance of VFE brand is set.
+		FusionAllowanceSet { brand_id: T::CollectionId, amount: u32 },
+
+		/// Two VFEs are fused into a new VFE.
+		VFEFused {
+			owner: T::AccountId,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			parents: [(T::CollectionId, T::ItemId); 2],
+			cost: BalanceOf<T>,
+		},
+
+		/// The worn VFE is repaired.
+		VFERepaired {
+			owner: T::AccountId,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			cost: BalanceOf<T>,
+		},
+
+		/// A gem collection is created.
+		GemCollectionCreated { who: T::AccountId, collection_id: T::CollectionId },
+
+		/// A gem is minted.
+		GemMinted {
+			owner: T::AccountId,
+			collection_id: T::CollectionId,
+			item_id: T::ItemId,
+			detail: GemDetail,
+		},
+
+		/// A gem is socketed into VFE.
+		GemSocketed {
+			owner: T::AccountId,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			gem_collection_id: T::CollectionId,
+			gem_item_id: T::ItemId,
+		},
+
+		/// A gem is removed from VFE.
+		GemUnsocketed {
+			owner: T::AccountId,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			gem_collection_id: T::CollectionId,
+			gem_item_id: T::ItemId,
+			fee: BalanceOf<T>,
+		},
+
+		/// The reward settings of VFE brand are set.
+		BrandRewardConfigSet {
+			brand_id: T::CollectionId,
+			config: Option<BrandRewardConfig<AssetIdOf<T>>>,
+		},
+
+		/// The brand pool is funded.
+		BrandRewardsFunded {
+			who: T::AccountId,
+			brand_id: T::CollectionId,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		},
+
+		/// The brand owner withdraws from the brand pool.
+		BrandRewardsWithdrawn {
+			who: T::AccountId,
+			brand_id: T::CollectionId,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		},
+
+		/// The training rewards are paid from the brand pool.
+		BrandRewardsPaid {
+			owner: T::AccountId,
+			brand_id: T::CollectionId,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		},
+
+		/// The brand pool can not cover the training rewards.
+		BrandRewardPoolExhausted { brand_id: T::CollectionId },
+
+		/// The plausibility rules are updated.
+		PlausibilityRulesUpdated { rules: Option<PlausibilityRules> },
+
+		/// The device is quarantined for the implausible reports.
+		DeviceQuarantined { device_key: DeviceKey, score: u32 },
+
+		/// The training rewards are held until the quarantine is cleared.
+		TrainingRewardsHeld { owner: T::AccountId, device_key: DeviceKey, amount: BalanceOf<T> },
+
+		/// The quarantine of the device is cleared.
+		DeviceQuarantineCleared {
+			operator: Option<T::AccountId>,
+			device_key: DeviceKey,
+			released: bool,
+		},
+
+		/// The training rewards are held during the challenge window.
+		TrainingRewardsPending {
+			owner: T::AccountId,
+			device_key: DeviceKey,
+			timestamp: u32,
+			amount: BalanceOf<T>,
+			deadline: T::BlockNumber,
+		},
+
+		/// The training report is challenged by the auditor.
+		ReportChallenged { challenger: T::AccountId, device_key: DeviceKey, timestamp: u32 },
+
+		/// The challenge of the training report is resolved.
+		ChallengeResolved {
+			auditor: T::AccountId,
+			device_key: DeviceKey,
+			timestamp: u32,
+			valid: bool,
+		},
+
+		/// The rewards of the training report are claimed after the challenge window.
+		ReportRewardsClaimed {
+			owner: T::AccountId,
+			device_key: DeviceKey,
+			timestamp: u32,
+			amount: BalanceOf<T>,
+		},
+
+		/// The leaderboard is set.
+		LeaderboardSet {
+			key: LeaderboardKey<T::CollectionId>,
+			asset_id: AssetIdOf<T>,
+			period: T::BlockNumber,
+		},
+
+		/// The leaderboard is removed.
+		LeaderboardRemoved { key: LeaderboardKey<T::CollectionId> },
+
+		/// The prize pool of the leaderboard is funded.
+		LeaderboardFunded {
+			who: T::AccountId,
+			key: LeaderboardKey<T::CollectionId>,
+			amount: BalanceOf<T>,
+		},
+
+		/// The prize is paid to the top rank of the leaderboard.
+		LeaderboardPrizePaid {
+			key: LeaderboardKey<T::CollectionId>,
+			period: u32,
+			rank: u32,
+			who: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+
+		/// The prize of the top rank of the leaderboard is failed to pay, and is kept in the
+		/// prize pool.
+		LeaderboardPrizeFailed {
+			key: LeaderboardKey<T::CollectionId>,
+			period: u32,
+			rank: u32,
+			who: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+
+		/// The period of the leaderboard is settled.
+		LeaderboardSettled { key: LeaderboardKey<T::CollectionId>, period: u32 },
+
+		/// The brand challenge is created.
+		BrandChallengeCreated {
+			challenge_id: u32,
+			brand_id: T::CollectionId,
+			terms: ChallengeTerms<AssetIdOf<T>, BalanceOf<T>, T::BlockNumber>,
+			prize_pool: BalanceOf<T>,
+		},
+
+		/// The user joins the brand challenge with VFE.
+		BrandChallengeJoined { challenge_id: u32, who: T::AccountId, item_id: T::ItemId },
+
+		/// The participant reaches the target of the brand challenge.
+		BrandChallengeCompleted { challenge_id: u32, who: T::AccountId },
+
+		/// The brand challenge is settled.
+		BrandChallengeSettled { challenge_id: u32, completed: u32, prize: BalanceOf<T> },
+
+		/// The participant claims the prize of the brand challenge.
+		BrandChallengePrizeClaimed { challenge_id: u32, who: T::AccountId, amount: BalanceOf<T> },
+
+		/// The team is created.
+		TeamCreated { team_id: u32, captain: T::AccountId },
+
+		/// The captain invites the user to join the team.
+		TeamMemberInvited { team_id: u32, who: T::AccountId },
+
+		/// The user joins the team.
+		TeamJoined { team_id: u32, who: T::AccountId },
+
+		/// The member leaves or is removed from the team.
+		TeamLeft { team_id: u32, who: T::AccountId },
+
+		/// The captain of the team is changed.
+		TeamCaptainChanged { team_id: u32, captain: T::AccountId },
+
+		/// The team is disbanded.
+		TeamDisbanded { team_id: u32 },
+
+		/// The team reaches the goal of the period.
+		TeamGoalReached { team_id: u32, period: u32 },
+
+		/// The referrer of the user is set.
+		ReferrerSet { who: T::AccountId, referrer: T::AccountId },
+
+		/// The referral rewards are paid to the referrer.
+		ReferralRewardsPaid {
+			referrer: T::AccountId,
+			referee: T::AccountId,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		},
+
+		/// The VFE is listed for lending.
+		VFEListedForLending {
+			owner: T::AccountId,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			duration: T::BlockNumber,
+			owner_share: Permill,
+		},
+
+		/// The VFE is borrowed.
+		VFEBorrowed {
+			borrower: T::AccountId,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			expiry: T::BlockNumber,
+		},
+
+		/// The loan of the VFE is ended, and the control returns to the owner.
+		VFELoanEnded { owner: T::AccountId, brand_id: T::CollectionId, item_id: T::ItemId },
+
+		/// The share of the training rewards of the borrowed VFE is paid to the owner.
+		VFELoanRewardsPaid {
+			owner: T::AccountId,
+			borrower: T::AccountId,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			amount: BalanceOf<T>,
+		},
+
+		/// The VFE is staked to boost the daily caps of the owner.
+		VFEStaked {
+			owner: T::AccountId,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			boost: StakingBoost<BalanceOf<T>>,
+		},
+
+		/// The VFE is unstaked, and can be withdrawn after the unbonding period.
+		VFEUnstaked {
+			owner: T::AccountId,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			unbonding_until: T::BlockNumber,
+		},
+
+		/// The unstaked VFE is withdrawn.
+		VFEWithdrawn { owner: T::AccountId, brand_id: T::CollectionId, item_id: T::ItemId },
+
+		/// The economic parameters are updated.
+		EconomicParametersUpdated { params: EconomicParameters<BalanceOf<T>> },
 	}
 
 	// Errors inform users that something went wrong.
@@ -457,6 +1319,130 @@ pub mod pallet {
 		TrainingReportTimeExpired,
 		/// Training report out of normal range
 		TrainingReportOutOfNormalRange,
+		/// the version of training report is not supported
+		UnsupportedReportVersion,
+		/// the sport type of training report is different from the device
+		ReportSportTypeMismatch,
+		/// sport type is not registered
+		SportTypeNotFound,
+		/// sport type is registered
+		SportTypeExisted,
+		/// Device is not activated
+		DeviceNotActivated,
+		/// The certificate public key of producer is not set
+		ProducerCertKeyNotSet,
+		/// The device certificate is invalid
+		DeviceCertificateInvalid,
+		/// The device batch is existed
+		DeviceBatchExisted,
+		/// The device batch is not found
+		DeviceBatchNotFound,
+		/// All devices of the batch are claimed
+		DeviceBatchExhausted,
+		/// The Merkle proof of the device is invalid
+		MerkleProofInvalid,
+		/// The VFE or device can not be bound again during the cooldown
+		BindCooldownNotExpired,
+		/// VFE is not upgrading
+		VFENotUpgrading,
+		/// The sport types of VFEs are different
+		VFESportTypeMismatch,
+		/// VFE can not breed again during the cooldown
+		BreedCooldownNotExpired,
+		/// VFE has reached the maximum number of breeding times
+		BreedCountExceeded,
+		/// VFE is worn out and must be repaired
+		VFEWornOut,
+		/// VFE is not worn
+		VFENotWorn,
+		/// Gem is not found
+		GemNotFound,
+		/// The permanent gem can not be removed
+		GemPermanent,
+		/// There is no unlocked socket available
+		NoSocketAvailable,
+		/// Gem is not socketed into the VFE
+		GemNotSocketed,
+		/// The reward settings of VFE brand are not set
+		BrandRewardConfigNotSet,
+		/// The brand pool should be withdrawn before changing the reward asset
+		BrandRewardPoolNotEmpty,
+		/// Device is not quarantined
+		DeviceNotQuarantined,
+		/// The pending training report is not found
+		ReportNotFound,
+		/// The challenge window of the report has expired
+		ChallengeWindowExpired,
+		/// The report is still in the challenge window
+		ChallengeWindowNotExpired,
+		/// The report has been challenged
+		ReportChallenged,
+		/// The report is not challenged
+		ReportNotChallenged,
+		/// The leaderboard is not found
+		LeaderboardNotFound,
+		/// The prize pool of the leaderboard is not empty
+		LeaderboardPoolNotEmpty,
+		/// The period of the leaderboard is not ended
+		LeaderboardPeriodNotEnded,
+		/// The brand challenge is not found
+		BrandChallengeNotFound,
+		/// The brand challenge has ended
+		BrandChallengeEnded,
+		/// The brand challenge is not ended
+		BrandChallengeNotEnded,
+		/// The brand challenge has been settled
+		BrandChallengeSettled,
+		/// The user has joined the brand challenge
+		BrandChallengeJoined,
+		/// The brand has too many unsettled challenges
+		TooManyBrandChallenges,
+		/// The user did not complete the brand challenge
+		BrandChallengeNotCompleted,
+		/// The prize of the brand challenge has been claimed
+		BrandChallengePrizeClaimed,
+		/// The team is not found
+		TeamNotFound,
+		/// The user is already in a team
+		AlreadyInTeam,
+		/// The user is not in the team
+		NotInTeam,
+		/// The team is full
+		TeamFull,
+		/// The user is not invited by the team
+		TeamInvitationNotFound,
+		/// The captain can not leave the team with other members
+		CaptainCannotLeave,
+		/// The referrer is invalid
+		ReferrerInvalid,
+		/// The referrer has been set
+		ReferrerAlreadySet,
+		/// The referrer can only be set before the user is created
+		UserAlreadyCreated,
+		/// The VFE is listed for lending or lent
+		VFELent,
+		/// The VFE is not listed for lending
+		VFENotListed,
+		/// The loan of the VFE has expired
+		VFELoanExpired,
+		/// The loan of the VFE has not expired
+		VFELoanNotExpired,
+		/// The VFE is staked or unbonding
+		VFEStaked,
+		/// The VFE is not staked
+		VFENotStaked,
+		/// The VFE is unbonding
+		VFEUnbonding,
+		/// The unbonding period of the VFE has not expired
+		UnbondingNotExpired,
+		/// The device is quarantined
+		DeviceQuarantined,
+		/// The standings of the leaderboard period are not found or have been settled
+		LeaderboardStandingsNotFound,
+		/// The brand has no fusion allowance left
+		FusionAllowanceExhausted,
+		/// The VFE has socketed gems
+		VFEGemSocketed,
 	}
 
 	#[pallet::hooks]
@@ -479,6 +1465,10 @@ pub mod pallet {
 
 			weight
 		}
+
+		fn on_runtime_upgrade() -> Weight {
+			migrations::migrate_to_v1::<T>()
+		}
 	}
 
 	#[pallet::call]
@@ -541,6 +1531,32 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// set the certificate public key of producer, the devices with certificates signed by it
+		/// can be registered lazily at binding time.
+		/// - origin AccountId, the producer owner
+		/// - producer_id ProducerId
+		/// - cert_key Option<DeviceKey>, `None` to remove the certificate public key
+		#[pallet::weight(10_000)]
+		pub fn set_producer_cert_key(
+			origin: OriginFor<T>,
+			producer_id: T::ObjectId,
+			cert_key: Option<DeviceKey>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::check_producer(who, producer_id)?;
+			match cert_key {
+				Some(key) => {
+					VerifyingKey::from_sec1_bytes(key.as_ref())
+						.map_err(|_| Error::<T>::PublicKeyEncodeError)?;
+					ProducerCertKeys::<T>::insert(producer_id, key);
+				},
+				None => ProducerCertKeys::<T>::remove(producer_id),
+			}
+
+			Self::deposit_event(Event::ProducerCertKeySet { producer_id, cert_key });
+			Ok(())
+		}
+
 		/// create a VFE brand
 		/// - origin AccountId
 		/// - class_id CollectionId
@@ -551,11 +1567,12 @@ pub mod pallet {
 		pub fn create_vfe_brand(
 			origin: OriginFor<T>,
 			meta_data: BoundedVec<u8, T::StringLimit>,
-			sport_type: SportType,
+			sport_type: SportId,
 			rarity: VFERarity,
 		) -> DispatchResult {
 			// Get identity role of origin
 			let who = T::BrandOrigin::ensure_origin(origin.clone())?;
+			ensure!(Self::sport_type_info(sport_type).is_some(), Error::<T>::SportTypeNotFound);
 			let brand_id = T::UniqueId::generate_object_id(T::VFEBrandId::get())?;
 			// let meta_data = meta_data.unwrap_or(Default::default());
 
@@ -625,72 +1642,124 @@ pub mod pallet {
 			brand_id: T::CollectionId,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
-			ensure!(!Devices::<T>::contains_key(puk), Error::<T>::DeviceExisted);
-			let producer = Self::check_producer(who.clone(), producer_id)?;
-			let vfe_brand = VFEBrands::<T>::get(brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
-
-			// Check if the collection is authorized to the producer
-			VFEApprovals::<T>::try_mutate(
-				&vfe_brand.brand_id,
-				&producer_id,
-				|maybe_approved| -> DispatchResult {
-					let mut approved = maybe_approved.take().ok_or(Error::<T>::NoneValue)?;
-
-					// remaining_mint--
-					let remaining = approved
-						.remaining_mint
-						.checked_sub(One::one())
-						.ok_or(Error::<T>::ValueOverflow)?;
-
-					// mint_cost handle transfer
-					if let Some((mint_asset_id, mint_price)) = approved.mint_cost {
-						// transfer tokens to NFT brand_id owner
-						<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
-							mint_asset_id,
-							&who,
-							&Self::into_account_id(producer_id),
-							mint_price,
-							true,
-						)?;
-						approved.locked_of_mint = approved
-							.locked_of_mint
-							.checked_add(&mint_price)
-							.ok_or(Error::<T>::ValueOverflow)?;
-					}
+			Self::do_register_device(&who, puk, producer_id, brand_id)
+		}
 
-					approved.registered = approved
-						.registered
-						.checked_add(One::one())
-						.ok_or(Error::<T>::ValueOverflow)?;
-					approved.remaining_mint = remaining;
+		/// commit a batch of devices by the Merkle root of the device keys, the `count` is
+		/// reserved from the `remaining_mint` and the `mint_cost` is locked up front.
+		/// - origin AccountId, the producer owner
+		/// - producer_id ProducerId
+		/// - brand_id CollectionId
+		/// - root Hash, the Merkle root of `Hashing(puk)` leaves with sorted pair hashing
+		/// - count u32
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn commit_device_batch(
+			origin: OriginFor<T>,
+			producer_id: T::ObjectId,
+			brand_id: T::CollectionId,
+			root: T::Hash,
+			#[pallet::compact] count: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::check_producer(who.clone(), producer_id)?;
+			ensure!(count > 0, Error::<T>::ValueInvalid);
+			ensure!(
+				!DeviceBatches::<T>::contains_key(&brand_id, &root),
+				Error::<T>::DeviceBatchExisted
+			);
 
-					Devices::<T>::insert(
-						puk,
-						Device {
-							brand_id,
-							item_id: None,
-							producer_id: producer.id,
-							status: DeviceStatus::Registered,
-							pk: puk,
-							nonce: 0u32,
-							sport_type: vfe_brand.sport_type,
-							timestamp: 0u32,
-							mint_cost: approved.mint_cost,
-						},
-					);
+			let mut approved =
+				VFEApprovals::<T>::get(&brand_id, &producer_id).ok_or(Error::<T>::NoneValue)?;
+			approved.remaining_mint =
+				approved.remaining_mint.checked_sub(count).ok_or(Error::<T>::ValueOverflow)?;
+			if let Some((mint_asset_id, mint_price)) = approved.mint_cost {
+				let total_cost = mint_price.saturating_mul(count.into());
+				// lock the minting fee of all devices up front
+				<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+					mint_asset_id,
+					&who,
+					&Self::into_account_id(producer_id),
+					total_cost,
+					true,
+				)?;
+				approved.locked_of_mint = approved
+					.locked_of_mint
+					.checked_add(&total_cost)
+					.ok_or(Error::<T>::ValueOverflow)?;
+			}
+			approved.registered =
+				approved.registered.checked_add(count).ok_or(Error::<T>::ValueOverflow)?;
 
-					*maybe_approved = Some(approved);
+			DeviceBatches::<T>::insert(
+				&brand_id,
+				&root,
+				DeviceBatch { producer_id, count, claimed: 0, mint_cost: approved.mint_cost },
+			);
+			VFEApprovals::<T>::insert(&brand_id, &producer_id, approved);
 
-					Self::deposit_event(Event::DeviceRegistered {
-						operator: who,
-						producer_id,
-						device_key: puk,
-						brand_id,
-					});
+			Self::deposit_event(Event::DeviceBatchCommitted {
+				operator: who,
+				producer_id,
+				brand_id,
+				root,
+				count,
+			});
+			Ok(())
+		}
 
-					Ok(())
-				},
-			)
+		/// close the device batch, the unclaimed devices are refunded to the producer owner and
+		/// released to the approval.
+		/// - origin AccountId, the producer owner
+		/// - brand_id CollectionId
+		/// - root Hash
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn close_device_batch(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			root: T::Hash,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let batch =
+				DeviceBatches::<T>::get(&brand_id, &root).ok_or(Error::<T>::DeviceBatchNotFound)?;
+			Self::check_producer(who.clone(), batch.producer_id)?;
+
+			let unclaimed = batch.count.saturating_sub(batch.claimed);
+			let mut approved = VFEApprovals::<T>::get(&brand_id, &batch.producer_id)
+				.ok_or(Error::<T>::NoneValue)?;
+			if let Some((mint_asset_id, mint_price)) = batch.mint_cost {
+				let refund = mint_price.saturating_mul(unclaimed.into());
+				<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+					mint_asset_id,
+					&Self::into_account_id(batch.producer_id),
+					&who,
+					refund,
+					false,
+				)?;
+				approved.locked_of_mint = approved
+					.locked_of_mint
+					.checked_sub(&refund)
+					.ok_or(Error::<T>::ValueOverflow)?;
+			}
+			approved.registered =
+				approved.registered.checked_sub(unclaimed).ok_or(Error::<T>::ValueOverflow)?;
+			approved.remaining_mint = approved
+				.remaining_mint
+				.checked_add(unclaimed)
+				.ok_or(Error::<T>::ValueOverflow)?;
+
+			VFEApprovals::<T>::insert(&brand_id, &batch.producer_id, approved);
+			DeviceBatches::<T>::remove(&brand_id, &root);
+
+			Self::deposit_event(Event::DeviceBatchClosed {
+				operator: who,
+				producer_id: batch.producer_id,
+				brand_id,
+				root,
+				unclaimed,
+			});
+			Ok(())
 		}
 
 		/// deregister_device
@@ -707,30 +1776,8 @@ pub mod pallet {
 				ensure!(device.status == DeviceStatus::Registered, Error::<T>::DeviceBond);
 				//check device producer
 				Self::check_producer(who.clone(), device.producer_id)?;
-				// get approval
-				let mut approved = VFEApprovals::<T>::get(&device.brand_id, &device.producer_id)
-					.ok_or(Error::<T>::NoneValue)?;
-				if let Some((mint_asset_id, mint_price)) = device.mint_cost {
-					// transfer tokens to NFT class owner
-					<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
-						mint_asset_id,
-						&Self::into_account_id(device.producer_id),
-						&who,
-						mint_price,
-						false,
-					)?;
-					approved.locked_of_mint = approved
-						.locked_of_mint
-						.checked_sub(&mint_price)
-						.ok_or(Error::<T>::ValueOverflow)?;
-				}
-				approved.registered =
-					approved.registered.checked_sub(One::one()).ok_or(Error::<T>::ValueOverflow)?;
-				approved.remaining_mint = approved
-					.remaining_mint
-					.checked_add(One::one())
-					.ok_or(Error::<T>::ValueOverflow)?;
-				VFEApprovals::<T>::insert(&device.brand_id, &device.producer_id, approved);
+				// refund the minting fee and release the approval
+				Self::release_registered_device(&device, &who)?;
 				//remove device from store
 				*maybe_device = None;
 				//emit event
@@ -752,79 +1799,101 @@ pub mod pallet {
 		) -> DispatchResult {
 			ensure_none(origin)?;
 			//  bind device signature
-			let mut device = Self::get_verified_device(from.clone(), puk, signature, nonce)?;
-			ensure!(device.item_id.is_none(), Error::<T>::DeviceBond);
-			// create the user if it is new
-			Self::find_user(&from);
-
-			//If it is a registered device, it will mint a new vfe for the user.
-			let new_vfe = if device.status == DeviceStatus::Registered {
-				let vfe = Self::create_vfe(&device.brand_id, &device.producer_id, &from)?;
-				// save new vfe detail
-				VFEDetails::<T>::insert(&vfe.brand_id, &vfe.item_id, vfe);
-				Self::deposit_event(Event::VFECreated { owner: from.clone(), detail: vfe });
-				Some(vfe)
-			} else {
-				None
-			};
-
-			// If the user passes itemId, bind this itemId, if not, bind a new vfe.
-			let vfe = match bind_item {
-				Some(item_id) => {
-					//check if item_id is belong to origin
-					let mut vfe = VFEDetails::<T>::get(&device.brand_id, &item_id)
-						.ok_or(Error::<T>::VFENotExist)?;
-					ensure!(vfe.device_key.is_none(), Error::<T>::VFEBond);
-					let owner = Self::owner(&device.brand_id, &item_id)
-						.ok_or(Error::<T>::OperationIsNotAllowed)?;
-					ensure!(owner == from, Error::<T>::OperationIsNotAllowed);
-					vfe.device_key = Some(puk);
-					vfe
-				},
-				None => {
-					//check if device status is register, we can get a new vfe to bind.
-					let mut vfe = new_vfe.ok_or(Error::<T>::DeviceBond)?;
-					vfe.device_key = Some(puk);
-
-					vfe
-				},
-			};
-
-			// save vfe detail after bond
-			VFEDetails::<T>::insert(&vfe.brand_id, &vfe.item_id, vfe);
-
-			device.nonce = nonce;
-			device.item_id = Some(vfe.item_id);
-			device.status = DeviceStatus::Activated;
-			// save device
-			Devices::<T>::insert(puk, device);
-			Self::deposit_event(Event::DeviceBound {
-				owner: from,
-				device_key: puk,
-				brand_id: device.brand_id,
-				item_id: vfe.item_id,
-			});
-
-			Ok(())
+			let device = Self::get_verified_device(from.clone(), puk, signature, nonce)?;
+			Self::do_bind_device(from, puk, device, nonce, bind_item)
 		}
 
-		/// unbind the device
+		/// bind the device with the certificate signed by the producer, if the device is not
+		/// registered, it will be registered lazily and the minting fee is paid by the producer
+		/// owner.
+		/// - from AccountId
+		/// - puk DeviceKey
+		/// - signature BoundedVec<u8, T::StringLimit>
+		/// - nonce u32
+		/// - certificate DeviceCertificate, the producer, the vfe brand and the signature of the
+		///   producer certificate key over `(puk, brand_id).encode()`
+		/// - bind_item Option<ItemId>
 		#[pallet::weight(10_000)]
 		#[transactional]
-		pub fn unbind_device(
+		pub fn bind_certified_device(
 			origin: OriginFor<T>,
-			brand_id: T::CollectionId,
-			item_id: T::ItemId,
+			from: T::AccountId,
+			puk: DeviceKey,
+			signature: BoundedVec<u8, T::StringLimit>,
+			nonce: u32,
+			certificate: DeviceCertificate<
+				T::ObjectId,
+				T::CollectionId,
+				BoundedVec<u8, T::StringLimit>,
+			>,
+			bind_item: Option<T::ItemId>,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			if !Devices::<T>::contains_key(puk) {
+				Self::check_device_certificate(puk, &certificate)?;
+				let producer = Producers::<T>::get(certificate.producer_id)
+					.ok_or(Error::<T>::ProducerNotExist)?;
+				Self::do_register_device(&producer.owner, puk, producer.id, certificate.brand_id)?;
+			}
+			//  bind device signature
+			let device = Self::get_verified_device(from.clone(), puk, signature, nonce)?;
+			Self::do_bind_device(from, puk, device, nonce, bind_item)
+		}
+
+		/// bind the device of the committed batch with the Merkle proof, if the device is not
+		/// claimed, it will be registered with the minting fee locked by the batch.
+		/// - from AccountId
+		/// - puk DeviceKey
+		/// - signature BoundedVec<u8, T::StringLimit>
+		/// - nonce u32
+		/// - batch_proof DeviceBatchProof, the vfe brand, the Merkle root and the proof of the
+		///   device
+		/// - bind_item Option<ItemId>
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn bind_batch_device(
+			origin: OriginFor<T>,
+			from: T::AccountId,
+			puk: DeviceKey,
+			signature: BoundedVec<u8, T::StringLimit>,
+			nonce: u32,
+			batch_proof: DeviceBatchProof<
+				T::CollectionId,
+				T::Hash,
+				BoundedVec<T::Hash, T::MaxMerkleProofLength>,
+			>,
+			bind_item: Option<T::ItemId>,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			if !Devices::<T>::contains_key(puk) {
+				Self::claim_batch_device(puk, &batch_proof)?;
+			}
+			//  bind device signature
+			let device = Self::get_verified_device(from.clone(), puk, signature, nonce)?;
+			Self::do_bind_device(from, puk, device, nonce, bind_item)
+		}
+
+		/// unbind the device
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn unbind_device(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
 		) -> DispatchResult {
 			let who = ensure_signed(origin.clone())?;
 			let mut vfe =
 				VFEDetails::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
 			let device_pk = vfe.device_key.ok_or(Error::<T>::VFENotBond)?;
 			let mut device = Devices::<T>::get(device_pk).ok_or(Error::<T>::DeviceNotExisted)?;
-			// check vfe owner
-			let vfe_owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
-			ensure!(vfe_owner == who, Error::<T>::OperationIsNotAllowed);
-			//todo: pay fee to unbind device
+			// check vfe owner, or the borrower of the lent vfe
+			let controller = Self::vfe_controller(&brand_id, &item_id)?;
+			ensure!(controller == who, Error::<T>::OperationIsNotAllowed);
+
+			// pay fee to unbind device
+			let settings = Self::unbind_settings();
+			Self::pay_unbind_fee(&who, &brand_id, &settings)?;
+			Self::set_bind_cooldown(&brand_id, &item_id, device_pk, settings.cooldown);
 
 			device.item_id = None;
 			vfe.device_key = None;
@@ -842,11 +1911,168 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// void a compromised or counterfeit device, the VFE bound to it will be unbound, and the
+		/// locked minting fee of a registered device will be refunded to the producer owner.
+		/// - origin AccountId or Root, the producer owner or the VFE brand owner
+		/// - puk DeviceKey
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn void_device(origin: OriginFor<T>, puk: DeviceKey) -> DispatchResult {
+			let operator = match ensure_signed(origin.clone()) {
+				Ok(who) => Some(who),
+				Err(_) => {
+					ensure_root(origin)?;
+					None
+				},
+			};
+			let mut device = Devices::<T>::get(puk).ok_or(Error::<T>::DeviceNotExisted)?;
+			ensure!(device.status != DeviceStatus::Voided, Error::<T>::DeviceVoided);
+			let producer =
+				Producers::<T>::get(device.producer_id).ok_or(Error::<T>::ProducerNotExist)?;
+
+			// only the producer owner, the vfe brand owner or root can void the device
+			if let Some(who) = &operator {
+				let is_brand_owner = Self::collection_owner(&device.brand_id).as_ref() == Some(who);
+				ensure!(
+					&producer.owner == who || is_brand_owner,
+					Error::<T>::OperationIsNotAllowed
+				);
+			}
+
+			// the registered device has not minted vfe, release the approval
+			let refund = if device.status == DeviceStatus::Registered {
+				Self::release_registered_device(&device, &producer.owner)?;
+				device.mint_cost.take()
+			} else {
+				None
+			};
+
+			// unbind the vfe, so that the device can not earn the rewards any more
+			let item_id = device.item_id.take();
+			if let Some(item_id) = item_id {
+				VFEDetails::<T>::try_mutate(&device.brand_id, &item_id, |maybe_vfe| {
+					let vfe = maybe_vfe.as_mut().ok_or(Error::<T>::VFENotExist)?;
+					vfe.device_key = None;
+					Ok::<(), DispatchError>(())
+				})?;
+				let vfe_owner =
+					Self::owner(&device.brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+				Self::deposit_event(Event::DeviceUnbound {
+					owner: vfe_owner,
+					device_key: puk,
+					brand_id: device.brand_id,
+					item_id,
+				});
+			}
+
+			// the unpaid rewards of the device are discarded and never minted
+			for (timestamp, report) in PendingReports::<T>::drain_prefix(puk) {
+				Self::revert_report_rewards(
+					puk,
+					timestamp,
+					&report.owner,
+					report.amount,
+					report.reported_at,
+				);
+			}
+			for (timestamp, held) in HeldRewards::<T>::drain_prefix(puk) {
+				Self::revert_report_rewards(
+					puk,
+					timestamp,
+					&held.owner,
+					held.amount,
+					held.reported_at,
+				);
+			}
+
+			device.status = DeviceStatus::Voided;
+			Devices::<T>::insert(puk, device);
+			Self::deposit_event(Event::DeviceVoided {
+				operator,
+				device_key: puk,
+				brand_id: device.brand_id,
+				item_id,
+				refund,
+			});
+			Ok(())
+		}
+
+		/// replace an activated device by a new key, the bound VFE and the nonce, timestamp of the
+		/// device are migrated to the new key, and the old key is voided.
+		/// - origin AccountId, the producer owner, or the VFE owner with the signature of the old
+		///   device over the new key
+		/// - old_key DeviceKey
+		/// - new_key DeviceKey
+		/// - signature Option<BoundedVec<u8, T::StringLimit>>
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn replace_device(
+			origin: OriginFor<T>,
+			old_key: DeviceKey,
+			new_key: DeviceKey,
+			signature: Option<BoundedVec<u8, T::StringLimit>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Devices::<T>::contains_key(new_key), Error::<T>::DeviceExisted);
+			VerifyingKey::from_sec1_bytes(new_key.as_ref())
+				.map_err(|_| Error::<T>::PublicKeyEncodeError)?;
+			let mut old_device = Devices::<T>::get(old_key).ok_or(Error::<T>::DeviceNotExisted)?;
+			ensure!(old_device.status != DeviceStatus::Voided, Error::<T>::DeviceVoided);
+			ensure!(old_device.status == DeviceStatus::Activated, Error::<T>::DeviceNotActivated);
+			// the quarantined device can not escape by the new key
+			ensure!(!QuarantinedDevices::<T>::contains_key(old_key), Error::<T>::DeviceQuarantined);
+
+			// the producer owner can replace the device directly, and the vfe owner must be
+			// authorized by the old device
+			let is_producer_owner =
+				Self::check_producer(who.clone(), old_device.producer_id).is_ok();
+			if !is_producer_owner {
+				let item_id = old_device.item_id.ok_or(Error::<T>::OperationIsNotAllowed)?;
+				let vfe_owner =
+					Self::owner(&old_device.brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+				ensure!(vfe_owner == who, Error::<T>::OperationIsNotAllowed);
+				let signature = signature.ok_or(Error::<T>::DeviceSignatureInvalid)?;
+				let flag =
+					Self::verify_device_signature(old_key, new_key.as_ref(), &signature[..])?;
+				ensure!(flag, Error::<T>::DeviceSignatureInvalid);
+			}
+
+			// migrate the bound vfe to the new key
+			if let Some(item_id) = old_device.item_id {
+				VFEDetails::<T>::try_mutate(&old_device.brand_id, &item_id, |maybe_vfe| {
+					let vfe = maybe_vfe.as_mut().ok_or(Error::<T>::VFENotExist)?;
+					vfe.device_key = Some(new_key);
+					Ok::<(), DispatchError>(())
+				})?;
+			}
+
+			// the nonce and timestamp are carried over, so that the old reports can not be replayed
+			let new_device = Device { pk: new_key, ..old_device };
+			Devices::<T>::insert(new_key, new_device);
+			// the suspicion score and the daily reports are carried over as well
+			DeviceSuspicion::<T>::insert(new_key, DeviceSuspicion::<T>::take(old_key));
+			DeviceDailyReports::<T>::insert(new_key, DeviceDailyReports::<T>::take(old_key));
+
+			let item_id = old_device.item_id.take();
+			old_device.status = DeviceStatus::Voided;
+			Devices::<T>::insert(old_key, old_device);
+
+			Self::deposit_event(Event::DeviceReplaced {
+				operator: who,
+				old_device_key: old_key,
+				new_device_key: new_key,
+				brand_id: old_device.brand_id,
+				item_id,
+			});
+			Ok(())
+		}
+
 		/// upload training report to the chain
 		///  - origin AccountId
 		/// - puk BoundedVec<u8, T::StringLimit>
 		/// - req_sig BoundedVec<u8, T::StringLimit>
-		/// - msg AccountId BoundedVec<u8, T::StringLimit>
+		/// - msg AccountId BoundedVec<u8, T::StringLimit>, a `TrainingReportEnvelope` or a legacy
+		///   raw report of the device's sport type
 		#[pallet::weight(10_000)]
 		#[transactional]
 		pub fn upload_training_report(
@@ -858,13 +2084,19 @@ pub mod pallet {
 			ensure_none(origin.clone())?;
 			let mut device =
 				Self::check_device_training_report(device_pk, report_sig, report_data.clone())?;
+			// the expired loan is ended on the next report, and the report is not rewarded
+			if let Some(item_id) = device.item_id {
+				if Self::try_end_expired_loan(&device.brand_id, &item_id)? {
+					return Ok(())
+				}
+			}
 
 			// decode the msg and earn the award
 			Self::handler_report_data(&mut device, report_data)?;
 			Ok(())
 		}
 
-		/// restore power
+		/// restore power, by the owner or the borrower of the lent VFE
 		/// - origin AccountId
 		/// - brand_id CollectionId
 		/// - item ItemId
@@ -881,8 +2113,13 @@ pub mod pallet {
 
 			let owner = Self::owner(&brand_id, &item).ok_or(Error::<T>::ItemNotFound)?;
 
-			ensure!(who == owner, Error::<T>::OperationIsNotAllowed);
+			ensure!(
+				who == owner || Self::vfe_controller(&brand_id, &item).ok() == Some(who.clone()),
+				Error::<T>::OperationIsNotAllowed
+			);
 
+			// settle the finished upgrade first
+			Self::try_complete_level_up(&brand_id, &item)?;
 			let mut vfe = VFEDetails::<T>::get(brand_id, item).ok_or(Error::<T>::VFENotExist)?;
 
 			ensure!(!vfe.is_upgrading, Error::<T>::VFEUpgrading);
@@ -894,7 +2131,7 @@ pub mod pallet {
 			// try to burn the charge
 			let incentive_token =
 				IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?;
-			T::Currencies::burn_from(incentive_token, &owner, total_charge_cost)?;
+			T::Currencies::burn_from(incentive_token, &who, total_charge_cost)?;
 
 			vfe.remaining_battery += charge_num;
 
@@ -902,7 +2139,7 @@ pub mod pallet {
 			VFEDetails::<T>::insert(brand_id, item, vfe);
 
 			Self::deposit_event(Event::PowerRestored {
-				owner,
+				owner: who,
 				charge_amount: charge_num,
 				use_amount: total_charge_cost,
 				brand_id,
@@ -933,437 +2170,3039 @@ pub mod pallet {
 			brand_id: T::CollectionId,
 			item_id: T::ItemId,
 		) -> DispatchResult {
-			// cost fee to level up vfe
-			VFEDetails::<T>::try_mutate(&brand_id, &item_id, |maybe_vfe| -> DispatchResult {
-				let who = ensure_signed(origin.clone())?;
-				let mut vfe = maybe_vfe.take().ok_or(Error::<T>::VFENotExist)?;
-				let vfe_owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
-				ensure!(vfe_owner == who, Error::<T>::OperationIsNotAllowed);
-				let mut user = Self::find_user(&who);
-
-				// Calculating level up fees for VFE
-				let level_cost = Self::calculate_level_up_costs(&vfe, &user);
+			let who = ensure_signed(origin)?;
+			// settle the finished upgrade first
+			Self::try_complete_level_up(&brand_id, &item_id)?;
+			let mut vfe =
+				VFEDetails::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+			let vfe_owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+			ensure!(vfe_owner == who, Error::<T>::OperationIsNotAllowed);
+			// the lent VFE can not be upgraded until the loan is ended
+			ensure!(!VFELoans::<T>::contains_key(&brand_id, &item_id), Error::<T>::VFELent);
+			// the boost of the staked VFE is fixed by its level when staking
+			ensure!(!StakedVFEs::<T>::contains_key(&brand_id, &item_id), Error::<T>::VFEStaked);
+			ensure!(!vfe.is_upgrading, Error::<T>::VFEUpgrading);
+			let user = Self::find_user(&who);
 
-				// level up should burn token
-				let incentive_token =
-					IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?;
-				T::Currencies::burn_from(incentive_token, &who, level_cost)?;
+			// Calculating level up fees for VFE
+			let level_cost = Self::calculate_level_up_costs(&vfe, &user);
 
-				vfe.level += 1;
-				vfe.available_points += vfe.rarity.growth_points();
-				*maybe_vfe = Some(vfe);
+			// level up should burn token
+			let incentive_token =
+				IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?;
+			T::Currencies::burn_from(incentive_token, &who, level_cost)?;
 
-				// increase the user's energy cap and earing cap of daily
-				// check if user current energy_total is less than new energy_total
-				let new_energy_cap = Self::level_into_energy_cap(vfe.level);
-				let new_earning_cap = Self::level_into_earning_cap(vfe.level);
-				if new_energy_cap > user.energy_total {
-					user.energy_total = new_energy_cap;
-				}
-				if new_earning_cap > user.earning_cap {
-					user.earning_cap = new_earning_cap;
-				}
-				Users::<T>::insert(&who, user);
+			// emit event
+			let level_up = vfe.level.checked_add(1).ok_or(Error::<T>::ValueOverflow)?;
+			Self::deposit_event(Event::VFELevelUp {
+				brand_id,
+				item_id,
+				level_up,
+				cost: level_cost,
+			});
 
-				// emit event
-				Self::deposit_event(Event::VFELevelUp {
-					brand_id,
-					item_id,
-					level_up: vfe.level,
-					cost: level_cost,
-				});
+			// VFE level up requires a level-dependent duration
+			let duration = T::LevelUpDurationPerLevel::get().saturating_mul(level_up.into());
+			if duration.is_zero() {
+				return Self::do_complete_level_up(&brand_id, &item_id, vfe)
+			}
 
-				//todo: VFE level up requires a cooldown.
+			let completion = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+			vfe.is_upgrading = true;
+			VFEDetails::<T>::insert(&brand_id, &item_id, vfe);
+			UpgradingVFEs::<T>::insert(&brand_id, &item_id, completion);
+			Self::deposit_event(Event::VFEUpgradeStarted {
+				brand_id,
+				item_id,
+				level_up,
+				completion,
+			});
 
-				Ok(())
-			})
+			Ok(())
 		}
 
-		/// Increase ability
+		/// speed up the upgrading VFE by burning incentive tokens for the remaining blocks
 		/// - origin AccountId
 		/// - brand_id CollectionId
-		/// - instance ItemId
-		/// - ability VFEAbility
+		/// - item_id ItemId
 		#[pallet::weight(10_000)]
 		#[transactional]
-		pub fn increase_ability(
+		pub fn speed_up_level_up(
 			origin: OriginFor<T>,
 			brand_id: T::CollectionId,
 			item_id: T::ItemId,
-			ability: VFEAbility,
 		) -> DispatchResult {
-			VFEDetails::<T>::try_mutate(&brand_id, &item_id, |maybe_vfe| -> DispatchResult {
-				let who = ensure_signed(origin.clone())?;
-				let mut vfe = maybe_vfe.take().ok_or(Error::<T>::VFENotExist)?;
-				let vfe_owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
-				ensure!(vfe_owner == who, Error::<T>::OperationIsNotAllowed);
-				let total_ability =
-					ability.efficiency + ability.skill + ability.luck + ability.durable;
-				ensure!(total_ability <= vfe.available_points, Error::<T>::ValueInvalid);
-
-				vfe.current_ability.efficiency =
-					vfe.current_ability.efficiency.saturating_add(ability.efficiency);
-				vfe.current_ability.skill = vfe.current_ability.skill.saturating_add(ability.skill);
-				vfe.current_ability.luck = vfe.current_ability.luck.saturating_add(ability.luck);
-				vfe.current_ability.durable =
-					vfe.current_ability.durable.saturating_add(ability.durable);
-				vfe.available_points = vfe.available_points.saturating_sub(total_ability);
-
-				*maybe_vfe = Some(vfe);
-
-				// emit event
-				Self::deposit_event(Event::VFEAbilityIncreased { brand_id, item_id });
+			let who = ensure_signed(origin)?;
+			let vfe_owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+			ensure!(vfe_owner == who, Error::<T>::OperationIsNotAllowed);
+			let completion =
+				UpgradingVFEs::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotUpgrading)?;
+			let vfe = VFEDetails::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+
+			// burn the incentive tokens for the remaining blocks
+			let remaining = completion.saturating_sub(frame_system::Pallet::<T>::block_number());
+			let cost = BalanceOf::<T>::saturated_from(remaining.saturated_into::<u128>())
+				.saturating_mul(T::LevelUpSpeedUpCost::get());
+			if !cost.is_zero() {
+				let incentive_token =
+					IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?;
+				T::Currencies::burn_from(incentive_token, &who, cost)?;
+			}
+			Self::deposit_event(Event::VFEUpgradeSpedUp { brand_id, item_id, cost });
 
-				Ok(())
-			})
+			Self::do_complete_level_up(&brand_id, &item_id, vfe)
 		}
 
-		/// transfer vfe
+		/// repair the worn VFE by burning incentive tokens, the cost increases with the level
 		/// - origin AccountId
-		/// - class CollectionId
-		/// - instance ItemId
-		/// - Source AccountId
-		#[pallet::weight(< T as pallet_uniques::Config < T::UniquesInstance >>::WeightInfo::transfer())]
-		pub fn transfer(
+		/// - brand_id CollectionId
+		/// - item_id ItemId
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn repair_vfe(
 			origin: OriginFor<T>,
 			brand_id: T::CollectionId,
 			item_id: T::ItemId,
-			dest: <T::Lookup as StaticLookup>::Source,
 		) -> DispatchResult {
-			let from = ensure_signed(origin.clone())?;
-			let to = T::Lookup::lookup(dest.clone())?;
+			let who = ensure_signed(origin)?;
+			// settle the finished upgrade first
+			Self::try_complete_level_up(&brand_id, &item_id)?;
 			let vfe_owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
-			ensure!(vfe_owner == from, Error::<T>::OperationIsNotAllowed);
-			<Self as NFTTransfer<T::AccountId>>::transfer(&brand_id, &item_id, &to)
-		}
-	}
-
-	#[pallet::validate_unsigned]
-	impl<T: Config> ValidateUnsigned for Pallet<T>
-	where
-		T::CollectionId: From<T::ObjectId>,
-		T::ItemId: From<T::ObjectId>,
-		T::ObjectId: From<T::CollectionId>,
-	{
-		type Call = Call<T>;
+			ensure!(vfe_owner == who, Error::<T>::OperationIsNotAllowed);
+			let vfe = VFEDetails::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+			let wear = VFEWear::<T>::get(&brand_id, &item_id);
+			ensure!(wear != Permill::zero(), Error::<T>::VFENotWorn);
+
+			// burn the incentive tokens for each per mille of wear and each level
+			let wear_per_mille: u32 = wear.mul_ceil(1000u32);
+			let cost = T::RepairCost::get()
+				.saturating_mul(wear_per_mille.into())
+				.saturating_mul((vfe.level as u32 + 1).into());
+			let incentive_token =
+				IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?;
+			T::Currencies::burn_from(incentive_token, &who, cost)?;
 
-		/// Validate unsigned call to this module.
-		///
-		/// By default unsigned transactions are disallowed, but implementing the validator
-		/// here we make sure that some particular calls (the ones produced by offchain worker)
-		/// are being whitelisted and marked as valid.
-		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-			const UNSIGNED_TXS_PRIORITY: u64 = 100;
-			let valid_device_tx = |provide| {
-				ValidTransaction::with_tag_prefix("VFEDevice")
-					.priority(UNSIGNED_TXS_PRIORITY)
-					.and_provides([&provide])
-					.longevity(TransactionLongevity::max_value())
-					.propagate(true)
-					.build()
-			};
+			VFEWear::<T>::remove(&brand_id, &item_id);
+			Self::deposit_event(Event::VFERepaired { owner: who, brand_id, item_id, cost });
+			Ok(())
+		}
 
-			match call.to_owned() {
-				Call::bind_device { from, puk, signature, nonce, bind_item: _bind_item } => {
-					Self::get_verified_device(from, puk, signature.clone(), nonce)
-						.map_err(dispatch_error_to_invalid)?;
-					valid_device_tx((puk, signature))
-				},
-				Call::upload_training_report { device_pk, report_sig, report_data } => {
-					Self::check_device_training_report(device_pk, report_sig.clone(), report_data)
-						.map_err(dispatch_error_to_invalid)?;
-					valid_device_tx((device_pk, report_sig))
-				},
+		/// set the reward settings of VFE brand by the brand owner, the reward asset can only be
+		/// changed when the brand pool is empty
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - config Option<BrandRewardConfig>
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn set_brand_reward_config(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			config: Option<BrandRewardConfig<AssetIdOf<T>>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let brand_owner =
+				Self::collection_owner(&brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
+			ensure!(brand_owner == who, Error::<T>::OperationIsNotAllowed);
+			ensure!(config.map_or(true, |c| !c.rate.is_zero()), Error::<T>::ValueInvalid);
+
+			if let Some(old) = BrandRewardConfigs::<T>::get(&brand_id) {
+				if config.map(|c| c.asset_id) != Some(old.asset_id) {
+					let pool = Self::brand_pool_account(&brand_id);
+					ensure!(
+						T::Currencies::balance(old.asset_id, &pool).is_zero(),
+						Error::<T>::BrandRewardPoolNotEmpty
+					);
+				}
+			}
 
-				_ => InvalidTransaction::Call.into(),
+			match config {
+				Some(c) => BrandRewardConfigs::<T>::insert(&brand_id, c),
+				None => BrandRewardConfigs::<T>::remove(&brand_id),
 			}
+			Self::deposit_event(Event::BrandRewardConfigSet { brand_id, config });
+			Ok(())
 		}
-	}
-}
 
-impl<T: Config> Pallet<T>
-where
-	T::CollectionId: From<T::ObjectId>,
-	T::ItemId: From<T::ObjectId>,
-	T::ObjectId: From<T::CollectionId>,
-{
-	fn max_generate_random() -> u32 {
-		1000
-	}
+		/// deposit the reward asset into the brand pool
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - amount Balance
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn fund_brand_rewards(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let config = BrandRewardConfigs::<T>::get(&brand_id)
+				.ok_or(Error::<T>::BrandRewardConfigNotSet)?;
+			ensure!(!amount.is_zero(), Error::<T>::ValueInvalid);
+			<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+				config.asset_id,
+				&who,
+				&Self::brand_pool_account(&brand_id),
+				amount,
+				false,
+			)?;
 
-	pub fn do_mint(
-		brand_id: T::CollectionId,
-		item_id: T::ItemId,
-		owner: T::AccountId,
-	) -> DispatchResult {
-		pallet_uniques::Pallet::<T, T::UniquesInstance>::mint_into(&brand_id, &item_id, &owner)?;
-		Self::deposit_event(Event::Issued { brand_id, item_id, owner });
-		Ok(())
-	}
+			Self::deposit_event(Event::BrandRewardsFunded {
+				who,
+				brand_id,
+				asset_id: config.asset_id,
+				amount,
+			});
+			Ok(())
+		}
+
+		/// withdraw the reward asset from the brand pool by the brand owner
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - amount Balance
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn withdraw_brand_rewards(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let brand_owner =
+				Self::collection_owner(&brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
+			ensure!(brand_owner == who, Error::<T>::OperationIsNotAllowed);
+			let config = BrandRewardConfigs::<T>::get(&brand_id)
+				.ok_or(Error::<T>::BrandRewardConfigNotSet)?;
+			ensure!(!amount.is_zero(), Error::<T>::ValueInvalid);
+			<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+				config.asset_id,
+				&Self::brand_pool_account(&brand_id),
+				&who,
+				amount,
+				false,
+			)?;
+
+			Self::deposit_event(Event::BrandRewardsWithdrawn {
+				who,
+				brand_id,
+				asset_id: config.asset_id,
+				amount,
+			});
+			Ok(())
+		}
+
+		/// create a collection of gems
+		/// - origin AccountId
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn create_gem_collection(origin: OriginFor<T>) -> DispatchResult {
+			let who = T::BrandOrigin::ensure_origin(origin)?;
+			let collection_id: T::CollectionId =
+				T::UniqueId::generate_object_id(T::GemId::get())?.into();
+			T::Gems::create_collection(&collection_id, &who, &who)?;
+
+			Self::deposit_event(Event::GemCollectionCreated { who, collection_id });
+			Ok(())
+		}
+
+		/// mint a gem by the owner of gem collection
+		/// - origin AccountId
+		/// - collection_id CollectionId
+		/// - owner AccountId
+		/// - detail GemDetail
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn mint_gem(
+			origin: OriginFor<T>,
+			collection_id: T::CollectionId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			detail: GemDetail,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let collection_owner =
+				T::Gems::collection_owner(&collection_id).ok_or(Error::<T>::GemNotFound)?;
+			ensure!(collection_owner == who, Error::<T>::OperationIsNotAllowed);
+			ensure!(
+				detail.ability != VFEAbility::default() &&
+					detail.ability.highest() <= T::MaxGemAbility::get(),
+				Error::<T>::ValueInvalid
+			);
+			let owner = T::Lookup::lookup(owner)?;
+
+			let parent_id = Self::into_parent_id(T::GemId::get(), collection_id.into());
+			let item_id: T::ItemId = T::UniqueId::generate_object_id(parent_id)?.into();
+			T::Gems::mint_into(&collection_id, &item_id, &owner)?;
+			GemDetails::<T>::insert(&collection_id, &item_id, detail);
+
+			Self::deposit_event(Event::GemMinted { owner, collection_id, item_id, detail });
+			Ok(())
+		}
+
+		/// socket a gem into VFE, the sockets are unlocked with the level of VFE
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - item_id ItemId
+		/// - gem_collection_id CollectionId
+		/// - gem_item_id ItemId
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn socket_gem(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			gem_collection_id: T::CollectionId,
+			gem_item_id: T::ItemId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// settle the finished upgrade first
+			Self::try_complete_level_up(&brand_id, &item_id)?;
+			let vfe_owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+			ensure!(vfe_owner == who, Error::<T>::OperationIsNotAllowed);
+			// the ability of the lent or staked VFE is fixed until it is returned
+			ensure!(!VFELoans::<T>::contains_key(&brand_id, &item_id), Error::<T>::VFELent);
+			ensure!(!StakedVFEs::<T>::contains_key(&brand_id, &item_id), Error::<T>::VFEStaked);
+			let gem_owner =
+				T::Gems::owner(&gem_collection_id, &gem_item_id).ok_or(Error::<T>::GemNotFound)?;
+			ensure!(gem_owner == who, Error::<T>::OperationIsNotAllowed);
+			let gem = GemDetails::<T>::get(&gem_collection_id, &gem_item_id)
+				.ok_or(Error::<T>::GemNotFound)?;
+			let mut vfe =
+				VFEDetails::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+
+			VFESockets::<T>::try_mutate(&brand_id, &item_id, |sockets| -> DispatchResult {
+				// a socket is unlocked every `LevelsPerSocket` levels
+				let unlocked = (vfe.level / T::LevelsPerSocket::get().max(1)) as u32;
+				ensure!(
+					(sockets.len() as u32) < unlocked.min(T::MaxSockets::get()),
+					Error::<T>::NoSocketAvailable
+				);
+				sockets
+					.try_push((gem_collection_id, gem_item_id))
+					.map_err(|_| Error::<T>::NoSocketAvailable)?;
+				Ok(())
+			})?;
+
+			// the gem is held by the pallet until it is removed
+			T::Gems::transfer(&gem_collection_id, &gem_item_id, &Self::account_id())?;
+			vfe.current_ability = vfe.current_ability.saturating_add(&gem.ability);
+			VFEDetails::<T>::insert(&brand_id, &item_id, vfe);
+
+			Self::deposit_event(Event::GemSocketed {
+				owner: who,
+				brand_id,
+				item_id,
+				gem_collection_id,
+				gem_item_id,
+			});
+			Ok(())
+		}
+
+		/// remove a gem from VFE by paying the fee, the permanent gem can not be removed
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - item_id ItemId
+		/// - gem_collection_id CollectionId
+		/// - gem_item_id ItemId
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn unsocket_gem(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			gem_collection_id: T::CollectionId,
+			gem_item_id: T::ItemId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let vfe_owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+			ensure!(vfe_owner == who, Error::<T>::OperationIsNotAllowed);
+			ensure!(!VFELoans::<T>::contains_key(&brand_id, &item_id), Error::<T>::VFELent);
+			ensure!(!StakedVFEs::<T>::contains_key(&brand_id, &item_id), Error::<T>::VFEStaked);
+			let gem = GemDetails::<T>::get(&gem_collection_id, &gem_item_id)
+				.ok_or(Error::<T>::GemNotFound)?;
+			ensure!(!gem.permanent, Error::<T>::GemPermanent);
+			let mut vfe =
+				VFEDetails::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+
+			VFESockets::<T>::try_mutate(&brand_id, &item_id, |sockets| -> DispatchResult {
+				let index = sockets
+					.iter()
+					.position(|socket| *socket == (gem_collection_id, gem_item_id))
+					.ok_or(Error::<T>::GemNotSocketed)?;
+				sockets.remove(index);
+				Ok(())
+			})?;
+
+			let fee = T::UnsocketFee::get();
+			if !fee.is_zero() {
+				let incentive_token =
+					IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?;
+				T::Currencies::burn_from(incentive_token, &who, fee)?;
+			}
+
+			T::Gems::transfer(&gem_collection_id, &gem_item_id, &who)?;
+			vfe.current_ability = vfe.current_ability.saturating_sub(&gem.ability);
+			VFEDetails::<T>::insert(&brand_id, &item_id, vfe);
+
+			Self::deposit_event(Event::GemUnsocketed {
+				owner: who,
+				brand_id,
+				item_id,
+				gem_collection_id,
+				gem_item_id,
+				fee,
+			});
+			Ok(())
+		}
+
+		/// Increase ability
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - instance ItemId
+		/// - ability VFEAbility
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn increase_ability(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			ability: VFEAbility,
+		) -> DispatchResult {
+			// settle the finished upgrade first
+			Self::try_complete_level_up(&brand_id, &item_id)?;
+			VFEDetails::<T>::try_mutate(&brand_id, &item_id, |maybe_vfe| -> DispatchResult {
+				let who = ensure_signed(origin.clone())?;
+				let mut vfe = maybe_vfe.take().ok_or(Error::<T>::VFENotExist)?;
+				let vfe_owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+				ensure!(vfe_owner == who, Error::<T>::OperationIsNotAllowed);
+				let total_ability =
+					ability.efficiency + ability.skill + ability.luck + ability.durable;
+				ensure!(total_ability <= vfe.available_points, Error::<T>::ValueInvalid);
+
+				vfe.current_ability.efficiency =
+					vfe.current_ability.efficiency.saturating_add(ability.efficiency);
+				vfe.current_ability.skill = vfe.current_ability.skill.saturating_add(ability.skill);
+				vfe.current_ability.luck = vfe.current_ability.luck.saturating_add(ability.luck);
+				vfe.current_ability.durable =
+					vfe.current_ability.durable.saturating_add(ability.durable);
+				vfe.available_points = vfe.available_points.saturating_sub(total_ability);
+
+				*maybe_vfe = Some(vfe);
+
+				// emit event
+				Self::deposit_event(Event::VFEAbilityIncreased { brand_id, item_id });
+
+				Ok(())
+			})
+		}
+
+		/// register a new sport type
+		/// - origin GovernanceOrigin
+		/// - sport_type SportId
+		/// - info SportTypeInfo
+		#[pallet::weight(10_000)]
+		pub fn register_sport_type(
+			origin: OriginFor<T>,
+			#[pallet::compact] sport_type: SportId,
+			info: SportTypeInfo,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(Self::sport_type_info(sport_type).is_none(), Error::<T>::SportTypeExisted);
+			ensure!(info.is_valid(), Error::<T>::ValueInvalid);
+
+			SportTypes::<T>::insert(sport_type, info);
+			Self::deposit_event(Event::SportTypeRegistered { sport_type, info });
+			Ok(())
+		}
+
+		/// update the parameters of a registered sport type, the report type can not be changed,
+		/// and the built-in sport types always keep their own report types.
+		/// - origin GovernanceOrigin
+		/// - sport_type SportId
+		/// - info SportTypeInfo
+		#[pallet::weight(10_000)]
+		pub fn update_sport_type(
+			origin: OriginFor<T>,
+			#[pallet::compact] sport_type: SportId,
+			info: SportTypeInfo,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			let current = Self::sport_type_info(sport_type).ok_or(Error::<T>::SportTypeNotFound)?;
+			ensure!(current.report_type == info.report_type, Error::<T>::OperationIsNotAllowed);
+			// the reports of the built-in sport types are decoded by their fixed layouts
+			if let Some(built_in) = Self::built_in_sport_type(sport_type) {
+				ensure!(info.report_type == built_in, Error::<T>::OperationIsNotAllowed);
+			}
+			ensure!(info.is_valid(), Error::<T>::ValueInvalid);
+
+			SportTypes::<T>::insert(sport_type, info);
+			Self::deposit_event(Event::SportTypeUpdated { sport_type, info });
+			Ok(())
+		}
+
+		/// set the unbind settings
+		/// - origin GovernanceOrigin
+		/// - settings UnbindSettings
+		#[pallet::weight(10_000)]
+		pub fn set_unbind_settings(
+			origin: OriginFor<T>,
+			settings: UnbindSettings<AssetIdOf<T>, BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+
+			UnbindConfig::<T>::put(settings);
+			Self::deposit_event(Event::UnbindSettingsUpdated { settings });
+			Ok(())
+		}
+
+		/// set the economic parameters
+		/// - origin GovernanceOrigin
+		/// - params EconomicParameters
+		#[pallet::weight(10_000)]
+		pub fn set_economic_parameters(
+			origin: OriginFor<T>,
+			params: EconomicParameters<BalanceOf<T>>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(params.is_valid(), Error::<T>::ValueInvalid);
+
+			EconomicParams::<T>::put(params);
+			Self::deposit_event(Event::EconomicParametersUpdated { params });
+			Ok(())
+		}
+
+		/// set the plausibility rules of training reports
+		/// - origin GovernanceOrigin
+		/// - rules Option<PlausibilityRules>
+		#[pallet::weight(10_000)]
+		pub fn set_plausibility_rules(
+			origin: OriginFor<T>,
+			rules: Option<PlausibilityRules>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			match rules {
+				Some(r) => {
+					ensure!(r.is_valid(), Error::<T>::ValueInvalid);
+					PlausibilityConfig::<T>::put(r);
+				},
+				None => PlausibilityConfig::<T>::kill(),
+			}
+			Self::deposit_event(Event::PlausibilityRulesUpdated { rules });
+			Ok(())
+		}
+
+		/// clear the quarantine of the device by the producer owner or `AuditOrigin`, the held
+		/// rewards are released to the VFE owners or discarded, the discarded rewards are reverted
+		/// like the rewards of the invalid reports.
+		/// - origin AccountId or AuditOrigin
+		/// - puk DeviceKey
+		/// - release bool
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn clear_device_quarantine(
+			origin: OriginFor<T>,
+			puk: DeviceKey,
+			release: bool,
+		) -> DispatchResult {
+			let device = Devices::<T>::get(puk).ok_or(Error::<T>::DeviceNotExisted)?;
+			ensure!(QuarantinedDevices::<T>::contains_key(puk), Error::<T>::DeviceNotQuarantined);
+			let operator = match T::AuditOrigin::ensure_origin(origin.clone()) {
+				Ok(_) => None,
+				Err(_) => {
+					let who = ensure_signed(origin)?;
+					let producer = Producers::<T>::get(device.producer_id)
+						.ok_or(Error::<T>::ProducerNotExist)?;
+					ensure!(producer.owner == who, Error::<T>::OperationIsNotAllowed);
+					Some(who)
+				},
+			};
+
+			QuarantinedDevices::<T>::remove(puk);
+			DeviceSuspicion::<T>::remove(puk);
+			for (timestamp, held) in HeldRewards::<T>::drain_prefix(puk) {
+				if !release {
+					// the rewards are never minted
+					Self::revert_report_rewards(
+						puk,
+						timestamp,
+						&held.owner,
+						held.amount,
+						held.reported_at,
+					);
+					continue
+				}
+				Self::pay_training_rewards(
+					&device.brand_id,
+					device.item_id,
+					&held.owner,
+					held.amount,
+				)?;
+				if held.referral {
+					Self::pay_referral_rewards(&held.owner, held.amount)?;
+				}
+			}
+
+			Self::deposit_event(Event::DeviceQuarantineCleared {
+				operator,
+				device_key: puk,
+				released: release,
+			});
+			Ok(())
+		}
+
+		/// challenge the training report in the challenge window, the rewards are held until the
+		/// challenge is resolved
+		/// - origin ChallengeOrigin
+		/// - puk DeviceKey
+		/// - timestamp u32
+		#[pallet::weight(10_000)]
+		pub fn challenge_report(
+			origin: OriginFor<T>,
+			puk: DeviceKey,
+			timestamp: u32,
+		) -> DispatchResult {
+			let challenger = T::ChallengeOrigin::ensure_origin(origin)?;
+			PendingReports::<T>::try_mutate(puk, timestamp, |maybe_report| -> DispatchResult {
+				let report = maybe_report.as_mut().ok_or(Error::<T>::ReportNotFound)?;
+				ensure!(report.challenger.is_none(), Error::<T>::ReportChallenged);
+				let now = frame_system::Pallet::<T>::block_number();
+				ensure!(now <= report.deadline, Error::<T>::ChallengeWindowExpired);
+				report.challenger = Some(challenger.clone());
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::ReportChallenged { challenger, device_key: puk, timestamp });
+			Ok(())
+		}
+
+		/// resolve the challenge of the training report, the rewards are released if the report
+		/// is valid, otherwise they are burned and the device is quarantined
+		/// - origin ChallengeOrigin
+		/// - puk DeviceKey
+		/// - timestamp u32
+		/// - valid bool
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn resolve_challenge(
+			origin: OriginFor<T>,
+			puk: DeviceKey,
+			timestamp: u32,
+			valid: bool,
+		) -> DispatchResult {
+			let auditor = T::ChallengeOrigin::ensure_origin(origin)?;
+			let report =
+				PendingReports::<T>::get(puk, timestamp).ok_or(Error::<T>::ReportNotFound)?;
+			ensure!(report.challenger.is_some(), Error::<T>::ReportNotChallenged);
+			PendingReports::<T>::remove(puk, timestamp);
+
+			if valid {
+				Self::pay_training_rewards(
+					&report.brand_id,
+					Some(report.item_id),
+					&report.owner,
+					report.amount,
+				)?;
+				if Self::in_referral_period(&report.owner, report.reported_at) {
+					Self::pay_referral_rewards(&report.owner, report.amount)?;
+				}
+			} else {
+				// the rewards are never minted
+				Self::revert_report_rewards(
+					puk,
+					timestamp,
+					&report.owner,
+					report.amount,
+					report.reported_at,
+				);
+				if !QuarantinedDevices::<T>::contains_key(puk) {
+					// the device is quarantined
+					QuarantinedDevices::<T>::insert(puk, frame_system::Pallet::<T>::block_number());
+					Self::deposit_event(Event::DeviceQuarantined {
+						device_key: puk,
+						score: DeviceSuspicion::<T>::get(puk),
+					});
+				}
+			}
+
+			Self::deposit_event(Event::ChallengeResolved {
+				auditor,
+				device_key: puk,
+				timestamp,
+				valid,
+			});
+			Ok(())
+		}
+
+		/// claim the rewards of the training report after the challenge window
+		/// - origin AccountId
+		/// - puk DeviceKey
+		/// - timestamp u32
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn claim_report_rewards(
+			origin: OriginFor<T>,
+			puk: DeviceKey,
+			timestamp: u32,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let report =
+				PendingReports::<T>::get(puk, timestamp).ok_or(Error::<T>::ReportNotFound)?;
+			ensure!(report.challenger.is_none(), Error::<T>::ReportChallenged);
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(now > report.deadline, Error::<T>::ChallengeWindowNotExpired);
+			PendingReports::<T>::remove(puk, timestamp);
+
+			Self::pay_training_rewards(
+				&report.brand_id,
+				Some(report.item_id),
+				&report.owner,
+				report.amount,
+			)?;
+			if Self::in_referral_period(&report.owner, report.reported_at) {
+				Self::pay_referral_rewards(&report.owner, report.amount)?;
+			}
+			Self::deposit_event(Event::ReportRewardsClaimed {
+				owner: report.owner,
+				device_key: puk,
+				timestamp,
+				amount: report.amount,
+			});
+			Ok(())
+		}
+
+		/// set the leaderboard, the sport leaderboard is set by GovernanceOrigin and the brand
+		/// leaderboard by the brand owner, the asset can only be changed when the pool is empty,
+		/// and the standings of the current period are reset, the ended period is kept to be
+		/// settled with the new settings
+		/// - origin AccountId
+		/// - key LeaderboardKey
+		/// - asset_id AssetId
+		/// - period BlockNumber
+		/// - prize_shares BoundedVec<Permill>
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn set_leaderboard(
+			origin: OriginFor<T>,
+			key: LeaderboardKey<T::CollectionId>,
+			asset_id: AssetIdOf<T>,
+			period: T::BlockNumber,
+			prize_shares: BoundedVec<Permill, T::LeaderboardSize>,
+		) -> DispatchResult {
+			Self::ensure_leaderboard_manager(origin, &key)?;
+			ensure!(!period.is_zero(), Error::<T>::ValueInvalid);
+			let total_shares = prize_shares
+				.iter()
+				.fold(0u32, |acc, share| acc.saturating_add(share.deconstruct()));
+			ensure!(total_shares <= Permill::one().deconstruct(), Error::<T>::ValueInvalid);
+
+			if let Some(old) = Leaderboards::<T>::get(&key) {
+				// keep the ended period of the old settings to be settled
+				Self::current_leaderboard_standings(&key, &old);
+				if old.asset_id != asset_id {
+					let pool = Self::leaderboard_pool_account(&key);
+					ensure!(
+						T::Currencies::balance(old.asset_id, &pool).is_zero(),
+						Error::<T>::LeaderboardPoolNotEmpty
+					);
+				}
+			}
+			Leaderboards::<T>::insert(&key, LeaderboardInfo { asset_id, period, prize_shares });
+			// the scores of the current period are not comparable with the new settings
+			LeaderboardStandings::<T>::remove(&key);
+			let _ = LeaderboardScores::<T>::clear_prefix(&key, u32::MAX, None);
+
+			Self::deposit_event(Event::LeaderboardSet { key, asset_id, period });
+			Ok(())
+		}
+
+		/// remove the leaderboard when the prize pool is empty
+		/// - origin AccountId
+		/// - key LeaderboardKey
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn remove_leaderboard(
+			origin: OriginFor<T>,
+			key: LeaderboardKey<T::CollectionId>,
+		) -> DispatchResult {
+			Self::ensure_leaderboard_manager(origin, &key)?;
+			let info = Leaderboards::<T>::get(&key).ok_or(Error::<T>::LeaderboardNotFound)?;
+			let pool = Self::leaderboard_pool_account(&key);
+			ensure!(
+				T::Currencies::balance(info.asset_id, &pool).is_zero(),
+				Error::<T>::LeaderboardPoolNotEmpty
+			);
+			Leaderboards::<T>::remove(&key);
+			LeaderboardStandings::<T>::remove(&key);
+			let _ = EndedLeaderboardStandings::<T>::clear_prefix(&key, u32::MAX, None);
+			let _ = LeaderboardScores::<T>::clear_prefix(&key, u32::MAX, None);
+			LeaderboardReservedPrizes::<T>::remove(&key);
+
+			Self::deposit_event(Event::LeaderboardRemoved { key });
+			Ok(())
+		}
+
+		/// deposit the prize asset into the prize pool of the leaderboard
+		/// - origin AccountId
+		/// - key LeaderboardKey
+		/// - amount Balance
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn fund_leaderboard(
+			origin: OriginFor<T>,
+			key: LeaderboardKey<T::CollectionId>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let info = Leaderboards::<T>::get(&key).ok_or(Error::<T>::LeaderboardNotFound)?;
+			ensure!(!amount.is_zero(), Error::<T>::ValueInvalid);
+			<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+				info.asset_id,
+				&who,
+				&Self::leaderboard_pool_account(&key),
+				amount,
+				false,
+			)?;
+
+			Self::deposit_event(Event::LeaderboardFunded { who, key, amount });
+			Ok(())
+		}
+
+		/// settle the ended period of the leaderboard and distribute the prizes reserved when the
+		/// period ends, the prize which is failed to pay is kept in the prize pool
+		/// - origin AccountId
+		/// - key LeaderboardKey
+		/// - period u32
+		#[pallet::weight(T::DbWeight::get().reads_writes(5, 4).saturating_add(
+			T::DbWeight::get().reads_writes(2, 2).saturating_mul(T::LeaderboardSize::get() as Weight)
+		))]
+		#[transactional]
+		pub fn settle_leaderboard(
+			origin: OriginFor<T>,
+			key: LeaderboardKey<T::CollectionId>,
+			period: u32,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let info = Leaderboards::<T>::get(&key).ok_or(Error::<T>::LeaderboardNotFound)?;
+			let standings = Self::current_leaderboard_standings(&key, &info);
+			let current_period = standings.period;
+			LeaderboardStandings::<T>::insert(&key, standings);
+			let ended = match EndedLeaderboardStandings::<T>::take(&key, period) {
+				Some(ended) => ended,
+				None if period >= current_period =>
+					return Err(Error::<T>::LeaderboardPeriodNotEnded.into()),
+				None => return Err(Error::<T>::LeaderboardStandingsNotFound.into()),
+			};
+			// the unpaid prizes are released to the later periods
+			LeaderboardReservedPrizes::<T>::mutate(&key, |reserved| {
+				*reserved = reserved.saturating_sub(ended.prizes)
+			});
+
+			let pool = Self::leaderboard_pool_account(&key);
+			for (rank, (who, _)) in ended.ranks.into_iter().enumerate() {
+				let amount = match info.prize_shares.get(rank) {
+					Some(share) => share.mul_floor(ended.prizes),
+					None => break,
+				};
+				if amount.is_zero() {
+					continue
+				}
+				let rank = rank as u32 + 1;
+				// the failed payout does not block the other ranks
+				match <T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+					info.asset_id,
+					&pool,
+					&who,
+					amount,
+					false,
+				) {
+					Ok(_) => Self::deposit_event(Event::LeaderboardPrizePaid {
+						key,
+						period,
+						rank,
+						who,
+						amount,
+					}),
+					Err(_) => Self::deposit_event(Event::LeaderboardPrizeFailed {
+						key,
+						period,
+						rank,
+						who,
+						amount,
+					}),
+				}
+			}
+
+			Self::deposit_event(Event::LeaderboardSettled { key, period });
+			Ok(())
+		}
+
+		/// create the brand challenge by the brand owner, the prize pool is funded by the owner
+		/// and the entry fees of the participants
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - terms ChallengeTerms
+		/// - prize_pool Balance
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn create_brand_challenge(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			terms: ChallengeTerms<AssetIdOf<T>, BalanceOf<T>, T::BlockNumber>,
+			#[pallet::compact] prize_pool: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let brand_owner =
+				Self::collection_owner(&brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
+			ensure!(brand_owner == who, Error::<T>::OperationIsNotAllowed);
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(terms.target > 0, Error::<T>::ValueInvalid);
+			ensure!(terms.start < terms.end && now < terms.end, Error::<T>::ValueInvalid);
+
+			let challenge_id = NextBrandChallengeId::<T>::get();
+			UnsettledBrandChallenges::<T>::try_mutate(&brand_id, |challenges| {
+				challenges.try_push(challenge_id)
+			})
+			.map_err(|_| Error::<T>::TooManyBrandChallenges)?;
+			if !prize_pool.is_zero() {
+				<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+					terms.asset_id,
+					&who,
+					&Self::brand_challenge_account(challenge_id),
+					prize_pool,
+					false,
+				)?;
+			}
+			BrandChallenges::<T>::insert(
+				challenge_id,
+				BrandChallenge { brand_id, terms, participants: 0, completed: 0, prize: None },
+			);
+			NextBrandChallengeId::<T>::put(challenge_id.saturating_add(1));
+
+			Self::deposit_event(Event::BrandChallengeCreated {
+				challenge_id,
+				brand_id,
+				terms,
+				prize_pool,
+			});
+			Ok(())
+		}
+
+		/// join the brand challenge with an owned VFE of the brand and pay the entry fee
+		/// - origin AccountId
+		/// - challenge_id u32
+		/// - item_id ItemId
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn join_brand_challenge(
+			origin: OriginFor<T>,
+			challenge_id: u32,
+			item_id: T::ItemId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut challenge = BrandChallenges::<T>::get(challenge_id)
+				.ok_or(Error::<T>::BrandChallengeNotFound)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(now <= challenge.terms.end, Error::<T>::BrandChallengeEnded);
+			ensure!(
+				!ChallengeEntries::<T>::contains_key(challenge_id, &who),
+				Error::<T>::BrandChallengeJoined
+			);
+			let vfe_owner =
+				Self::owner(&challenge.brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+			ensure!(vfe_owner == who, Error::<T>::OperationIsNotAllowed);
+
+			if !challenge.terms.entry_fee.is_zero() {
+				<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+					challenge.terms.asset_id,
+					&who,
+					&Self::brand_challenge_account(challenge_id),
+					challenge.terms.entry_fee,
+					false,
+				)?;
+			}
+			ChallengeEntries::<T>::insert(
+				challenge_id,
+				&who,
+				ChallengeEntry { item_id, progress: 0, claimed: false },
+			);
+			challenge.participants = challenge.participants.saturating_add(1);
+			BrandChallenges::<T>::insert(challenge_id, challenge);
+
+			Self::deposit_event(Event::BrandChallengeJoined { challenge_id, who, item_id });
+			Ok(())
+		}
+
+		/// settle the ended brand challenge, the prize pool is shared by the participants who
+		/// complete it, or returned to the brand owner if nobody completes it
+		/// - origin AccountId
+		/// - challenge_id u32
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn settle_brand_challenge(origin: OriginFor<T>, challenge_id: u32) -> DispatchResult {
+			ensure_signed(origin)?;
+			let mut challenge = BrandChallenges::<T>::get(challenge_id)
+				.ok_or(Error::<T>::BrandChallengeNotFound)?;
+			ensure!(challenge.prize.is_none(), Error::<T>::BrandChallengeSettled);
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(now > challenge.terms.end, Error::<T>::BrandChallengeNotEnded);
+
+			let pool = Self::brand_challenge_account(challenge_id);
+			let prize_pool =
+				T::Currencies::reducible_balance(challenge.terms.asset_id, &pool, false);
+			let prize = if challenge.completed > 0 {
+				prize_pool / BalanceOf::<T>::from(challenge.completed)
+			} else {
+				if !prize_pool.is_zero() {
+					let brand_owner = Self::collection_owner(&challenge.brand_id)
+						.ok_or(Error::<T>::VFEBrandNotFound)?;
+					<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+						challenge.terms.asset_id,
+						&pool,
+						&brand_owner,
+						prize_pool,
+						false,
+					)?;
+				}
+				Zero::zero()
+			};
+			challenge.prize = Some(prize);
+			BrandChallenges::<T>::insert(challenge_id, challenge);
+			UnsettledBrandChallenges::<T>::mutate(&challenge.brand_id, |challenges| {
+				challenges.retain(|id| *id != challenge_id)
+			});
+
+			Self::deposit_event(Event::BrandChallengeSettled {
+				challenge_id,
+				completed: challenge.completed,
+				prize,
+			});
+			Ok(())
+		}
+
+		/// claim the prize of the settled brand challenge by the participant who completes it
+		/// - origin AccountId
+		/// - challenge_id u32
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn claim_brand_challenge_prize(
+			origin: OriginFor<T>,
+			challenge_id: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let challenge = BrandChallenges::<T>::get(challenge_id)
+				.ok_or(Error::<T>::BrandChallengeNotFound)?;
+			let prize = challenge.prize.ok_or(Error::<T>::BrandChallengeNotEnded)?;
+			ChallengeEntries::<T>::try_mutate(
+				challenge_id,
+				&who,
+				|maybe_entry| -> DispatchResult {
+					let entry =
+						maybe_entry.as_mut().ok_or(Error::<T>::BrandChallengeNotCompleted)?;
+					ensure!(
+						entry.progress >= challenge.terms.target,
+						Error::<T>::BrandChallengeNotCompleted
+					);
+					ensure!(!entry.claimed, Error::<T>::BrandChallengePrizeClaimed);
+					entry.claimed = true;
+					Ok(())
+				},
+			)?;
+
+			if !prize.is_zero() {
+				<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+					challenge.terms.asset_id,
+					&Self::brand_challenge_account(challenge_id),
+					&who,
+					prize,
+					false,
+				)?;
+			}
+			Self::deposit_event(Event::BrandChallengePrizeClaimed {
+				challenge_id,
+				who,
+				amount: prize,
+			});
+			Ok(())
+		}
+
+		/// set the referrer before the user is created by the first binding
+		/// - origin AccountId
+		/// - referrer AccountId
+		#[pallet::weight(10_000)]
+		pub fn set_referrer(
+			origin: OriginFor<T>,
+			referrer: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let referrer = T::Lookup::lookup(referrer)?;
+			ensure!(!Users::<T>::contains_key(&who), Error::<T>::UserAlreadyCreated);
+			ensure!(!Referrers::<T>::contains_key(&who), Error::<T>::ReferrerAlreadySet);
+			ensure!(
+				referrer != who && Users::<T>::contains_key(&referrer),
+				Error::<T>::ReferrerInvalid
+			);
+			Referrers::<T>::insert(&who, &referrer);
+
+			Self::deposit_event(Event::ReferrerSet { who, referrer });
+			Ok(())
+		}
+
+		/// create a team, the creator becomes the captain
+		/// - origin AccountId
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn create_team(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!UserTeam::<T>::contains_key(&who), Error::<T>::AlreadyInTeam);
+
+			let team_id = NextTeamId::<T>::get();
+			let mut members = BoundedVec::default();
+			members.try_push(who.clone()).map_err(|_| Error::<T>::TeamFull)?;
+			Teams::<T>::insert(team_id, Team { captain: who.clone(), members });
+			UserTeam::<T>::insert(&who, team_id);
+			NextTeamId::<T>::put(team_id.saturating_add(1));
+
+			Self::deposit_event(Event::TeamCreated { team_id, captain: who });
+			Ok(())
+		}
+
+		/// invite the user to join the team by the captain
+		/// - origin AccountId
+		/// - team_id u32
+		/// - who AccountId
+		#[pallet::weight(10_000)]
+		pub fn invite_team_member(
+			origin: OriginFor<T>,
+			team_id: u32,
+			who: T::AccountId,
+		) -> DispatchResult {
+			let captain = ensure_signed(origin)?;
+			let team = Teams::<T>::get(team_id).ok_or(Error::<T>::TeamNotFound)?;
+			ensure!(team.captain == captain, Error::<T>::OperationIsNotAllowed);
+			ensure!(!UserTeam::<T>::contains_key(&who), Error::<T>::AlreadyInTeam);
+			TeamInvitations::<T>::insert(team_id, &who, ());
+
+			Self::deposit_event(Event::TeamMemberInvited { team_id, who });
+			Ok(())
+		}
+
+		/// join the team invited by the captain
+		/// - origin AccountId
+		/// - team_id u32
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn join_team(origin: OriginFor<T>, team_id: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!UserTeam::<T>::contains_key(&who), Error::<T>::AlreadyInTeam);
+			let mut team = Teams::<T>::get(team_id).ok_or(Error::<T>::TeamNotFound)?;
+			ensure!(
+				TeamInvitations::<T>::take(team_id, &who).is_some(),
+				Error::<T>::TeamInvitationNotFound
+			);
+			team.members.try_push(who.clone()).map_err(|_| Error::<T>::TeamFull)?;
+			Teams::<T>::insert(team_id, team);
+			UserTeam::<T>::insert(&who, team_id);
+
+			Self::deposit_event(Event::TeamJoined { team_id, who });
+			Ok(())
+		}
+
+		/// leave the team, the team is disbanded when the captain leaves alone
+		/// - origin AccountId
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn leave_team(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let team_id = UserTeam::<T>::get(&who).ok_or(Error::<T>::NotInTeam)?;
+			let team = Teams::<T>::get(team_id).ok_or(Error::<T>::TeamNotFound)?;
+			if team.captain == who {
+				ensure!(team.members.len() == 1, Error::<T>::CaptainCannotLeave);
+				Teams::<T>::remove(team_id);
+				TeamProgresses::<T>::remove(team_id);
+				let _ = TeamInvitations::<T>::clear_prefix(team_id, u32::MAX, None);
+				UserTeam::<T>::remove(&who);
+				Self::deposit_event(Event::TeamLeft { team_id, who });
+				Self::deposit_event(Event::TeamDisbanded { team_id });
+				return Ok(())
+			}
+
+			Self::do_remove_team_member(team_id, team, who)
+		}
+
+		/// remove the member from the team by the captain
+		/// - origin AccountId
+		/// - who AccountId
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn remove_team_member(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			let captain = ensure_signed(origin)?;
+			let team_id = UserTeam::<T>::get(&captain).ok_or(Error::<T>::NotInTeam)?;
+			let team = Teams::<T>::get(team_id).ok_or(Error::<T>::TeamNotFound)?;
+			ensure!(team.captain == captain && who != captain, Error::<T>::OperationIsNotAllowed);
+			ensure!(UserTeam::<T>::get(&who) == Some(team_id), Error::<T>::NotInTeam);
+
+			Self::do_remove_team_member(team_id, team, who)
+		}
+
+		/// hand over the captain of the team to another member
+		/// - origin AccountId
+		/// - new_captain AccountId
+		#[pallet::weight(10_000)]
+		pub fn transfer_team_captain(
+			origin: OriginFor<T>,
+			new_captain: T::AccountId,
+		) -> DispatchResult {
+			let captain = ensure_signed(origin)?;
+			let team_id = UserTeam::<T>::get(&captain).ok_or(Error::<T>::NotInTeam)?;
+			let mut team = Teams::<T>::get(team_id).ok_or(Error::<T>::TeamNotFound)?;
+			ensure!(team.captain == captain, Error::<T>::OperationIsNotAllowed);
+			ensure!(UserTeam::<T>::get(&new_captain) == Some(team_id), Error::<T>::NotInTeam);
+			team.captain = new_captain.clone();
+			Teams::<T>::insert(team_id, team);
+
+			Self::deposit_event(Event::TeamCaptainChanged { team_id, captain: new_captain });
+			Ok(())
+		}
+
+		/// list the unbound VFE for lending, the borrower can bind it to the device and train in
+		/// the duration, and the share of the training rewards is paid to the owner
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - item_id ItemId
+		/// - duration BlockNumber
+		/// - owner_share Permill
+		#[pallet::weight(10_000)]
+		pub fn list_vfe_for_lending(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			duration: T::BlockNumber,
+			owner_share: Permill,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+			ensure!(owner == who, Error::<T>::OperationIsNotAllowed);
+			ensure!(!duration.is_zero(), Error::<T>::ValueInvalid);
+			ensure!(!VFELoans::<T>::contains_key(&brand_id, &item_id), Error::<T>::VFELent);
+			ensure!(!StakedVFEs::<T>::contains_key(&brand_id, &item_id), Error::<T>::VFEStaked);
+			let vfe = VFEDetails::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+			ensure!(vfe.device_key.is_none(), Error::<T>::VFEBond);
+			ensure!(!vfe.is_upgrading, Error::<T>::VFEUpgrading);
+
+			VFELoans::<T>::insert(
+				&brand_id,
+				&item_id,
+				VFELoan { owner: who.clone(), duration, owner_share, borrower: None },
+			);
+
+			Self::deposit_event(Event::VFEListedForLending {
+				owner: who,
+				brand_id,
+				item_id,
+				duration,
+				owner_share,
+			});
+			Ok(())
+		}
+
+		/// borrow the VFE listed for lending, the borrower can bind it to the device until the
+		/// loan expires
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - item_id ItemId
+		#[pallet::weight(10_000)]
+		pub fn borrow_vfe(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let expiry = VFELoans::<T>::try_mutate(
+				&brand_id,
+				&item_id,
+				|maybe_loan| -> Result<T::BlockNumber, DispatchError> {
+					let loan = maybe_loan.as_mut().ok_or(Error::<T>::VFENotListed)?;
+					ensure!(loan.borrower.is_none(), Error::<T>::VFELent);
+					ensure!(loan.owner != who, Error::<T>::OperationIsNotAllowed);
+					let expiry =
+						frame_system::Pallet::<T>::block_number().saturating_add(loan.duration);
+					loan.borrower = Some((who.clone(), expiry));
+					Ok(expiry)
+				},
+			)?;
+
+			Self::deposit_event(Event::VFEBorrowed { borrower: who, brand_id, item_id, expiry });
+			Ok(())
+		}
+
+		/// end the loan of the VFE, the owner cancels the listing which is not borrowed, the
+		/// borrower returns the VFE before the expiry, and anyone can end the expired loan. The
+		/// VFE is unbound from the device without the fee, and the bind cooldown is applied.
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - item_id ItemId
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn end_vfe_loan(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let loan = VFELoans::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotListed)?;
+			match &loan.borrower {
+				None => ensure!(loan.owner == who, Error::<T>::OperationIsNotAllowed),
+				Some((borrower, expiry)) => {
+					let now = frame_system::Pallet::<T>::block_number();
+					ensure!(borrower == &who || now >= *expiry, Error::<T>::VFELoanNotExpired);
+				},
+			}
+			Self::do_end_vfe_loan(&brand_id, &item_id, loan.owner)
+		}
+
+		/// stake the unbound and fully charged VFE to boost the energy cap or the earning cap of
+		/// the owner, the boost depends on the rarity and the level of VFE
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - item_id ItemId
+		/// - target StakingTarget
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn stake_vfe(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			target: StakingTarget,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+			ensure!(owner == who, Error::<T>::OperationIsNotAllowed);
+			// the staked VFE is idle like the transferable VFE
+			Self::check_vfe_can_transfer(&brand_id, &item_id)?;
+			let vfe = VFEDetails::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+
+			let boost = Self::staking_boost(&vfe, target);
+			let mut user = Self::find_user(&who);
+			user.energy_total = user.energy_total.saturating_add(boost.energy);
+			user.earning_cap = user.earning_cap.saturating_add(boost.earning_cap);
+			Users::<T>::insert(&who, user);
+			StakingBoosts::<T>::mutate(&who, |total| {
+				total.energy = total.energy.saturating_add(boost.energy);
+				total.earning_cap = total.earning_cap.saturating_add(boost.earning_cap);
+			});
+			StakedVFEs::<T>::insert(
+				&brand_id,
+				&item_id,
+				StakedVFE { owner: who.clone(), boost, unbonding_until: None },
+			);
+
+			Self::deposit_event(Event::VFEStaked { owner: who, brand_id, item_id, boost });
+			Ok(())
+		}
+
+		/// unstake the VFE, the boost is removed from the owner and the VFE can be withdrawn
+		/// after the unbonding period
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - item_id ItemId
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn unstake_vfe(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut staked =
+				StakedVFEs::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotStaked)?;
+			ensure!(staked.owner == who, Error::<T>::OperationIsNotAllowed);
+			ensure!(staked.unbonding_until.is_none(), Error::<T>::VFEUnbonding);
+
+			let boost = staked.boost;
+			let mut user = Self::find_user(&who);
+			user.energy_total = user.energy_total.saturating_sub(boost.energy);
+			user.energy = user.energy.min(user.energy_total);
+			user.earning_cap = user.earning_cap.saturating_sub(boost.earning_cap);
+			Users::<T>::insert(&who, user);
+			StakingBoosts::<T>::mutate(&who, |total| {
+				total.energy = total.energy.saturating_sub(boost.energy);
+				total.earning_cap = total.earning_cap.saturating_sub(boost.earning_cap);
+			});
+
+			let unbonding_until = frame_system::Pallet::<T>::block_number()
+				.saturating_add(T::StakingUnbondingPeriod::get());
+			staked.unbonding_until = Some(unbonding_until);
+			StakedVFEs::<T>::insert(&brand_id, &item_id, staked);
+
+			Self::deposit_event(Event::VFEUnstaked {
+				owner: who,
+				brand_id,
+				item_id,
+				unbonding_until,
+			});
+			Ok(())
+		}
+
+		/// withdraw the unstaked VFE after the unbonding period
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - item_id ItemId
+		#[pallet::weight(10_000)]
+		pub fn withdraw_vfe(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let staked =
+				StakedVFEs::<T>::get(&brand_id, &item_id).ok_or(Error::<T>::VFENotStaked)?;
+			ensure!(staked.owner == who, Error::<T>::OperationIsNotAllowed);
+			let until = staked.unbonding_until.ok_or(Error::<T>::VFEStaked)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(now >= until, Error::<T>::UnbondingNotExpired);
+			StakedVFEs::<T>::remove(&brand_id, &item_id);
+
+			Self::deposit_event(Event::VFEWithdrawn { owner: who, brand_id, item_id });
+			Ok(())
+		}
+
+		/// set the number of VFEs that can be created by fusion into the brand, by the brand
+		/// owner
+		/// - origin AccountId
+		/// - brand_id CollectionId
+		/// - amount u32
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn set_fusion_allowance(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			amount: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let brand_owner =
+				Self::collection_owner(&brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
+			ensure!(brand_owner == who, Error::<T>::OperationIsNotAllowed);
+
+			FusionAllowances::<T>::insert(&brand_id, amount);
+			Self::deposit_event(Event::FusionAllowanceSet { brand_id, amount });
+			Ok(())
+		}
+
+		/// fuse two owned VFEs of the same sport into a new VFE of the brand, the base abilities
+		/// of the new VFE are derived from the genes of parents and randomness, and the parents
+		/// are put on cooldown. The rarity of the new VFE is the rarity of the brand, and each
+		/// fusion uses one of the fusion allowance set by the brand owner.
+		/// - origin AccountId
+		/// - brand_id CollectionId, the brand of the new VFE
+		/// - item_id ItemId
+		/// - mate_brand_id CollectionId
+		/// - mate_item_id ItemId
+		#[pallet::weight(10_000)]
+		#[transactional]
+		pub fn fuse_vfe(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			mate_brand_id: T::CollectionId,
+			mate_item_id: T::ItemId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				(brand_id, item_id) != (mate_brand_id, mate_item_id),
+				Error::<T>::OperationIsNotAllowed
+			);
+			let vfe_brand = VFEBrands::<T>::get(brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
+			let mate_brand =
+				VFEBrands::<T>::get(mate_brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
+			ensure!(
+				vfe_brand.sport_type == mate_brand.sport_type,
+				Error::<T>::VFESportTypeMismatch
+			);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			let parents = [(brand_id, item_id), (mate_brand_id, mate_item_id)];
+			let mut breeds_total = 0u16;
+			for (parent_brand_id, parent_item_id) in parents.iter() {
+				let owner =
+					Self::owner(parent_brand_id, parent_item_id).ok_or(Error::<T>::VFENotExist)?;
+				ensure!(owner == who, Error::<T>::OperationIsNotAllowed);
+				ensure!(
+					!VFELoans::<T>::contains_key(parent_brand_id, parent_item_id),
+					Error::<T>::VFELent
+				);
+				ensure!(
+					!StakedVFEs::<T>::contains_key(parent_brand_id, parent_item_id),
+					Error::<T>::VFEStaked
+				);
+				let vfe = VFEDetails::<T>::get(parent_brand_id, parent_item_id)
+					.ok_or(Error::<T>::VFENotExist)?;
+				ensure!(!vfe.is_upgrading, Error::<T>::VFEUpgrading);
+				ensure!(vfe.device_key.is_none(), Error::<T>::VFEBond);
+				ensure!(
+					VFESockets::<T>::get(parent_brand_id, parent_item_id).is_empty(),
+					Error::<T>::VFEGemSocketed
+				);
+
+				let breed = VFEBreeds::<T>::get(parent_brand_id, parent_item_id);
+				ensure!(breed.count < T::MaxBreedCount::get(), Error::<T>::BreedCountExceeded);
+				ensure!(now >= breed.cooldown_until, Error::<T>::BreedCooldownNotExpired);
+				breeds_total = breeds_total.saturating_add(breed.count);
+			}
+
+			FusionAllowances::<T>::try_mutate(&brand_id, |remaining| -> DispatchResult {
+				*remaining =
+					remaining.checked_sub(1).ok_or(Error::<T>::FusionAllowanceExhausted)?;
+				Ok(())
+			})?;
+
+			// fusion should burn token
+			let cost = T::FusionCost::get().saturating_mul((breeds_total as u32 + 1).into());
+			let incentive_token =
+				IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?;
+			T::Currencies::burn_from(incentive_token, &who, cost)?;
+
+			// put the parents on cooldown
+			for (parent_brand_id, parent_item_id) in parents.iter() {
+				VFEBreeds::<T>::mutate(parent_brand_id, parent_item_id, |breed| {
+					breed.count = breed.count.saturating_add(1);
+					breed.cooldown_until = now.saturating_add(T::BreedCooldown::get());
+				});
+			}
+
+			let vfe = VFEDetails::<T>::get(brand_id, item_id).ok_or(Error::<T>::VFENotExist)?;
+			let mate =
+				VFEDetails::<T>::get(mate_brand_id, mate_item_id).ok_or(Error::<T>::VFENotExist)?;
+			let child = Self::breed_vfe(&brand_id, vfe_brand.rarity, &who, &vfe, &mate)?;
+			VFEDetails::<T>::insert(&child.brand_id, &child.item_id, child);
+			Self::deposit_event(Event::VFECreated { owner: who.clone(), detail: child });
+
+			Self::deposit_event(Event::VFEFused {
+				owner: who,
+				brand_id: child.brand_id,
+				item_id: child.item_id,
+				parents,
+				cost,
+			});
+			Ok(())
+		}
+
+		/// transfer vfe
+		/// - origin AccountId
+		/// - class CollectionId
+		/// - instance ItemId
+		/// - Source AccountId
+		#[pallet::weight(< T as pallet_uniques::Config < T::UniquesInstance >>::WeightInfo::transfer())]
+		pub fn transfer(
+			origin: OriginFor<T>,
+			brand_id: T::CollectionId,
+			item_id: T::ItemId,
+			dest: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let from = ensure_signed(origin.clone())?;
+			let to = T::Lookup::lookup(dest.clone())?;
+			let vfe_owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::VFENotExist)?;
+			ensure!(vfe_owner == from, Error::<T>::OperationIsNotAllowed);
+			<Self as NFTTransfer<T::AccountId>>::transfer(&brand_id, &item_id, &to)
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T>
+	where
+		T::CollectionId: From<T::ObjectId>,
+		T::ItemId: From<T::ObjectId>,
+		T::ObjectId: From<T::CollectionId>,
+	{
+		type Call = Call<T>;
+
+		/// Validate unsigned call to this module.
+		///
+		/// By default unsigned transactions are disallowed, but implementing the validator
+		/// here we make sure that some particular calls (the ones produced by offchain worker)
+		/// are being whitelisted and marked as valid.
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			const UNSIGNED_TXS_PRIORITY: u64 = 100;
+			let valid_device_tx = |provide| {
+				ValidTransaction::with_tag_prefix("VFEDevice")
+					.priority(UNSIGNED_TXS_PRIORITY)
+					.and_provides([&provide])
+					.longevity(TransactionLongevity::max_value())
+					.propagate(true)
+					.build()
+			};
+
+			match call.to_owned() {
+				Call::bind_device { from, puk, signature, nonce, bind_item: _bind_item } => {
+					Self::get_verified_device(from, puk, signature.clone(), nonce)
+						.map_err(dispatch_error_to_invalid)?;
+					valid_device_tx((puk, signature))
+				},
+				Call::bind_certified_device {
+					from,
+					puk,
+					signature,
+					nonce,
+					certificate,
+					bind_item: _bind_item,
+				} => {
+					if Devices::<T>::contains_key(puk) {
+						Self::get_verified_device(from, puk, signature.clone(), nonce)
+							.map_err(dispatch_error_to_invalid)?;
+					} else {
+						Self::check_device_certificate(puk, &certificate)
+							.map_err(dispatch_error_to_invalid)?;
+						let flag =
+							Self::verify_bind_device_message(from, nonce, puk, &signature[..])
+								.map_err(dispatch_error_to_invalid)?;
+						if !flag || nonce == 0 {
+							return InvalidTransaction::BadProof.into()
+						}
+					}
+					valid_device_tx((puk, signature))
+				},
+				Call::bind_batch_device {
+					from,
+					puk,
+					signature,
+					nonce,
+					batch_proof,
+					bind_item: _bind_item,
+				} => {
+					if Devices::<T>::contains_key(puk) {
+						Self::get_verified_device(from, puk, signature.clone(), nonce)
+							.map_err(dispatch_error_to_invalid)?;
+					} else {
+						Self::check_batch_device_proof(puk, &batch_proof)
+							.map_err(dispatch_error_to_invalid)?;
+						let flag =
+							Self::verify_bind_device_message(from, nonce, puk, &signature[..])
+								.map_err(dispatch_error_to_invalid)?;
+						if !flag || nonce == 0 {
+							return InvalidTransaction::BadProof.into()
+						}
+					}
+					valid_device_tx((puk, signature))
+				},
+				Call::upload_training_report { device_pk, report_sig, report_data } => {
+					Self::check_device_training_report(device_pk, report_sig.clone(), report_data)
+						.map_err(dispatch_error_to_invalid)?;
+					valid_device_tx((device_pk, report_sig))
+				},
+
+				_ => InvalidTransaction::Call.into(),
+			}
+		}
+	}
+}
+
+impl<T: Config> Pallet<T>
+where
+	T::CollectionId: From<T::ObjectId>,
+	T::ItemId: From<T::ObjectId>,
+	T::ObjectId: From<T::CollectionId>,
+{
+	fn max_generate_random() -> u32 {
+		1000
+	}
+
+	pub fn do_mint(
+		brand_id: T::CollectionId,
+		item_id: T::ItemId,
+		owner: T::AccountId,
+	) -> DispatchResult {
+		pallet_uniques::Pallet::<T, T::UniquesInstance>::mint_into(&brand_id, &item_id, &owner)?;
+		Self::deposit_event(Event::Issued { brand_id, item_id, owner });
+		Ok(())
+	}
+
+	pub fn do_burn(brand_id: T::CollectionId, item_id: T::ItemId) -> DispatchResult {
+		let owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::ItemNotFound)?;
+		ensure!(!VFELoans::<T>::contains_key(&brand_id, &item_id), Error::<T>::VFELent);
+		ensure!(!StakedVFEs::<T>::contains_key(&brand_id, &item_id), Error::<T>::VFEStaked);
+		// the socketed gems are returned to the owner, and the permanent gems are burned
+		for (gem_collection_id, gem_item_id) in VFESockets::<T>::take(&brand_id, &item_id) {
+			match GemDetails::<T>::get(&gem_collection_id, &gem_item_id) {
+				Some(gem) if gem.permanent => {
+					T::Gems::burn(&gem_collection_id, &gem_item_id, None)?;
+					GemDetails::<T>::remove(&gem_collection_id, &gem_item_id);
+				},
+				_ => T::Gems::transfer(&gem_collection_id, &gem_item_id, &owner)?,
+			}
+		}
+		VFEWear::<T>::remove(&brand_id, &item_id);
+		<pallet_uniques::Pallet<T, T::UniquesInstance> as Mutate<T::AccountId>>::burn(
+			&brand_id, &item_id, None,
+		)?;
+		Self::deposit_event(Event::Burned { brand_id, item_id, owner });
+		Ok(())
+	}
+
+	/// The account ID of the pallet.
+	///
+	/// This actually does computation. If you need to keep using it, then make sure you cache the
+	/// value and only call this once.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account_truncating()
+	}
+
+	/// The account ID of the Producer.
+	pub fn into_account_id(id: T::ObjectId) -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating(id)
+	}
+
+	/// The account ID of the brand pool.
+	pub fn brand_pool_account(brand_id: &T::CollectionId) -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating((b"brnd", brand_id))
+	}
+
+	// pay the training rewards from the brand pool, and return the rewards should be minted in
+	// the incentive token
+	fn pay_brand_rewards(
+		brand_id: &T::CollectionId,
+		owner: &T::AccountId,
+		award: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let config = match BrandRewardConfigs::<T>::get(brand_id) {
+			Some(config) => config,
+			None => return Ok(award),
+		};
+
+		// the rewards are converted into the asset by the rate of brand, and stop when the pool
+		// is exhausted
+		let amount: BalanceOf<T> =
+			config.rate.saturating_mul_int(award.saturated_into::<u128>()).saturated_into();
+		let pool = Self::brand_pool_account(brand_id);
+		let paid = T::Currencies::reducible_balance(config.asset_id, &pool, false).min(amount);
+		if !paid.is_zero() {
+			<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+				config.asset_id,
+				&pool,
+				owner,
+				paid,
+				false,
+			)?;
+			Self::deposit_event(Event::BrandRewardsPaid {
+				owner: owner.clone(),
+				brand_id: *brand_id,
+				asset_id: config.asset_id,
+				amount: paid,
+			});
+		}
+		if paid < amount {
+			Self::deposit_event(Event::BrandRewardPoolExhausted { brand_id: *brand_id });
+		}
+
+		Ok(match config.mode {
+			BrandRewardMode::Fallback if amount.is_zero() => award,
+			// the incentive token is minted for the share of the rewards the pool can not cover
+			BrandRewardMode::Fallback => {
+				let covered = FixedU128::saturating_from_rational(
+					paid.saturated_into::<u128>(),
+					amount.saturated_into::<u128>(),
+				)
+				.saturating_mul_int(award.saturated_into::<u128>());
+				award.saturating_sub(covered.saturated_into())
+			},
+			BrandRewardMode::Combine => award,
+		})
+	}
+
+	fn get_and_increment_nonce() -> Vec<u8> {
+		let nonce = Nonce::<T>::get();
+		Nonce::<T>::put(nonce.wrapping_add(1));
+		nonce.encode()
+	}
+
+	fn generate_random_number() -> (u32, T::Hash, T::BlockNumber) {
+		let nonce = Self::get_and_increment_nonce();
+		let (random_seed, block_number) = T::Randomness::random(&nonce);
+		let random_number = <u32>::decode(&mut random_seed.as_ref())
+			.expect("secure hashes should always be bigger than u32; qed");
+		(random_number, random_seed, block_number)
+	}
+
+	// Randomly value from among the total number.
+	fn random_value(total: u16) -> u16 {
+		if total == 0 {
+			return 0
+		}
+		let (mut random_number, _, _) = Self::generate_random_number();
+
+		// Best effort attempt to remove bias from modulus operator.
+		for _ in 1..Self::max_generate_random() {
+			if random_number < u32::MAX - u32::MAX % (total as u32) {
+				break
+			}
+
+			let (random_number2, _, _) = Self::generate_random_number();
+			random_number = random_number2;
+		}
+
+		(random_number as u16) % total
+	}
+
+	// verify the signature of the device over the message
+	fn verify_device_signature(
+		puk: DeviceKey,
+		msg: &[u8],
+		signature: &[u8],
+	) -> Result<bool, DispatchError> {
+		let verify_key = VerifyingKey::from_sec1_bytes(puk.as_ref())
+			.map_err(|_| Error::<T>::PublicKeyEncodeError)?;
+
+		let sig =
+			Signature::from_bytes(signature).map_err(|_| Error::<T>::DeviceSignatureInvalid)?;
+
+		// check the validity of the signature
+		Ok(verify_key.verify(msg, &sig).is_ok())
+	}
+
+	// verify the device certificate signed by the certificate key of producer
+	fn check_device_certificate(
+		puk: DeviceKey,
+		certificate: &DeviceCertificate<
+			T::ObjectId,
+			T::CollectionId,
+			BoundedVec<u8, T::StringLimit>,
+		>,
+	) -> DispatchResult {
+		let cert_key = ProducerCertKeys::<T>::get(certificate.producer_id)
+			.ok_or(Error::<T>::ProducerCertKeyNotSet)?;
+		let msg = (puk, certificate.brand_id).encode();
+		let flag = Self::verify_device_signature(cert_key, &msg, &certificate.signature[..])
+			.map_err(|_| Error::<T>::DeviceCertificateInvalid)?;
+		ensure!(flag, Error::<T>::DeviceCertificateInvalid);
+		Ok(())
+	}
+
+	// verify the Merkle proof of the device key and return the batch
+	fn check_batch_device_proof(
+		puk: DeviceKey,
+		batch_proof: &DeviceBatchProof<
+			T::CollectionId,
+			T::Hash,
+			BoundedVec<T::Hash, T::MaxMerkleProofLength>,
+		>,
+	) -> Result<DeviceBatch<T::ObjectId, AssetIdOf<T>, BalanceOf<T>>, DispatchError> {
+		let batch = DeviceBatches::<T>::get(&batch_proof.brand_id, &batch_proof.root)
+			.ok_or(Error::<T>::DeviceBatchNotFound)?;
+		ensure!(batch.claimed < batch.count, Error::<T>::DeviceBatchExhausted);
+
+		// the pairs are sorted before hashing, so that the proof does not need the positions
+		let leaf = T::Hashing::hash(puk.as_ref());
+		let root = batch_proof.proof.iter().fold(leaf, |node, sibling| {
+			let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+			T::Hashing::hash_of(&(left, right))
+		});
+		ensure!(root == batch_proof.root, Error::<T>::MerkleProofInvalid);
+		Ok(batch)
+	}
+
+	// claim the device of the batch and register it with the minting fee locked by the batch
+	fn claim_batch_device(
+		puk: DeviceKey,
+		batch_proof: &DeviceBatchProof<
+			T::CollectionId,
+			T::Hash,
+			BoundedVec<T::Hash, T::MaxMerkleProofLength>,
+		>,
+	) -> DispatchResult {
+		let mut batch = Self::check_batch_device_proof(puk, batch_proof)?;
+		let brand_id = batch_proof.brand_id;
+		let vfe_brand = VFEBrands::<T>::get(brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
+
+		batch.claimed = batch.claimed.saturating_add(1);
+		DeviceBatches::<T>::insert(&brand_id, &batch_proof.root, batch);
+		Devices::<T>::insert(
+			puk,
+			Device {
+				brand_id,
+				item_id: None,
+				producer_id: batch.producer_id,
+				status: DeviceStatus::Registered,
+				pk: puk,
+				nonce: 0u32,
+				sport_type: vfe_brand.sport_type,
+				timestamp: 0u32,
+				mint_cost: batch.mint_cost,
+			},
+		);
+
+		Self::deposit_event(Event::DeviceBatchClaimed {
+			device_key: puk,
+			brand_id,
+			root: batch_proof.root,
+		});
+		Ok(())
+	}
+
+	fn verify_bind_device_message(
+		account: T::AccountId,
+		nonce: u32,
+		puk: DeviceKey,
+		signature: &[u8],
+	) -> Result<bool, DispatchError> {
+		let verify_key = VerifyingKey::from_sec1_bytes(puk.as_ref())
+			.map_err(|_| Error::<T>::PublicKeyEncodeError)?;
+
+		let sig =
+			Signature::from_bytes(signature).map_err(|_| Error::<T>::DeviceSignatureInvalid)?;
+
+		let account_nonce = nonce.to_le_bytes().to_vec();
+		let account_rip160 = Ripemd::Hash::hash(account.encode().as_ref());
+
+		let mut msg: Vec<u8> = Vec::new();
+		msg.extend(account_nonce);
+		msg.extend(account_rip160.to_vec());
+
+		// check the validity of the signature
+		let flag = verify_key.verify(&msg, &sig).is_ok();
+
+		Ok(flag)
+	}
+
+	// verifty the device binding signature and return device.
+	fn get_verified_device(
+		account: T::AccountId,
+		puk: DeviceKey,
+		signature: BoundedVec<u8, T::StringLimit>,
+		nonce: u32,
+	) -> Result<
+		Device<T::CollectionId, T::ItemId, T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
+		DispatchError,
+	> {
+		// get the producer owner
+		let device = Devices::<T>::get(puk).ok_or(Error::<T>::DeviceNotExisted)?;
+
+		ensure!(device.status != DeviceStatus::Voided, Error::<T>::DeviceVoided);
+
+		let flag = Self::verify_bind_device_message(account, nonce, puk, &signature[..])?;
+
+		ensure!(flag, Error::<T>::DeviceSignatureInvalid);
+		// check the nonce
+		ensure!(nonce > device.nonce, Error::<T>::NonceMustGreatThanBefore);
+
+		Ok(device)
+	}
+
+	// check the device's training report.
+	fn check_device_training_report(
+		puk: DeviceKey,
+		req_sig: BoundedVec<u8, T::StringLimit>,
+		msg: BoundedVec<u8, T::StringLimit>,
+	) -> Result<
+		Device<T::CollectionId, T::ItemId, T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
+		DispatchError,
+	> {
+		// get the producer owner
+		let device = Devices::<T>::get(puk).ok_or(Error::<T>::DeviceNotExisted)?;
+
+		ensure!(device.status != DeviceStatus::Voided, Error::<T>::DeviceVoided);
+		ensure!(device.item_id.is_some(), Error::<T>::DeviceNotBond);
+
+		let target = &req_sig[..];
+		let sig = Signature::from_bytes(target).map_err(|_| Error::<T>::DeviceSignatureInvalid)?;
+
+		let verify_key = VerifyingKey::from_sec1_bytes(puk.as_ref())
+			.map_err(|_| Error::<T>::PublicKeyEncodeError)?;
+
+		// check the validity of the signature
+		let final_msg: &[u8] = msg.as_ref();
+		let flag = verify_key.verify(final_msg, &sig).is_ok();
+
+		ensure!(flag, Error::<T>::DeviceSignatureInvalid);
+
+		Ok(device)
+	}
+
+	// handler report data to get rewards
+	fn handler_report_data(
+		device: &mut Device<T::CollectionId, T::ItemId, T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
+		report_data: BoundedVec<u8, T::StringLimit>,
+	) -> Result<(), DispatchError> {
+		let brand_id = device.brand_id;
+		let item_id = device.item_id.ok_or(Error::<T>::DeviceNotBond)?;
+		// settle the finished upgrade first
+		Self::try_complete_level_up(&brand_id, &item_id)?;
+		let sport =
+			Self::sport_type_info(device.sport_type).ok_or(Error::<T>::SportTypeNotFound)?;
+		let sport_type = sport.report_type;
+		// the borrower trains with the lent VFE
+		let account = Self::vfe_controller(&brand_id, &item_id)?;
+
+		// First try to restore user energy and daily earning cap.
+		Self::_restore_energy(&account)?;
+		Self::_reset_daily_earned(&account)?;
+
+		let report_data = report_data.into_inner();
+		let training = match TrainingReportEnvelope::try_from(report_data.clone()) {
+			Ok(envelope) => {
+				ensure!(envelope.sport_type == sport_type, Error::<T>::ReportSportTypeMismatch);
+				Self::decode_training_report(sport_type, envelope.version, envelope.payload)?
+			},
+			// the legacy report is a raw payload with the exact length of the sport type
+			Err(_) => {
+				let report_len = match sport_type {
+					SportType::JumpRope => 17,
+					SportType::Running => 18,
+					SportType::Riding => 16,
+				};
+				ensure!(report_data.len() == report_len, Error::<T>::ValueInvalid);
+				Self::decode_training_report(
+					sport_type,
+					TrainingReportEnvelope::VERSION_1,
+					report_data,
+				)?
+			},
+		};
+
+		Self::reward_training(device, account, sport, training)
+	}
+
+	// decode the training report payload of the sport type and version.
+	fn decode_training_report(
+		sport_type: SportType,
+		version: u8,
+		payload: Vec<u8>,
+	) -> Result<TrainingSummary, DispatchError> {
+		let training = match (sport_type, version) {
+			(SportType::JumpRope, TrainingReportEnvelope::VERSION_1) => {
+				let training_report = JumpRopeTrainingReport::try_from(payload)
+					.map_err(|_| Error::<T>::ValueInvalid)?;
+				TrainingSummary::from(training_report)
+			},
+			(SportType::Running, TrainingReportEnvelope::VERSION_1) => {
+				let training_report = RunningTrainingReport::try_from(payload)
+					.map_err(|_| Error::<T>::ValueInvalid)?;
+				ensure!(training_report.is_plausible(), Error::<T>::TrainingReportOutOfNormalRange);
+				TrainingSummary::from(training_report)
+			},
+			(SportType::Riding, TrainingReportEnvelope::VERSION_1) => {
+				let training_report = RidingTrainingReport::try_from(payload)
+					.map_err(|_| Error::<T>::ValueInvalid)?;
+				ensure!(training_report.is_plausible(), Error::<T>::TrainingReportOutOfNormalRange);
+				TrainingSummary::from(training_report)
+			},
+			_ => return Err(Error::<T>::UnsupportedReportVersion.into()),
+		};
+		Ok(training)
+	}
+
+	// calculate the rewards of the training and update user, vfe and device.
+	fn reward_training(
+		device: &mut Device<T::CollectionId, T::ItemId, T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
+		account: T::AccountId,
+		sport: SportTypeInfo,
+		training: TrainingSummary,
+	) -> Result<(), DispatchError> {
+		let brand_id = device.brand_id;
+		let item_id = device.item_id.ok_or(Error::<T>::DeviceNotBond)?;
+		let sport_type = device.sport_type;
+
+		// Check whether data is submitted repeatedly
+		ensure!(training.timestamp > device.timestamp, Error::<T>::ValueInvalid);
+		let now = T::UnixTime::now().as_secs();
+		let expired_time = training.timestamp + T::ReportValidityPeriod::get();
+		ensure!(now >= training.timestamp as u64, Error::<T>::ValueInvalid);
+		ensure!(now <= expired_time as u64, Error::<T>::TrainingReportTimeExpired);
+		let quarantined = Self::check_plausibility(device, &training);
+
+		let mut vfe = VFEDetails::<T>::get(brand_id, item_id).ok_or(Error::<T>::VFENotExist)?;
+
+		let mut user = Self::find_user(&account);
+
+		ensure!(user.energy > 0, Error::<T>::EnergyExhausted);
+
+		// check if earned cap
+		ensure!(user.earned < user.earning_cap, Error::<T>::EarnedCap);
+
+		// Power consumption = training-duration / training_unit_duration
+		let mut power_used = training.effective_duration / sport.training_unit_duration;
+
+		// Check if the training is enough
+		ensure!(power_used > 0, Error::<T>::InsufficientTraining);
+
+		// check the user energy
+		if power_used > user.energy {
+			power_used = user.energy;
+		}
+
+		// check if VFE remaining battery is enough
+		ensure!(vfe.remaining_battery > 0, Error::<T>::LowBattery);
+
+		// check if VFE is worn out
+		let wear = VFEWear::<T>::get(brand_id, item_id);
+		let condition = Permill::one() - wear;
+		ensure!(condition != Permill::zero(), Error::<T>::VFEWornOut);
+
+		// check the vfe electric
+		if power_used > vfe.remaining_battery {
+			power_used = vfe.remaining_battery;
+		}
+
+		// update user energy and vfe remaining battery
+		user.energy -= power_used;
+		vfe.remaining_battery -= power_used;
+
+		// the abilities are calculated in the wider integers, so that the boosted abilities do
+		// not overflow
+		let skill = vfe.current_ability.skill as u32;
+		let r_luck = Self::random_value(vfe.current_ability.luck) as u32 + 1;
+		let r_skill = (skill * training.performance as u32) /
+			((training.interruptions as u32 + 1) * sport.frequency_standard as u32);
+		let s = if skill > r_skill {
+			skill - Self::random_value((skill - r_skill) as u16) as u32
+		} else {
+			let range = (r_skill - skill).min(u16::MAX as u32) as u16;
+			skill + Self::random_value(range) as u32
+		};
+
+		let f = sport.is_frequency_range(training.average_frequency);
+		ensure!(f > 0, Error::<T>::TrainingReportOutOfNormalRange);
+
+		let e = vfe.current_ability.efficiency as u64;
+
+		let training_volume = (e + s as u64 + 2 * r_luck as u64)
+			.checked_mul(power_used as u64)
+			.and_then(|volume| volume.checked_mul(f as u64))
+			.ok_or(Error::<T>::ValueOverflow)?;
+		// the cost unit decreases with the share of the period's budget already minted
+		let mut emission = Self::current_emission();
+		let remaining = emission.budget.saturating_sub(emission.minted);
+		let cost_unit = Self::economic_params().cost_unit;
+		let cost_unit = cost_unit.saturating_sub(
+			Permill::from_rational(emission.minted, emission.budget).mul(cost_unit),
+		);
+		let final_award = BalanceOf::<T>::saturated_from(training_volume)
+			.saturating_mul(cost_unit)
+			.min(remaining);
+
+		// the rewards are reduced in proportion when the condition of VFE is poor
+		let threshold = T::WearPenaltyThreshold::get();
+		let final_award = if condition < threshold {
+			Permill::from_rational(condition.deconstruct(), threshold.deconstruct())
+				.mul(final_award)
+		} else {
+			final_award
+		};
+
+		// the members of the team which reached the goal in the last period earn the bonus
+		let final_award = if Self::team_bonus_active(&account) {
+			final_award
+				.saturating_add(T::TeamBonus::get().mul_floor(final_award))
+				.min(remaining)
+		} else {
+			final_award
+		};
+
+		// the training wears VFE, and the VFE with higher durable wears slower
+		let durable = vfe.current_ability.durable.max(1) as u32;
+		let wear_increase = Permill::from_parts(
+			T::WearPerEnergy::get().deconstruct().saturating_mul(power_used as u32) / durable,
+		);
+
+		//save user earned
+		let earned = final_award.saturating_add(user.earned);
+		let actual_award = if earned > user.earning_cap {
+			user.earned = user.earning_cap;
+			user.earning_cap.saturating_sub(user.earned)
+		} else {
+			user.earned = earned;
+			final_award
+		};
+
+		// update the electric with user and vfe and device.
+		device.timestamp = training.timestamp;
+		Devices::<T>::insert(device.pk, device);
+		Users::<T>::insert(account.clone(), user);
+		VFEDetails::<T>::insert(brand_id, item_id, vfe);
+		VFEWear::<T>::insert(brand_id, item_id, wear.saturating_add(wear_increase));
+
+		let reward_asset_id = IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?;
+		let pending =
+			!quarantined && !T::ChallengeWindow::get().is_zero() && !actual_award.is_zero();
+		// the referral period is checked at the time of the report
+		let reported_at = frame_system::Pallet::<T>::block_number();
+		let referral = Self::in_referral_period(&account, reported_at);
+		let minted_award = if quarantined {
+			// the held rewards take the budget of emission until the quarantine is cleared
+			if !actual_award.is_zero() {
+				HeldRewards::<T>::insert(
+					device.pk,
+					training.timestamp,
+					HeldReport {
+						owner: account.clone(),
+						amount: actual_award,
+						reported_at,
+						referral,
+					},
+				);
+			}
+			Self::deposit_event(Event::TrainingRewardsHeld {
+				owner: account.clone(),
+				device_key: device.pk,
+				amount: actual_award,
+			});
+			emission.minted = emission.minted.saturating_add(actual_award);
+			Zero::zero()
+		} else if pending {
+			// the rewards are held until the challenge window expires
+			let deadline = reported_at.saturating_add(T::ChallengeWindow::get());
+			PendingReports::<T>::insert(
+				device.pk,
+				training.timestamp,
+				PendingReport {
+					owner: account.clone(),
+					brand_id,
+					item_id,
+					amount: actual_award,
+					reported_at,
+					challenger: None,
+					deadline,
+				},
+			);
+			Self::deposit_event(Event::TrainingRewardsPending {
+				owner: account.clone(),
+				device_key: device.pk,
+				timestamp: training.timestamp,
+				amount: actual_award,
+				deadline,
+			});
+			emission.minted = emission.minted.saturating_add(actual_award);
+			Zero::zero()
+		} else {
+			let minted_award =
+				Self::pay_training_rewards(&brand_id, Some(item_id), &account, actual_award)?;
+			emission.minted = emission.minted.saturating_add(minted_award);
+			minted_award
+		};
+		Emission::<T>::put(emission);
+		if !quarantined && !pending && referral {
+			Self::pay_referral_rewards(&account, actual_award)?;
+		}
+		if !quarantined {
+			let volume = training_volume;
+			Self::update_leaderboard(&LeaderboardKey::Sport(sport_type), &account, volume);
+			Self::update_leaderboard(&LeaderboardKey::Brand(brand_id), &account, volume);
+			Self::update_brand_challenges(&brand_id, &item_id, &account, &training);
+			Self::update_team_progress(&account, volume);
+		}
+		Self::record_training_history(&account, sport_type, &training, power_used, actual_award);
+
+		Self::deposit_event(Event::TrainingReportsAndRewards {
+			owner: account,
+			brand_id,
+			item_id,
+			sport_type,
+			training_time: training.timestamp,
+			training_duration: training.training_duration,
+			training_count: training.training_count,
+			energy_used: power_used,
+			asset_id: reward_asset_id,
+			rewards: minted_award,
+		});
 
-	pub fn do_burn(brand_id: T::CollectionId, item_id: T::ItemId) -> DispatchResult {
-		let owner = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::ItemNotFound)?;
-		<pallet_uniques::Pallet<T, T::UniquesInstance> as Mutate<T::AccountId>>::burn(
-			&brand_id, &item_id, None,
-		)?;
-		Self::deposit_event(Event::Burned { brand_id, item_id, owner });
 		Ok(())
 	}
 
-	/// The account ID of the pallet.
-	///
-	/// This actually does computation. If you need to keep using it, then make sure you cache the
-	/// value and only call this once.
-	pub fn account_id() -> T::AccountId {
-		T::PalletId::get().into_account_truncating()
+	fn do_remove_team_member(
+		team_id: u32,
+		mut team: Team<T::AccountId, T::MaxTeamMembers>,
+		who: T::AccountId,
+	) -> DispatchResult {
+		team.members.retain(|member| *member != who);
+		Teams::<T>::insert(team_id, team);
+		UserTeam::<T>::remove(&who);
+
+		Self::deposit_event(Event::TeamLeft { team_id, who });
+		Ok(())
 	}
 
-	/// The account ID of the Producer.
-	pub fn into_account_id(id: T::ObjectId) -> T::AccountId {
-		T::PalletId::get().into_sub_account_truncating(id)
+	// the index of the current period of the team goal
+	fn team_period() -> u32 {
+		let now = frame_system::Pallet::<T>::block_number();
+		(now / T::TeamGoalPeriod::get().max(One::one())).saturated_into::<u32>()
 	}
 
-	fn get_and_increment_nonce() -> Vec<u8> {
-		let nonce = Nonce::<T>::get();
-		Nonce::<T>::put(nonce.wrapping_add(1));
-		nonce.encode()
+	// whether the team of the user reached the goal in the last period
+	fn team_bonus_active(who: &T::AccountId) -> bool {
+		match UserTeam::<T>::get(who) {
+			Some(team_id) => TeamProgresses::<T>::get(team_id)
+				.achieved
+				.map_or(false, |period| period.saturating_add(1) == Self::team_period()),
+			None => false,
+		}
 	}
 
-	fn generate_random_number() -> (u32, T::Hash, T::BlockNumber) {
-		let nonce = Self::get_and_increment_nonce();
-		let (random_seed, block_number) = T::Randomness::random(&nonce);
-		let random_number = <u32>::decode(&mut random_seed.as_ref())
-			.expect("secure hashes should always be bigger than u32; qed");
-		(random_number, random_seed, block_number)
+	// add the training volume of the member to the team progress
+	fn update_team_progress(who: &T::AccountId, volume: u64) {
+		let team_id = match UserTeam::<T>::get(who) {
+			Some(team_id) => team_id,
+			None => return,
+		};
+		let team = match Teams::<T>::get(team_id) {
+			Some(team) => team,
+			None => return,
+		};
+		let period = Self::team_period();
+		let goal = T::TeamGoalPerMember::get().saturating_mul(team.members.len() as u64);
+		let reached = TeamProgresses::<T>::mutate(team_id, |progress| {
+			if progress.period != period {
+				progress.period = period;
+				progress.volume = 0;
+			}
+			progress.volume = progress.volume.saturating_add(volume);
+			if progress.achieved != Some(period) && progress.volume >= goal {
+				progress.achieved = Some(period);
+				return true
+			}
+			false
+		});
+		if reached {
+			Self::deposit_event(Event::TeamGoalReached { team_id, period });
+		}
 	}
 
-	// Randomly value from among the total number.
-	fn random_value(total: u16) -> u16 {
-		let (mut random_number, _, _) = Self::generate_random_number();
+	/// Get the details of the team.
+	pub fn get_team_summary(team_id: u32) -> Option<TeamSummary<T::AccountId>> {
+		let team = Teams::<T>::get(team_id)?;
+		let period = Self::team_period();
+		let progress = TeamProgresses::<T>::get(team_id);
+		Some(TeamSummary {
+			team_id,
+			captain: team.captain,
+			volume: if progress.period == period { progress.volume } else { 0 },
+			goal: T::TeamGoalPerMember::get().saturating_mul(team.members.len() as u64),
+			bonus_active: progress.achieved.map_or(false, |p| p.saturating_add(1) == period),
+			members: team.members.into_inner(),
+		})
+	}
 
-		// Best effort attempt to remove bias from modulus operator.
-		for _ in 1..Self::max_generate_random() {
-			if random_number < u32::MAX - u32::MAX % (total as u32) {
-				break
+	/// Get the details of the team which the user is in.
+	pub fn get_user_team_summary(who: T::AccountId) -> Option<TeamSummary<T::AccountId>> {
+		UserTeam::<T>::get(who).and_then(Self::get_team_summary)
+	}
+
+	/// The account of the prize pool of the brand challenge.
+	pub fn brand_challenge_account(challenge_id: u32) -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating((b"chlg", challenge_id))
+	}
+
+	// add the training to the progress of the running brand challenges joined with the VFE
+	fn update_brand_challenges(
+		brand_id: &T::CollectionId,
+		item_id: &T::ItemId,
+		who: &T::AccountId,
+		training: &TrainingSummary,
+	) {
+		let now = frame_system::Pallet::<T>::block_number();
+		for challenge_id in UnsettledBrandChallenges::<T>::get(brand_id) {
+			let mut challenge = match BrandChallenges::<T>::get(challenge_id) {
+				Some(challenge) => challenge,
+				None => continue,
+			};
+			if now < challenge.terms.start || now > challenge.terms.end {
+				continue
+			}
+			let completed =
+				ChallengeEntries::<T>::mutate(challenge_id, who, |maybe_entry| match maybe_entry {
+					Some(entry) if entry.item_id == *item_id => {
+						let reached = entry.progress >= challenge.terms.target;
+						entry.progress =
+							entry.progress.saturating_add(challenge.terms.progress_of(training));
+						!reached && entry.progress >= challenge.terms.target
+					},
+					_ => false,
+				});
+			if completed {
+				challenge.completed = challenge.completed.saturating_add(1);
+				BrandChallenges::<T>::insert(challenge_id, challenge);
+				Self::deposit_event(Event::BrandChallengeCompleted {
+					challenge_id,
+					who: who.clone(),
+				});
 			}
+		}
+	}
 
-			let (random_number2, _, _) = Self::generate_random_number();
-			random_number = random_number2;
+	// ensure the origin may manage the leaderboard
+	fn ensure_leaderboard_manager(
+		origin: OriginFor<T>,
+		key: &LeaderboardKey<T::CollectionId>,
+	) -> DispatchResult {
+		match key {
+			LeaderboardKey::Sport(_) => {
+				T::GovernanceOrigin::ensure_origin(origin)?;
+			},
+			LeaderboardKey::Brand(brand_id) => {
+				let who = ensure_signed(origin)?;
+				let brand_owner =
+					Self::collection_owner(brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
+				ensure!(brand_owner == who, Error::<T>::OperationIsNotAllowed);
+			},
+		}
+		Ok(())
+	}
+
+	/// The account of the prize pool of the leaderboard.
+	pub fn leaderboard_pool_account(key: &LeaderboardKey<T::CollectionId>) -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating((b"lbrd", key))
+	}
+
+	// the index of the current period of the leaderboard
+	fn leaderboard_period(
+		info: &LeaderboardInfo<AssetIdOf<T>, T::BlockNumber, T::LeaderboardSize>,
+	) -> u32 {
+		let now = frame_system::Pallet::<T>::block_number();
+		(now / info.period).saturated_into::<u32>()
+	}
+
+	// return the standings of the current period of the leaderboard, and keep the top ranks of
+	// the ended period to be settled by `settle_leaderboard` with the prizes reserved from the
+	// prize pool which is not reserved by the other ended periods
+	fn current_leaderboard_standings(
+		key: &LeaderboardKey<T::CollectionId>,
+		info: &LeaderboardInfo<AssetIdOf<T>, T::BlockNumber, T::LeaderboardSize>,
+	) -> LeaderboardRanks<T::AccountId, T::LeaderboardSize> {
+		let period = Self::leaderboard_period(info);
+		match LeaderboardStandings::<T>::get(key) {
+			Some(standings) if standings.period == period => return standings,
+			Some(standings) if !standings.ranks.is_empty() => {
+				let pool = Self::leaderboard_pool_account(key);
+				let prizes = LeaderboardReservedPrizes::<T>::mutate(key, |reserved| {
+					let prizes = T::Currencies::reducible_balance(info.asset_id, &pool, false)
+						.saturating_sub(*reserved);
+					*reserved = reserved.saturating_add(prizes);
+					prizes
+				});
+				EndedLeaderboardStandings::<T>::insert(
+					key,
+					standings.period,
+					EndedLeaderboardRanks { ranks: standings.ranks, prizes },
+				);
+			},
+			_ => {},
 		}
+		LeaderboardRanks { period, ranks: Default::default() }
+	}
 
-		(random_number as u16) % total
+	// add the training volume of the user to the leaderboard and update the top ranks
+	fn update_leaderboard(key: &LeaderboardKey<T::CollectionId>, who: &T::AccountId, volume: u64) {
+		let info = match Leaderboards::<T>::get(key) {
+			Some(info) => info,
+			None => return,
+		};
+		let mut standings = Self::current_leaderboard_standings(key, &info);
+		let period = standings.period;
+		let score = LeaderboardScores::<T>::mutate(key, who, |(last_period, score)| {
+			if *last_period != period {
+				*last_period = period;
+				*score = 0;
+			}
+			*score = score.saturating_add(volume);
+			*score
+		});
+
+		standings.ranks.retain(|(account, _)| account != who);
+		let index = standings.ranks.iter().position(|(_, v)| *v < score);
+		let index = index.unwrap_or(standings.ranks.len());
+		if index < T::LeaderboardSize::get() as usize {
+			if standings.ranks.len() >= T::LeaderboardSize::get() as usize {
+				standings.ranks.remove(standings.ranks.len() - 1);
+			}
+			// there is room for the user after removing the last rank
+			let _ = standings.ranks.try_insert(index, (who.clone(), score));
+		}
+		LeaderboardStandings::<T>::insert(key, standings);
 	}
 
-	fn verify_bind_device_message(
-		account: T::AccountId,
-		nonce: u32,
+	/// Get the top ranks of the leaderboard in the current period.
+	pub fn get_leaderboard(key: LeaderboardKey<T::CollectionId>) -> Vec<(T::AccountId, u64)> {
+		let info = match Leaderboards::<T>::get(&key) {
+			Some(info) => info,
+			None => return Vec::new(),
+		};
+		match LeaderboardStandings::<T>::get(&key) {
+			Some(standings) if standings.period == Self::leaderboard_period(&info) =>
+				standings.ranks.into_inner(),
+			_ => Vec::new(),
+		}
+	}
+
+	// revert the rewards of the invalid or discarded report, which are counted in the daily earned
+	// and the emission of the period when it is reported, and in the training history
+	fn revert_report_rewards(
 		puk: DeviceKey,
-		signature: &[u8],
-	) -> Result<bool, DispatchError> {
-		let verify_key = VerifyingKey::from_sec1_bytes(puk.as_ref())
-			.map_err(|_| Error::<T>::PublicKeyEncodeError)?;
+		timestamp: u32,
+		owner: &T::AccountId,
+		amount: BalanceOf<T>,
+		reported_at: T::BlockNumber,
+	) {
+		if LastDailyEarnedReset::<T>::get() <= reported_at {
+			Users::<T>::mutate(owner, |maybe_user| {
+				if let Some(user) = maybe_user.as_mut() {
+					user.earned = user.earned.saturating_sub(amount);
+				}
+			});
+		}
 
-		let sig =
-			Signature::from_bytes(signature).map_err(|_| Error::<T>::DeviceSignatureInvalid)?;
+		let mut emission = Self::current_emission();
+		if emission.period == Self::emission_period(reported_at) {
+			emission.minted = emission.minted.saturating_sub(amount);
+			Emission::<T>::put(emission);
+		}
 
-		let account_nonce = nonce.to_le_bytes().to_vec();
-		let account_rip160 = Ripemd::Hash::hash(account.encode().as_ref());
+		if let Some(device) = Devices::<T>::get(puk) {
+			let day = timestamp / (24 * 60 * 60);
+			TrainingHistory::<T>::mutate(owner, device.sport_type, |history| {
+				if let Some(record) = history.iter_mut().find(|r| r.day == day) {
+					record.rewards = record.rewards.saturating_sub(amount);
+				}
+			});
+		}
+	}
+
+	// add the training to the daily aggregates of the user and prune the expired days
+	fn record_training_history(
+		who: &T::AccountId,
+		sport_type: SportId,
+		training: &TrainingSummary,
+		energy_used: u16,
+		rewards: BalanceOf<T>,
+	) {
+		let max_days = T::MaxHistoryDays::get();
+		let today = (T::UnixTime::now().as_secs() / (24 * 60 * 60)) as u32;
+		let day = training.timestamp / (24 * 60 * 60);
+		if day.saturating_add(max_days) <= today {
+			return
+		}
+
+		TrainingHistory::<T>::mutate(who, sport_type, |history| {
+			history.retain(|r| r.day.saturating_add(max_days) > today);
+			if let Some(record) = history.iter_mut().find(|r| r.day == day) {
+				record.training_duration =
+					record.training_duration.saturating_add(training.training_duration as u32);
+				record.training_count =
+					record.training_count.saturating_add(training.training_count as u32);
+				record.energy_used = record.energy_used.saturating_add(energy_used as u32);
+				record.rewards = record.rewards.saturating_add(rewards);
+				record.reports = record.reports.saturating_add(1);
+			} else {
+				let index = history.iter().position(|r| r.day > day).unwrap_or(history.len());
+				// there is always room after pruning, as one record is kept per day
+				let _ = history.try_insert(
+					index,
+					DailyTraining {
+						day,
+						sport_type,
+						training_duration: training.training_duration as u32,
+						training_count: training.training_count as u32,
+						energy_used: energy_used as u32,
+						rewards,
+						reports: 1,
+					},
+				);
+			}
+		});
+	}
+
+	// pay the training rewards of the VFE, the share of the owner is split from the rewards of the
+	// borrower if the VFE is lent, and return the minted rewards
+	fn pay_training_rewards(
+		brand_id: &T::CollectionId,
+		item_id: Option<T::ItemId>,
+		owner: &T::AccountId,
+		award: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let loan = item_id.and_then(|item_id| {
+			VFELoans::<T>::get(brand_id, item_id)
+				.filter(|loan| loan.borrower.as_ref().map(|(b, _)| b) == Some(owner))
+				.map(|loan| (item_id, loan))
+		});
+		let (item_id, loan) = match loan {
+			Some(loan) => loan,
+			None => return Self::pay_rewards_to(brand_id, owner, award),
+		};
+
+		let share = loan.owner_share.mul_floor(award);
+		let mut minted_award = Self::pay_rewards_to(brand_id, &loan.owner, share)?;
+		minted_award = minted_award.saturating_add(Self::pay_rewards_to(
+			brand_id,
+			owner,
+			award.saturating_sub(share),
+		)?);
+		if !share.is_zero() {
+			Self::deposit_event(Event::VFELoanRewardsPaid {
+				owner: loan.owner,
+				borrower: owner.clone(),
+				brand_id: *brand_id,
+				item_id,
+				amount: share,
+			});
+		}
+		Ok(minted_award)
+	}
+
+	// pay the rewards from the brand pool and mint the rest in the incentive token, and return
+	// the minted rewards
+	fn pay_rewards_to(
+		brand_id: &T::CollectionId,
+		owner: &T::AccountId,
+		award: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let reward_asset_id = IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?;
+		let minted_award = Self::pay_brand_rewards(brand_id, owner, award)?;
+		if !minted_award.is_zero() {
+			T::Currencies::mint_into(reward_asset_id, owner, minted_award)?;
+		}
+		Ok(minted_award)
+	}
 
-		let mut msg: Vec<u8> = Vec::new();
-		msg.extend(account_nonce);
-		msg.extend(account_rip160.to_vec());
+	// whether the training of the referee at the block is in the referral period
+	fn in_referral_period(referee: &T::AccountId, at: T::BlockNumber) -> bool {
+		Referrers::<T>::contains_key(referee) &&
+			Users::<T>::get(referee).map_or(false, |user| {
+				at <= user.create_block.saturating_add(T::ReferralPeriod::get())
+			})
+	}
 
-		// check the validity of the signature
-		let flag = verify_key.verify(&msg, &sig).is_ok();
+	// mint the share of the training rewards reported in the referral period to the referrer,
+	// limited by the max referral rewards and the emission budget
+	fn pay_referral_rewards(referee: &T::AccountId, award: BalanceOf<T>) -> DispatchResult {
+		let referrer = match Referrers::<T>::get(referee) {
+			Some(referrer) => referrer,
+			None => return Ok(()),
+		};
 
-		Ok(flag)
+		let mut emission = Self::current_emission();
+		let earned = ReferralEarnings::<T>::get(&referrer);
+		let amount = T::ReferralShare::get()
+			.mul_floor(award)
+			.min(T::MaxReferralRewards::get().saturating_sub(earned))
+			.min(emission.budget.saturating_sub(emission.minted));
+		if amount.is_zero() {
+			return Ok(())
+		}
+
+		let reward_asset_id = IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?;
+		T::Currencies::mint_into(reward_asset_id, &referrer, amount)?;
+		ReferralEarnings::<T>::insert(&referrer, earned.saturating_add(amount));
+		emission.minted = emission.minted.saturating_add(amount);
+		Emission::<T>::put(emission);
+
+		Self::deposit_event(Event::ReferralRewardsPaid {
+			referrer,
+			referee: referee.clone(),
+			asset_id: reward_asset_id,
+			amount,
+		});
+		Ok(())
 	}
 
-	// verifty the device binding signature and return device.
-	fn get_verified_device(
-		account: T::AccountId,
-		puk: DeviceKey,
-		signature: BoundedVec<u8, T::StringLimit>,
-		nonce: u32,
-	) -> Result<
-		Device<T::CollectionId, T::ItemId, T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
-		DispatchError,
-	> {
-		// get the producer owner
-		let device = Devices::<T>::get(puk).ok_or(Error::<T>::DeviceNotExisted)?;
+	// check the training with the plausibility rules and update the suspicion score of the
+	// device, return whether the device is quarantined
+	fn check_plausibility(
+		device: &Device<T::CollectionId, T::ItemId, T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
+		training: &TrainingSummary,
+	) -> bool {
+		let rules = match PlausibilityConfig::<T>::get() {
+			Some(rules) => rules,
+			None => return QuarantinedDevices::<T>::contains_key(device.pk),
+		};
 
-		ensure!(device.status != DeviceStatus::Voided, Error::<T>::DeviceVoided);
+		let day = training.timestamp / (24 * 60 * 60);
+		let reports_today = DeviceDailyReports::<T>::mutate(device.pk, |(last_day, count)| {
+			if *last_day != day {
+				*last_day = day;
+				*count = 0;
+			}
+			*count = count.saturating_add(1);
+			*count
+		});
 
-		let flag = Self::verify_bind_device_message(account, nonce, puk, &signature[..])?;
+		let violations = rules.count_violations(training, reports_today);
+		let score = DeviceSuspicion::<T>::mutate(device.pk, |score| {
+			if violations > 0 {
+				*score = score.saturating_add(violations.saturating_mul(rules.violation_score));
+			} else {
+				*score = score.saturating_sub(rules.decay_score);
+			}
+			*score
+		});
 
-		ensure!(flag, Error::<T>::DeviceSignatureInvalid);
-		// check the nonce
-		ensure!(nonce > device.nonce, Error::<T>::NonceMustGreatThanBefore);
+		if score >= rules.quarantine_threshold && !QuarantinedDevices::<T>::contains_key(device.pk)
+		{
+			QuarantinedDevices::<T>::insert(device.pk, frame_system::Pallet::<T>::block_number());
+			Self::deposit_event(Event::DeviceQuarantined { device_key: device.pk, score });
+		}
+		QuarantinedDevices::<T>::contains_key(device.pk)
+	}
 
-		Ok(device)
+	/// Get the parameters of the sport type, the built-in sport types are used if it is not
+	/// registered.
+	pub fn sport_type_info(sport_type: SportId) -> Option<SportTypeInfo> {
+		SportTypes::<T>::get(sport_type)
+			.or_else(|| Self::built_in_sport_type(sport_type).map(SportTypeInfo::from))
 	}
 
-	// check the device's training report.
-	fn check_device_training_report(
-		puk: DeviceKey,
-		req_sig: BoundedVec<u8, T::StringLimit>,
-		msg: BoundedVec<u8, T::StringLimit>,
-	) -> Result<
-		Device<T::CollectionId, T::ItemId, T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
-		DispatchError,
-	> {
-		// get the producer owner
-		let device = Devices::<T>::get(puk).ok_or(Error::<T>::DeviceNotExisted)?;
+	// the built-in sport type of the id
+	fn built_in_sport_type(sport_type: SportId) -> Option<SportType> {
+		u8::try_from(sport_type).ok().and_then(|id| SportType::try_from(id).ok())
+	}
 
-		ensure!(device.item_id.is_some(), Error::<T>::DeviceNotBond);
+	// check the producer if it is exist and the owner meets the rules
+	fn check_producer(
+		owner: T::AccountId,
+		id: T::ObjectId,
+	) -> Result<Producer<T::ObjectId, T::AccountId>, DispatchError> {
+		// get the producer owner
+		let producer = Producers::<T>::get(id).ok_or(Error::<T>::ProducerNotExist)?;
+		// check the machine owner
+		ensure!(owner == producer.owner, Error::<T>::OperationIsNotAllowed);
+		Ok(producer)
+	}
 
-		let target = &req_sig[..];
-		let sig = Signature::from_bytes(target).map_err(|_| Error::<T>::DeviceSignatureInvalid)?;
+	// register the device of the producer for the vfe brand, the minting fee is paid by `who`
+	fn do_register_device(
+		who: &T::AccountId,
+		puk: DeviceKey,
+		producer_id: T::ObjectId,
+		brand_id: T::CollectionId,
+	) -> DispatchResult {
+		ensure!(!Devices::<T>::contains_key(puk), Error::<T>::DeviceExisted);
+		let producer = Self::check_producer(who.clone(), producer_id)?;
+		let vfe_brand = VFEBrands::<T>::get(brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
 
-		let verify_key = VerifyingKey::from_sec1_bytes(puk.as_ref())
-			.map_err(|_| Error::<T>::PublicKeyEncodeError)?;
+		// Check if the collection is authorized to the producer
+		VFEApprovals::<T>::try_mutate(
+			&vfe_brand.brand_id,
+			&producer_id,
+			|maybe_approved| -> DispatchResult {
+				let mut approved = maybe_approved.take().ok_or(Error::<T>::NoneValue)?;
 
-		// check the validity of the signature
-		let final_msg: &[u8] = msg.as_ref();
-		let flag = verify_key.verify(final_msg, &sig).is_ok();
+				// remaining_mint--
+				let remaining = approved
+					.remaining_mint
+					.checked_sub(One::one())
+					.ok_or(Error::<T>::ValueOverflow)?;
 
-		ensure!(flag, Error::<T>::DeviceSignatureInvalid);
+				// mint_cost handle transfer
+				if let Some((mint_asset_id, mint_price)) = approved.mint_cost {
+					// transfer tokens to NFT brand_id owner
+					<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+						mint_asset_id,
+						who,
+						&Self::into_account_id(producer_id),
+						mint_price,
+						true,
+					)?;
+					approved.locked_of_mint = approved
+						.locked_of_mint
+						.checked_add(&mint_price)
+						.ok_or(Error::<T>::ValueOverflow)?;
+				}
 
-		Ok(device)
-	}
+				approved.registered =
+					approved.registered.checked_add(One::one()).ok_or(Error::<T>::ValueOverflow)?;
+				approved.remaining_mint = remaining;
 
-	// handler report data to get rewards
-	fn handler_report_data(
-		device: &mut Device<T::CollectionId, T::ItemId, T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
-		report_data: BoundedVec<u8, T::StringLimit>,
-	) -> Result<(), DispatchError> {
-		let brand_id = device.brand_id;
-		let item_id = device.item_id.ok_or(Error::<T>::DeviceNotBond)?;
-		let sport_type = device.sport_type;
-		let account = Self::owner(&brand_id, &item_id).ok_or(Error::<T>::ItemNotFound)?;
+				Devices::<T>::insert(
+					puk,
+					Device {
+						brand_id,
+						item_id: None,
+						producer_id: producer.id,
+						status: DeviceStatus::Registered,
+						pk: puk,
+						nonce: 0u32,
+						sport_type: vfe_brand.sport_type,
+						timestamp: 0u32,
+						mint_cost: approved.mint_cost,
+					},
+				);
 
-		// First try to restore user energy and daily earning cap.
-		Self::_restore_energy(&account)?;
-		Self::_reset_daily_earned(&account)?;
+				*maybe_approved = Some(approved);
 
-		match sport_type {
-			SportType::JumpRope => {
-				ensure!(report_data.len() == 17, Error::<T>::ValueInvalid);
+				Self::deposit_event(Event::DeviceRegistered {
+					operator: who.clone(),
+					producer_id,
+					device_key: puk,
+					brand_id,
+				});
 
-				let training_report = JumpRopeTrainingReport::try_from(report_data.into_inner())
-					.map_err(|_| Error::<T>::ValueInvalid)?;
-				// Check whether data is submitted repeatedly
-				ensure!(training_report.timestamp > device.timestamp, Error::<T>::ValueInvalid);
-				let now = T::UnixTime::now().as_secs();
-				let expired_time = training_report.timestamp + T::ReportValidityPeriod::get();
-				ensure!(now >= training_report.timestamp as u64, Error::<T>::ValueInvalid);
-				ensure!(now <= expired_time as u64, Error::<T>::TrainingReportTimeExpired);
+				Ok(())
+			},
+		)
+	}
 
-				let mut vfe =
-					VFEDetails::<T>::get(brand_id, item_id).ok_or(Error::<T>::VFENotExist)?;
+	// bind the verified device to the vfe of `from`, a new vfe is minted if the device is
+	// registered
+	fn do_bind_device(
+		from: T::AccountId,
+		puk: DeviceKey,
+		mut device: Device<T::CollectionId, T::ItemId, T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
+		nonce: u32,
+		bind_item: Option<T::ItemId>,
+	) -> DispatchResult {
+		ensure!(device.item_id.is_none(), Error::<T>::DeviceBond);
+		// the device and the vfe can not be bound again during the cooldown
+		Self::check_bind_cooldown(DeviceBindCooldowns::<T>::take(puk))?;
+		if let Some(item_id) = bind_item {
+			Self::check_bind_cooldown(VFEBindCooldowns::<T>::take(&device.brand_id, &item_id))?;
+		}
+		// create the user if it is new
+		Self::find_user(&from);
 
-				let mut user = Self::find_user(&account);
+		//If it is a registered device, it will mint a new vfe for the user.
+		let new_vfe = if device.status == DeviceStatus::Registered {
+			let vfe = Self::create_vfe(&device.brand_id, &device.producer_id, &from)?;
+			// save new vfe detail
+			VFEDetails::<T>::insert(&vfe.brand_id, &vfe.item_id, vfe);
+			Self::deposit_event(Event::VFECreated { owner: from.clone(), detail: vfe });
+			Some(vfe)
+		} else {
+			None
+		};
 
-				ensure!(user.energy > 0, Error::<T>::EnergyExhausted);
+		// If the user passes itemId, bind this itemId, if not, bind a new vfe.
+		let vfe = match bind_item {
+			Some(item_id) => {
+				//check if item_id is belong to origin
+				let mut vfe = VFEDetails::<T>::get(&device.brand_id, &item_id)
+					.ok_or(Error::<T>::VFENotExist)?;
+				ensure!(vfe.device_key.is_none(), Error::<T>::VFEBond);
+				ensure!(
+					!StakedVFEs::<T>::contains_key(&device.brand_id, &item_id),
+					Error::<T>::VFEStaked
+				);
+				let controller = Self::vfe_controller(&device.brand_id, &item_id)?;
+				ensure!(controller == from, Error::<T>::OperationIsNotAllowed);
+				vfe.device_key = Some(puk);
+				vfe
+			},
+			None => {
+				//check if device status is register, we can get a new vfe to bind.
+				let mut vfe = new_vfe.ok_or(Error::<T>::DeviceBond)?;
+				vfe.device_key = Some(puk);
 
-				// check if earned cap
-				ensure!(user.earned < user.earning_cap, Error::<T>::EarnedCap);
+				vfe
+			},
+		};
 
-				// Power consumption = training-duration / training_unit_duration
-				let mut power_used =
-					training_report.jump_rope_duration / sport_type.training_unit_duration();
+		// save vfe detail after bond
+		VFEDetails::<T>::insert(&vfe.brand_id, &vfe.item_id, vfe);
+
+		device.nonce = nonce;
+		device.item_id = Some(vfe.item_id);
+		device.status = DeviceStatus::Activated;
+		// save device
+		Devices::<T>::insert(puk, device);
+		Self::deposit_event(Event::DeviceBound {
+			owner: from,
+			device_key: puk,
+			brand_id: device.brand_id,
+			item_id: vfe.item_id,
+		});
 
-				// Check if the training is enough
-				ensure!(power_used > 0, Error::<T>::InsufficientTraining);
+		Ok(())
+	}
 
-				// check the user energy
-				if power_used > user.energy {
-					power_used = user.energy;
-				}
+	/// Get the emission budget of the period, which halves every `HalvingPeriods` periods.
+	pub fn emission_budget(period: u32) -> BalanceOf<T> {
+		let halvings = period.checked_div(T::HalvingPeriods::get()).unwrap_or_default();
+		let divisor = BalanceOf::<T>::from(2u32).saturating_pow(halvings as usize);
+		T::InitialEmission::get() / divisor
+	}
 
-				// check if VFE remaining battery is enough
-				ensure!(vfe.remaining_battery > 0, Error::<T>::LowBattery);
+	/// Get the emission period of the block.
+	pub fn emission_period(block: T::BlockNumber) -> u32 {
+		(block / T::EmissionPeriod::get().max(One::one())).saturated_into()
+	}
 
-				// check the vfe electric
-				if power_used > vfe.remaining_battery {
-					power_used = vfe.remaining_battery;
-				}
+	/// Get the emission state of the current period.
+	pub fn current_emission() -> EmissionState<BalanceOf<T>> {
+		let period = Self::emission_period(frame_system::Pallet::<T>::block_number());
+		match Emission::<T>::get() {
+			Some(emission) if emission.period == period => emission,
+			_ => EmissionState {
+				period,
+				budget: Self::emission_budget(period),
+				minted: Zero::zero(),
+			},
+		}
+	}
 
-				// update user energy and vfe remaining battery
-				user.energy -= power_used;
-				vfe.remaining_battery -= power_used;
-
-				let r_luck = Self::random_value(vfe.current_ability.luck) + 1;
-				let r_skill = (vfe.current_ability.skill * training_report.max_jump_rope_count) /
-					((training_report.interruptions as u16 + 1) *
-						sport_type.frequency_standard());
-				let s = if vfe.current_ability.skill > r_skill {
-					vfe.current_ability.skill -
-						Self::random_value(vfe.current_ability.skill - r_skill)
-				} else {
-					vfe.current_ability.skill +
-						Self::random_value(r_skill - vfe.current_ability.skill)
-				};
+	/// Get the period, the budget and the minted incentive tokens of the current emission.
+	pub fn get_emission_state() -> (u32, BalanceOf<T>, BalanceOf<T>) {
+		let emission = Self::current_emission();
+		(emission.period, emission.budget, emission.minted)
+	}
 
-				let f = sport_type.is_frequency_range(training_report.average_speed);
-				ensure!(f > 0, Error::<T>::TrainingReportOutOfNormalRange);
+	/// Get the daily training aggregates of the user in the recent `MaxHistoryDays` days.
+	pub fn get_user_training_history(who: T::AccountId) -> Vec<DailyTraining<BalanceOf<T>>> {
+		let max_days = T::MaxHistoryDays::get();
+		let today = (T::UnixTime::now().as_secs() / (24 * 60 * 60)) as u32;
+		TrainingHistory::<T>::iter_prefix_values(who)
+			.flat_map(|history| history.into_inner())
+			.filter(|r| r.day.saturating_add(max_days) > today)
+			.collect()
+	}
 
-				let e = vfe.current_ability.efficiency;
+	/// Get the block when the upgrading VFE completes.
+	pub fn get_upgrade_completion(
+		brand_id: T::CollectionId,
+		item: T::ItemId,
+	) -> Option<T::BlockNumber> {
+		UpgradingVFEs::<T>::get(brand_id, item)
+	}
 
-				let training_volume = (e + s + 2 * r_luck) * power_used * f;
-				let cost_unit = T::CostUnit::get();
-				let final_award = BalanceOf::<T>::from(training_volume).saturating_mul(cost_unit);
+	// complete the upgrading VFE if its completion block is reached
+	pub(crate) fn try_complete_level_up(
+		brand_id: &T::CollectionId,
+		item_id: &T::ItemId,
+	) -> DispatchResult {
+		if let Some(completion) = UpgradingVFEs::<T>::get(brand_id, item_id) {
+			if frame_system::Pallet::<T>::block_number() >= completion {
+				let vfe = VFEDetails::<T>::get(brand_id, item_id).ok_or(Error::<T>::VFENotExist)?;
+				Self::do_complete_level_up(brand_id, item_id, vfe)?;
+			}
+		}
+		Ok(())
+	}
 
-				//save user earned
-				let earned = final_award.saturating_add(user.earned);
-				let actual_award = if earned > user.earning_cap {
-					user.earned = user.earning_cap;
-					user.earning_cap.saturating_sub(user.earned)
-				} else {
-					user.earned = earned;
-					final_award
-				};
+	// level up the VFE, and increase the owner's energy cap and earning cap of daily
+	fn do_complete_level_up(
+		brand_id: &T::CollectionId,
+		item_id: &T::ItemId,
+		mut vfe: VFEDetail<T::CollectionId, T::ItemId, T::Hash, T::BlockNumber>,
+	) -> DispatchResult {
+		let owner = Self::owner(brand_id, item_id).ok_or(Error::<T>::VFENotExist)?;
+		let mut user = Self::find_user(&owner);
+
+		vfe.level = vfe.level.saturating_add(1);
+		vfe.available_points = vfe
+			.available_points
+			.saturating_add(Self::economic_params().growth_points(vfe.rarity));
+		vfe.is_upgrading = false;
+		VFEDetails::<T>::insert(brand_id, item_id, vfe);
+		UpgradingVFEs::<T>::remove(brand_id, item_id);
+
+		// increase the user's energy cap and earing cap of daily
+		// check if user current energy_total is less than new energy_total, the boosts of the
+		// staked VFEs are kept on top of the caps
+		let boost = StakingBoosts::<T>::get(&owner);
+		let new_energy_cap = Self::level_into_energy_cap(vfe.level);
+		let new_earning_cap = Self::level_into_earning_cap(vfe.level);
+		if new_energy_cap > user.energy_total.saturating_sub(boost.energy) {
+			user.energy_total = new_energy_cap.saturating_add(boost.energy);
+		}
+		if new_earning_cap > user.earning_cap.saturating_sub(boost.earning_cap) {
+			user.earning_cap = new_earning_cap.saturating_add(boost.earning_cap);
+		}
+		Users::<T>::insert(&owner, user);
 
-				// update the electric with user and vfe and device.
-				device.timestamp = training_report.timestamp;
-				Devices::<T>::insert(device.pk, device);
-				Users::<T>::insert(account.clone(), user);
-				VFEDetails::<T>::insert(brand_id, item_id, vfe);
+		Self::deposit_event(Event::VFEUpgradeCompleted {
+			brand_id: *brand_id,
+			item_id: *item_id,
+			level: vfe.level,
+		});
+		Ok(())
+	}
 
-				let reward_asset_id =
-					IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?;
-				T::Currencies::mint_into(reward_asset_id, &account, actual_award)?;
+	// check the cooldown of binding is expired
+	fn check_bind_cooldown(until: Option<T::BlockNumber>) -> DispatchResult {
+		if let Some(until) = until {
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(now >= until, Error::<T>::BindCooldownNotExpired);
+		}
+		Ok(())
+	}
 
-				Self::deposit_event(Event::TrainingReportsAndRewards {
-					owner: account,
-					brand_id,
-					item_id,
-					sport_type,
-					training_time: training_report.timestamp,
-					training_duration: training_report.jump_rope_duration,
-					training_count: training_report.total_jump_rope_count,
-					energy_used: power_used,
-					asset_id: reward_asset_id,
-					rewards: actual_award,
-				});
+	/// Get the unbind settings, the fee of `Config` is charged in the incentive token and burned
+	/// if it is not set.
+	pub fn unbind_settings() -> UnbindSettings<AssetIdOf<T>, BalanceOf<T>, T::BlockNumber> {
+		UnbindConfig::<T>::get().unwrap_or(UnbindSettings {
+			asset: None,
+			fee: T::UnbindFee::get(),
+			destination: FeeDestination::Burn,
+			cooldown: Zero::zero(),
+		})
+	}
 
-				Ok(())
+	// pay the fee of unbinding device to the destination
+	fn pay_unbind_fee(
+		who: &T::AccountId,
+		brand_id: &T::CollectionId,
+		settings: &UnbindSettings<AssetIdOf<T>, BalanceOf<T>, T::BlockNumber>,
+	) -> DispatchResult {
+		if settings.fee.is_zero() {
+			return Ok(())
+		}
+		let asset_id = match settings.asset {
+			Some(asset_id) => asset_id,
+			None => IncentiveToken::<T>::get().ok_or(Error::<T>::IncentiveTokenNotSet)?,
+		};
+		match settings.destination {
+			FeeDestination::Burn => {
+				T::Currencies::burn_from(asset_id, who, settings.fee)?;
+			},
+			FeeDestination::BrandOwner => {
+				let brand_owner =
+					Self::collection_owner(brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
+				<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+					asset_id,
+					who,
+					&brand_owner,
+					settings.fee,
+					false,
+				)?;
+			},
+			FeeDestination::Treasury => {
+				<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+					asset_id,
+					who,
+					&T::TreasuryAccount::get(),
+					settings.fee,
+					false,
+				)?;
 			},
-			SportType::Running => Err(Error::<T>::ValueInvalid)?,
-			SportType::Riding => Err(Error::<T>::ValueInvalid)?,
 		}
+		Self::deposit_event(Event::UnbindFeePaid {
+			who: who.clone(),
+			asset_id,
+			fee: settings.fee,
+			destination: settings.destination,
+		});
+		Ok(())
 	}
 
-	// check the producer if it is exist and the owner meets the rules
-	fn check_producer(
-		owner: T::AccountId,
-		id: T::ObjectId,
-	) -> Result<Producer<T::ObjectId, T::AccountId>, DispatchError> {
-		// get the producer owner
-		let producer = Producers::<T>::get(id).ok_or(Error::<T>::ProducerNotExist)?;
-		// check the machine owner
-		ensure!(owner == producer.owner, Error::<T>::OperationIsNotAllowed);
-		Ok(producer)
+	// refund the minting fee of the registered device to `beneficiary` and release the approval
+	fn release_registered_device(
+		device: &Device<T::CollectionId, T::ItemId, T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
+		beneficiary: &T::AccountId,
+	) -> DispatchResult {
+		let mut approved = VFEApprovals::<T>::get(&device.brand_id, &device.producer_id)
+			.ok_or(Error::<T>::NoneValue)?;
+		if let Some((mint_asset_id, mint_price)) = device.mint_cost {
+			// transfer tokens to NFT class owner
+			<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
+				mint_asset_id,
+				&Self::into_account_id(device.producer_id),
+				beneficiary,
+				mint_price,
+				false,
+			)?;
+			approved.locked_of_mint = approved
+				.locked_of_mint
+				.checked_sub(&mint_price)
+				.ok_or(Error::<T>::ValueOverflow)?;
+		}
+		approved.registered =
+			approved.registered.checked_sub(One::one()).ok_or(Error::<T>::ValueOverflow)?;
+		approved.remaining_mint = approved
+			.remaining_mint
+			.checked_add(One::one())
+			.ok_or(Error::<T>::ValueOverflow)?;
+		VFEApprovals::<T>::insert(&device.brand_id, &device.producer_id, approved);
+		Ok(())
 	}
 
 	// find user by `account_id` if user not exist and create it
@@ -1395,7 +5234,7 @@ where
 		let vfe_brand = VFEBrands::<T>::get(brand_id).ok_or(Error::<T>::VFEBrandNotFound)?;
 		let rarity = vfe_brand.rarity;
 
-		let (min, max) = rarity.base_range_of_ability();
+		let (min, max) = Self::economic_params().base_range_of_ability(rarity);
 		let efficiency = min + Self::random_value(max - min);
 		let skill = min + Self::random_value(max - min);
 		let luck = min + Self::random_value(max - min);
@@ -1478,6 +5317,58 @@ where
 		})
 	}
 
+	// mint a new VFE in the brand whose rarity and base abilities are derived from the genes of
+	// parents and randomness
+	fn breed_vfe(
+		brand_id: &T::CollectionId,
+		rarity: VFERarity,
+		owner: &T::AccountId,
+		vfe: &VFEDetail<T::CollectionId, T::ItemId, T::Hash, T::BlockNumber>,
+		mate: &VFEDetail<T::CollectionId, T::ItemId, T::Hash, T::BlockNumber>,
+	) -> Result<VFEDetail<T::CollectionId, T::ItemId, T::Hash, T::BlockNumber>, DispatchError> {
+		let (_, random_seed, _) = Self::generate_random_number();
+		let gene: T::Hash = T::Hashing::hash_of(&(vfe.gene, mate.gene, random_seed));
+		let bytes = gene.as_ref();
+
+		// the base ability is the average of parents with a mutation of -2..=2, in the range of
+		// the brand rarity
+		let (min, max) = Self::economic_params().base_range_of_ability(rarity);
+		let inherit = |a: u16, b: u16, byte: u8| -> u16 {
+			let average = (a.saturating_add(b) / 2).saturating_add(byte as u16 % 5);
+			average.saturating_sub(2).clamp(min, max)
+		};
+		let base_ability = VFEAbility {
+			efficiency: inherit(
+				vfe.base_ability.efficiency,
+				mate.base_ability.efficiency,
+				bytes[2],
+			),
+			skill: inherit(vfe.base_ability.skill, mate.base_ability.skill, bytes[3]),
+			luck: inherit(vfe.base_ability.luck, mate.base_ability.luck, bytes[4]),
+			durable: inherit(vfe.base_ability.durable, mate.base_ability.durable, bytes[5]),
+		};
+
+		let parent_id = Self::into_parent_id(T::VFEBrandId::get(), brand_id.to_owned().into());
+		let instance = T::UniqueId::generate_object_id(parent_id)?;
+		let item_id: T::ItemId = instance.into();
+		Self::do_mint(brand_id.to_owned(), item_id, owner.clone())?;
+
+		Ok(VFEDetail {
+			brand_id: brand_id.to_owned(),
+			item_id,
+			base_ability,
+			current_ability: base_ability,
+			rarity,
+			level: 0,
+			remaining_battery: 100,
+			gene,
+			last_block: frame_system::Pallet::<T>::block_number(),
+			is_upgrading: false,
+			available_points: 0,
+			device_key: None,
+		})
+	}
+
 	// approve to mint a new instance
 	fn do_mint_approved(
 		vfe_brand_id: T::CollectionId,
@@ -1503,8 +5394,8 @@ where
 
 				// mint_cost handle transfer
 				if let Some((mint_asset_id, mint_price)) = approved.mint_cost {
-					let vfe_brand_owner_radio =
-						Permill::from_percent(100) - T::UserVFEMintedProfitRatio::get();
+					let user_profit_ratio = Self::economic_params().user_vfe_minted_profit_ratio;
+					let vfe_brand_owner_radio = Permill::from_percent(100) - user_profit_ratio;
 					let vfe_brand_owner_profit = vfe_brand_owner_radio.mul(mint_price);
 					// transfer tokens to VFE brand owner
 					<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
@@ -1516,7 +5407,7 @@ where
 					)?;
 
 					// transfer tokens to VFE item of owner
-					let vfe_item_owner_profit = T::UserVFEMintedProfitRatio::get().mul(mint_price);
+					let vfe_item_owner_profit = user_profit_ratio.mul(mint_price);
 					<T::Currencies as fungibles::Transfer<T::AccountId>>::transfer(
 						mint_asset_id,
 						&Self::into_account_id(producer_id.to_owned()),
@@ -1552,8 +5443,10 @@ where
 			let recoverable_times = last_energy_recovery.saturating_sub(user_last_restore_block);
 			let recoverable_times =
 				recoverable_times.checked_div(&duration).ok_or(Error::<T>::ValueOverflow)?;
-			let average_recovery: u16 =
-				T::EnergyRecoveryRatio::get().mul(user.energy_total as u32).saturated_into();
+			let average_recovery: u16 = Self::economic_params()
+				.energy_recovery_ratio
+				.mul(user.energy_total as u32)
+				.saturated_into();
 			let recoverable_energy = recoverable_times.saturated_into::<u16>() * average_recovery;
 			let max_recovery_energy = recoverable_energy + user.energy;
 			let restored_amount = if max_recovery_energy > user.energy_total {
@@ -1605,17 +5498,62 @@ where
 		values
 	}
 
+	/// Get the economic parameters, the defaults of `Config` are used if it is not set.
+	pub fn economic_params() -> EconomicParameters<BalanceOf<T>> {
+		EconomicParams::<T>::get().unwrap_or_else(|| {
+			let rarities = [VFERarity::Common, VFERarity::Elite, VFERarity::Rare, VFERarity::Epic];
+			EconomicParameters {
+				cost_unit: T::CostUnit::get(),
+				level_up_cost_factor: T::LevelUpCostFactor::get(),
+				init_energy: T::InitEnergy::get(),
+				init_earning_cap: T::InitEarningCap::get(),
+				energy_recovery_ratio: T::EnergyRecoveryRatio::get(),
+				user_vfe_minted_profit_ratio: T::UserVFEMintedProfitRatio::get(),
+				base_range_of_ability: rarities.map(|r| r.base_range_of_ability()),
+				growth_points: rarities.map(|r| r.growth_points()),
+			}
+		})
+	}
+
 	// level into energy cap of daily
 	pub fn level_into_energy_cap(level: u16) -> u16 {
+		let init_energy = Self::economic_params().init_energy as u32;
 		// increase energy per 2 level
-		(level / 2) * T::InitEnergy::get() / 2 + T::InitEnergy::get()
+		((level as u32 / 2) * init_energy / 2 + init_energy).saturated_into()
 	}
 
 	// level into earning cap of daily
 	pub fn level_into_earning_cap(level: u16) -> BalanceOf<T> {
-		let base_cap = T::InitEarningCap::get();
-		let cap = base_cap * level + base_cap;
-		BalanceOf::<T>::saturated_from(cap).saturating_mul(T::CostUnit::get())
+		let params = Self::economic_params();
+		let base_cap = params.init_earning_cap as u32;
+		let cap = base_cap * (level as u32 + 1);
+		BalanceOf::<T>::from(cap).saturating_mul(params.cost_unit)
+	}
+
+	/// Get the boost of staking the VFE, which is `StakingBoostRatio` of the cap of the VFE's
+	/// level for each rarity, from 1 of Common to 4 of Epic.
+	pub fn staking_boost(
+		vfe: &VFEDetail<T::CollectionId, T::ItemId, T::Hash, T::BlockNumber>,
+		target: StakingTarget,
+	) -> StakingBoost<BalanceOf<T>> {
+		let ratio = T::StakingBoostRatio::get();
+		let rarity = vfe.rarity as u32 + 1;
+		match target {
+			StakingTarget::Energy => {
+				let cap = Self::level_into_energy_cap(vfe.level) as u32;
+				StakingBoost {
+					energy: ratio.mul_floor(cap.saturating_mul(rarity)).saturated_into(),
+					earning_cap: Zero::zero(),
+				}
+			},
+			StakingTarget::EarningCap => {
+				let cap = Self::level_into_earning_cap(vfe.level);
+				StakingBoost {
+					energy: 0,
+					earning_cap: ratio.mul_floor(cap.saturating_mul(rarity.into())),
+				}
+			},
+		}
 	}
 
 	// calculate VFE charging costs
@@ -1624,24 +5562,19 @@ where
 		charge_num: u16,
 	) -> BalanceOf<T> {
 		let mut charge_num = charge_num;
-		if vfe.remaining_battery + charge_num > 100u16 {
-			charge_num = 100u16 - vfe.remaining_battery;
+		if vfe.remaining_battery.saturating_add(charge_num) > 100u16 {
+			charge_num = 100u16.saturating_sub(vfe.remaining_battery);
 		}
 
-		let p_one = (vfe.base_ability.efficiency +
-			vfe.base_ability.skill +
-			vfe.base_ability.luck +
-			vfe.base_ability.durable) /
-			2;
-
-		let p_two = (vfe.current_ability.efficiency +
-			vfe.current_ability.skill +
-			vfe.current_ability.luck +
-			vfe.current_ability.durable) /
-			(4 * vfe.current_ability.durable);
+		// the abilities are summed in u64 so that the boosted abilities do not overflow
+		let p_one = vfe.base_ability.sum() / 2;
+		let p_two = vfe.current_ability.sum() / (4 * vfe.current_ability.durable.max(1) as u64);
 
-		let p_two = p_two.pow(2) * vfe.level;
-		BalanceOf::<T>::from((p_one + p_two) * charge_num).saturating_mul(T::CostUnit::get())
+		let p_two = p_two.saturating_pow(2).saturating_mul(vfe.level as u64);
+		BalanceOf::<T>::saturated_from(
+			p_one.saturating_add(p_two).saturating_mul(charge_num as u64),
+		)
+		.saturating_mul(Self::economic_params().cost_unit)
 	}
 
 	// calculate VFE level up costs
@@ -1650,20 +5583,24 @@ where
 		user: &User<T::AccountId, T::BlockNumber, BalanceOf<T>>,
 	) -> BalanceOf<T> {
 		// Calculating level up fees for VFE
-		let t = T::LevelUpCostFactor::get();
-		let cost_unit = T::CostUnit::get();
-		let base_ability = (vfe
-			.base_ability
-			.efficiency
-			.saturating_add(vfe.base_ability.skill)
-			.saturating_add(vfe.base_ability.luck)
-			.saturating_sub(vfe.base_ability.durable)) /
+		let params = Self::economic_params();
+		let t = params.level_up_cost_factor;
+		let cost_unit = params.cost_unit;
+		let base_ability = (vfe.base_ability.efficiency as u64 +
+			vfe.base_ability.skill as u64 +
+			vfe.base_ability.luck as u64)
+			.saturating_sub(vfe.base_ability.durable as u64) /
 			2;
-		let g = vfe.rarity.growth_points();
-		let n = user.energy_total;
-		let level_up_cost = base_ability + vfe.level * (g - 1) * n;
-
-		BalanceOf::<T>::from(level_up_cost).saturating_mul(t).saturating_mul(cost_unit)
+		let g = params.growth_points(vfe.rarity).saturating_sub(1) as u64;
+		// the boost of the staked VFEs does not raise the cost
+		let boost = StakingBoosts::<T>::get(&user.owner);
+		let n = user.energy_total.saturating_sub(boost.energy) as u64;
+		// the product of three u16 values can not overflow u64
+		let level_up_cost = base_ability + vfe.level as u64 * g * n;
+
+		BalanceOf::<T>::saturated_from(level_up_cost)
+			.saturating_mul(t)
+			.saturating_mul(cost_unit)
 	}
 
 	// get VFE charging costs
@@ -1703,14 +5640,100 @@ where
 	}
 
 	pub fn check_vfe_can_transfer(brand_id: &T::CollectionId, item: &T::ItemId) -> DispatchResult {
-		// Only VFE is fully charged or not uprading or unbound can be transferred
+		// Only VFE is fully charged or not uprading or unbound or not lent or not staked can be
+		// transferred
+		ensure!(!VFELoans::<T>::contains_key(brand_id, item), Error::<T>::VFELent);
+		ensure!(!StakedVFEs::<T>::contains_key(brand_id, item), Error::<T>::VFEStaked);
 		let vfe = VFEDetails::<T>::get(brand_id, item).ok_or(Error::<T>::VFENotExist)?;
 		ensure!(vfe.remaining_battery >= 100, Error::<T>::VFENotFullyCharged);
-		ensure!(!vfe.is_upgrading, Error::<T>::VFEUpgrading);
+		// the finished upgrade will be settled before transferring
+		let now = frame_system::Pallet::<T>::block_number();
+		let upgrading = vfe.is_upgrading &&
+			Self::get_upgrade_completion(*brand_id, *item).map_or(true, |c| c > now);
+		ensure!(!upgrading, Error::<T>::VFEUpgrading);
 		ensure!(vfe.device_key.is_none(), Error::<T>::VFEBond);
 		Ok(())
 	}
 
+	/// Get the account controlling the VFE, which is the borrower if the VFE is lent. The VFE
+	/// listed for lending or with the expired loan can not be used until the loan is ended.
+	pub fn vfe_controller(
+		brand_id: &T::CollectionId,
+		item_id: &T::ItemId,
+	) -> Result<T::AccountId, DispatchError> {
+		match VFELoans::<T>::get(brand_id, item_id) {
+			None => Self::owner(brand_id, item_id).ok_or_else(|| Error::<T>::ItemNotFound.into()),
+			Some(VFELoan { borrower: Some((borrower, expiry)), .. }) => {
+				let now = frame_system::Pallet::<T>::block_number();
+				ensure!(now < expiry, Error::<T>::VFELoanExpired);
+				Ok(borrower)
+			},
+			Some(_) => Err(Error::<T>::VFELent.into()),
+		}
+	}
+
+	// end the loan of the VFE and return the control to the owner, the VFE is unbound from the
+	// device with the bind cooldown
+	fn do_end_vfe_loan(
+		brand_id: &T::CollectionId,
+		item_id: &T::ItemId,
+		owner: T::AccountId,
+	) -> DispatchResult {
+		VFELoans::<T>::remove(brand_id, item_id);
+
+		let mut vfe = VFEDetails::<T>::get(brand_id, item_id).ok_or(Error::<T>::VFENotExist)?;
+		if let Some(device_pk) = vfe.device_key.take() {
+			if let Some(mut device) = Devices::<T>::get(device_pk) {
+				device.item_id = None;
+				Devices::<T>::insert(device_pk, &device);
+			}
+			VFEDetails::<T>::insert(brand_id, item_id, vfe);
+			Self::set_bind_cooldown(brand_id, item_id, device_pk, Self::unbind_settings().cooldown);
+			Self::deposit_event(Event::DeviceUnbound {
+				owner: owner.clone(),
+				device_key: device_pk,
+				brand_id: *brand_id,
+				item_id: *item_id,
+			});
+		}
+
+		Self::deposit_event(Event::VFELoanEnded { owner, brand_id: *brand_id, item_id: *item_id });
+		Ok(())
+	}
+
+	// end the loan of the VFE if it is expired, and return whether the loan is ended
+	fn try_end_expired_loan(
+		brand_id: &T::CollectionId,
+		item_id: &T::ItemId,
+	) -> Result<bool, DispatchError> {
+		let loan = match VFELoans::<T>::get(brand_id, item_id) {
+			Some(loan) => loan,
+			None => return Ok(false),
+		};
+		let now = frame_system::Pallet::<T>::block_number();
+		match loan.borrower {
+			Some((_, expiry)) if now >= expiry => {
+				Self::do_end_vfe_loan(brand_id, item_id, loan.owner)?;
+				Ok(true)
+			},
+			_ => Ok(false),
+		}
+	}
+
+	// the unbound device and VFE can not be bound again until the cooldown ends
+	fn set_bind_cooldown(
+		brand_id: &T::CollectionId,
+		item_id: &T::ItemId,
+		device_pk: DeviceKey,
+		cooldown: T::BlockNumber,
+	) {
+		if !cooldown.is_zero() {
+			let until = frame_system::Pallet::<T>::block_number().saturating_add(cooldown);
+			VFEBindCooldowns::<T>::insert(brand_id, item_id, until);
+			DeviceBindCooldowns::<T>::insert(device_pk, until);
+		}
+	}
+
 	/// The parent ID of the VFE Brand Id.
 	pub fn into_parent_id(
 		parent_id: T::Hash,
@@ -0,0 +1,87 @@
+// This file is part of Polket.
+// Copyright (C) 2021-2022 Polket.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::*;
+use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+/// The storage layout before the sport types are registered by governance, the sport type of
+/// VFE brands and devices is the built-in `SportType`.
+pub mod v0 {
+	use super::*;
+
+	#[derive(Eq, PartialEq, Clone, RuntimeDebug, Encode, Decode)]
+	pub struct VFEBrand<CollectionId, StringLimit: Get<u32>> {
+		pub brand_id: CollectionId,
+		pub sport_type: SportType,
+		pub rarity: VFERarity,
+		pub approvals: u32,
+		pub uri: BoundedVec<u8, StringLimit>,
+	}
+
+	#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode)]
+	pub struct Device<CollectionId, ItemId, ObjectId, AssetId, Balance> {
+		pub sport_type: SportType,
+		pub brand_id: CollectionId,
+		pub item_id: Option<ItemId>,
+		pub producer_id: ObjectId,
+		pub status: DeviceStatus,
+		pub pk: DeviceKey,
+		pub nonce: u32,
+		pub timestamp: u32,
+		pub mint_cost: Option<(AssetId, Balance)>,
+	}
+}
+
+/// Migrate the sport type of `VFEBrands` and `Devices` to the `SportId` of the built-in sport
+/// type, and register the built-in sport types in `SportTypes`.
+pub fn migrate_to_v1<T: Config>() -> Weight {
+	if Pallet::<T>::on_chain_storage_version() >= 1 {
+		return T::DbWeight::get().reads(1)
+	}
+
+	let mut reads: Weight = 1;
+	let mut writes: Weight = 1;
+	for sport_type in [SportType::JumpRope, SportType::Running, SportType::Riding] {
+		let sport_id = sport_type as SportId;
+		reads += 1;
+		if !SportTypes::<T>::contains_key(sport_id) {
+			SportTypes::<T>::insert(sport_id, SportTypeInfo::from(sport_type));
+			writes += 1;
+		}
+	}
+
+	VFEBrands::<T>::translate::<v0::VFEBrand<T::CollectionId, T::StringLimit>, _>(|_, old| {
+		reads += 1;
+		writes += 1;
+		Some(VFEBrand {
+			brand_id: old.brand_id,
+			sport_type: old.sport_type as SportId,
+			rarity: old.rarity,
+			approvals: old.approvals,
+			uri: old.uri,
+		})
+	});
+
+	Devices::<T>::translate::<
+		v0::Device<T::CollectionId, T::ItemId, T::ObjectId, AssetIdOf<T>, BalanceOf<T>>,
+		_,
+	>(|_, old| {
+		reads += 1;
+		writes += 1;
+		Some(Device {
+			sport_type: old.sport_type as SportId,
+			brand_id: old.brand_id,
+			item_id: old.item_id,
+			producer_id: old.producer_id,
+			status: old.status,
+			pk: old.pk,
+			nonce: old.nonce,
+			timestamp: old.timestamp,
+			mint_cost: old.mint_cost,
+		})
+	});
+
+	StorageVersion::new(1).put::<Pallet<T>>();
+	T::DbWeight::get().reads_writes(reads, writes)
+}
@@ -27,6 +27,7 @@ pub const DANY: AccountId = AccountId::new([5u8; 32]);
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 pub type Instance = pallet_uniques::Instance1;
+pub type GemsInstance = pallet_uniques::Instance2;
 
 // Configure a mock runtime to test the pallet.
 frame_support::construct_runtime!(
@@ -40,6 +41,7 @@ frame_support::construct_runtime!(
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
 		VFEUniques: pallet_uniques::<Instance1>::{Pallet, Call, Storage, Event<T>},
+		Gems: pallet_uniques::<Instance2>::{Pallet, Call, Storage, Event<T>},
 		UniqueId: pallet_unique_id::{Pallet, Storage},
 		Currencies: pallet_currencies::{Pallet, Call, Storage, Event<T>},
 		VFE: pallet_vfe::{Pallet, Call, Storage, Event<T>},
@@ -163,6 +165,20 @@ impl<O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>> Ensu
 	}
 }
 
+pub struct EnsureAuditor<AccountId>(sp_std::marker::PhantomData<AccountId>);
+
+impl<O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>> EnsureOrigin<O>
+	for EnsureAuditor<AccountId>
+{
+	type Success = AccountId;
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			RawOrigin::Signed(who) if (who == BOB) => Ok(who),
+			r => Err(O::from(r)),
+		})
+	}
+}
+
 pub struct EnsureProducer<AccountId>(sp_std::marker::PhantomData<AccountId>);
 
 impl<O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>> EnsureOrigin<O>
@@ -207,6 +223,25 @@ impl pallet_uniques::Config<Instance> for Test {
 	type Locker = ();
 }
 
+impl pallet_uniques::Config<GemsInstance> for Test {
+	type Event = Event;
+	type CollectionId = u32;
+	type ItemId = u32;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type CollectionDeposit = CollectionDeposit;
+	type ItemDeposit = ItemDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type AttributeDepositBase = AttributeDepositBase;
+	type DepositPerByte = MetadataDepositPerByte;
+	type StringLimit = StringLimit;
+	type KeyLimit = KeyLimit;
+	type ValueLimit = ValueLimit;
+	type WeightInfo = ();
+	type Locker = ();
+}
+
 impl pallet_unique_id::Config for Test {
 	type ParentId = Self::Hash;
 	type ObjectId = u32;
@@ -233,6 +268,7 @@ parameter_types! {
 	pub const VFEPalletId: PalletId = PalletId(*b"poc/acas");
 	pub ProducerId: H256 = <BlakeTwo256 as sp_runtime::traits::Hash>::hash(b"produceridkey");
 	pub VFEBrandId: H256 = <BlakeTwo256 as sp_runtime::traits::Hash>::hash(b"vfebrandidkey");
+	pub GemId: H256 = <BlakeTwo256 as sp_runtime::traits::Hash>::hash(b"gemidkey");
 	pub const IncentiveToken: u32 = 0;
 	pub const UnbindFee:u32 = 1;
 	pub const CostUnit: u64 = 100000;
@@ -244,6 +280,39 @@ parameter_types! {
 	pub const EnergyRecoveryRatio: Permill = Permill::from_percent(25); //25%
 	pub const ReportValidityPeriod: u32 = 24 * 60 * 60;
 	pub const UserVFEMintedProfitRatio: Permill = Permill::from_percent(30); //30%
+	pub const MaxMerkleProofLength: u32 = 20;
+	pub const EmissionPeriod: u64 = 100;
+	pub const InitialEmission: u64 = 1_000_000_000_000_000;
+	pub const HalvingPeriods: u32 = 2;
+	pub const MaxHistoryDays: u32 = 2;
+	pub const LeaderboardSize: u32 = 2;
+	pub const MaxBrandChallenges: u32 = 2;
+	pub const MaxTeamMembers: u32 = 2;
+	pub const TeamGoalPeriod: u64 = 100;
+	pub const TeamGoalPerMember: u64 = 100;
+	pub const TeamBonus: Permill = Permill::from_percent(10);
+	pub const ReferralShare: Permill = Permill::from_percent(10);
+	pub const ReferralPeriod: u64 = 100;
+	pub const MaxReferralRewards: u64 = 1000000;
+	pub const StakingBoostRatio: Permill = Permill::from_percent(25);
+	pub const StakingUnbondingPeriod: u64 = 10;
+	pub const TreasuryAccount: AccountId = TOM;
+	pub const LevelUpSpeedUpCost: u64 = 10000;
+	pub const BreedCooldown: u64 = 10;
+	pub const MaxBreedCount: u16 = 2;
+	pub const FusionCost: u64 = 100000;
+	pub const WearPerEnergy: Permill = Permill::from_percent(1);
+	pub const WearPenaltyThreshold: Permill = Permill::from_percent(50);
+	pub const RepairCost: u64 = 100;
+	pub const MaxSockets: u32 = 2;
+	pub const LevelsPerSocket: u16 = 1;
+	pub const MaxGemAbility: u16 = 10;
+	pub const UnsocketFee: u64 = 1000;
+}
+
+parameter_types! {
+	pub static LevelUpDurationPerLevel: u64 = 0;
+	pub static ChallengeWindow: u64 = 0;
 }
 
 impl Config for Test {
@@ -269,6 +338,41 @@ impl Config for Test {
 	type UnixTime = Timestamp;
 	type ReportValidityPeriod = ReportValidityPeriod;
 	type UserVFEMintedProfitRatio = UserVFEMintedProfitRatio;
+	type GovernanceOrigin = EnsureRoot<Self::AccountId>;
+	type AuditOrigin = EnsureRoot<Self::AccountId>;
+	type ChallengeOrigin = EnsureAuditor<Self::AccountId>;
+	type ChallengeWindow = ChallengeWindow;
+	type MaxHistoryDays = MaxHistoryDays;
+	type LeaderboardSize = LeaderboardSize;
+	type MaxBrandChallenges = MaxBrandChallenges;
+	type MaxTeamMembers = MaxTeamMembers;
+	type TeamGoalPeriod = TeamGoalPeriod;
+	type TeamGoalPerMember = TeamGoalPerMember;
+	type TeamBonus = TeamBonus;
+	type ReferralShare = ReferralShare;
+	type ReferralPeriod = ReferralPeriod;
+	type MaxReferralRewards = MaxReferralRewards;
+	type StakingBoostRatio = StakingBoostRatio;
+	type StakingUnbondingPeriod = StakingUnbondingPeriod;
+	type LevelUpDurationPerLevel = LevelUpDurationPerLevel;
+	type LevelUpSpeedUpCost = LevelUpSpeedUpCost;
+	type BreedCooldown = BreedCooldown;
+	type MaxBreedCount = MaxBreedCount;
+	type FusionCost = FusionCost;
+	type WearPerEnergy = WearPerEnergy;
+	type WearPenaltyThreshold = WearPenaltyThreshold;
+	type RepairCost = RepairCost;
+	type Gems = Gems;
+	type GemId = GemId;
+	type MaxSockets = MaxSockets;
+	type LevelsPerSocket = LevelsPerSocket;
+	type MaxGemAbility = MaxGemAbility;
+	type UnsocketFee = UnsocketFee;
+	type TreasuryAccount = TreasuryAccount;
+	type MaxMerkleProofLength = MaxMerkleProofLength;
+	type EmissionPeriod = EmissionPeriod;
+	type InitialEmission = InitialEmission;
+	type HalvingPeriods = HalvingPeriods;
 }
 
 pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
@@ -284,11 +388,13 @@ pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
 			// id, owner, is_sufficient, min_balance
 			(0, ALICE, true, 1),
 			(1, ALICE, true, 1),
+			(2, CANDY, true, 1),
 		],
 		metadata: vec![
 			// id, name, symbol, decimals
 			(0, "PNT".into(), "PNT".into(), 12),
 			(1, "FUN".into(), "FUN".into(), 12),
+			(2, "BRD".into(), "BRD".into(), 12),
 		],
 		accounts: vec![
 			// id, account_id, balance
@@ -19,7 +19,7 @@ use p256::{
 };
 use rand_core::OsRng;
 use sha2::Digest;
-use sp_core::crypto::Ss58Codec;
+use sp_core::{crypto::Ss58Codec, H256};
 use sp_std::convert::TryInto;
 
 macro_rules! bvec {
@@ -46,6 +46,17 @@ fn produce_device_bind_vfe(
 	user: AccountId,
 	pub_key: DeviceKey,
 	key: SigningKey,
+) {
+	produce_sport_device_bind_vfe(producer, user, pub_key, key, SportType::JumpRope as SportId)
+}
+
+// produce a device of the sport type and bind a vfe
+fn produce_sport_device_bind_vfe(
+	producer: AccountId,
+	user: AccountId,
+	pub_key: DeviceKey,
+	key: SigningKey,
+	sport_type: SportId,
 ) {
 	//set incentive token
 	assert_ok!(VFE::set_incentive_token(Origin::root(), 1));
@@ -55,7 +66,7 @@ fn produce_device_bind_vfe(
 	assert_ok!(VFE::create_vfe_brand(
 		Origin::signed(CANDY),
 		bvec![0u8; 20],
-		SportType::JumpRope,
+		sport_type,
 		VFERarity::Common
 	));
 	assert_ok!(VFE::approve_mint(Origin::signed(CANDY), 1, 1, 10, Some((0, 10))));
@@ -152,19 +163,19 @@ fn create_vfe_brand_unit_test() {
 		assert_ok!(VFE::create_vfe_brand(
 			Origin::signed(CANDY),
 			bvec![0u8; 20],
-			SportType::JumpRope,
+			SportType::JumpRope as SportId,
 			VFERarity::Common
 		));
 
 		System::assert_has_event(Event::VFE(crate::Event::VFEBrandCreated {
 			who: CANDY,
 			brand_id: 1,
-			sport_type: SportType::JumpRope,
+			sport_type: SportType::JumpRope as SportId,
 			rarity: VFERarity::Common,
 			note: bvec![0u8; 20],
 		}));
 		let vfe_brand = VFE::get_vfe_brands(1).expect("can not find vfe brand");
-		assert_eq!(vfe_brand.sport_type, SportType::JumpRope);
+		assert_eq!(vfe_brand.sport_type, SportType::JumpRope as SportId);
 		assert_eq!(vfe_brand.rarity, VFERarity::Common);
 
 		let vfe_brand_owner = VFEUniques::collection_owner(1).expect("can not find collection");
@@ -179,7 +190,7 @@ fn approve_mint_unit_test() {
 		assert_ok!(VFE::create_vfe_brand(
 			Origin::signed(CANDY),
 			bvec![0u8; 20],
-			SportType::JumpRope,
+			SportType::JumpRope as SportId,
 			VFERarity::Common
 		));
 		assert_ok!(VFE::approve_mint(Origin::signed(CANDY), 1, 1, 10, None));
@@ -214,7 +225,7 @@ fn register_device_unit_test() {
 		assert_ok!(VFE::create_vfe_brand(
 			Origin::signed(CANDY),
 			bvec![0u8; 20],
-			SportType::JumpRope,
+			SportType::JumpRope as SportId,
 			VFERarity::Common
 		));
 		assert_ok!(VFE::approve_mint(Origin::signed(CANDY), 1, 1, 10, Some((0, 10))));
@@ -237,7 +248,7 @@ fn register_device_unit_test() {
 		assert_eq!(device.brand_id, 1);
 		assert_eq!(device.item_id, None);
 		assert_eq!(device.producer_id, 1);
-		assert_eq!(device.sport_type, SportType::JumpRope);
+		assert_eq!(device.sport_type, SportType::JumpRope as SportId);
 		assert_eq!(device.status, DeviceStatus::Registered);
 		assert_eq!(device.pk, puk);
 		assert_eq!(device.nonce, 0);
@@ -291,7 +302,7 @@ fn bind_device_should_work() {
 		// register producer
 		assert_ok!(VFE::producer_register(Origin::root(), ALICE));
 		// create vfe brand
-		assert_ok!(VFE::create_vfe_brand(Origin::signed(CANDY), bvec![0u8; 20], SportType::JumpRope, VFERarity::Common));
+		assert_ok!(VFE::create_vfe_brand(Origin::signed(CANDY), bvec![0u8; 20], SportType::JumpRope as SportId, VFERarity::Common));
 		assert_ok!(VFE::approve_mint(Origin::signed(CANDY), 1, 1, 10, Some((0,100))));
 		// register device
 		assert_ok!(VFE::register_device(Origin::signed(ALICE), puk, 1, 1));
@@ -347,12 +358,20 @@ fn bind_device_should_work() {
 		);
 		assert_eq!(vfe_brand_owner_balance, 70);
 
-		//unbind_device
+		//unbind_device, the fee is charged in the incentive token and burned
+		assert_noop!(
+			VFE::unbind_device(Origin::signed(user.clone()), 1, 1),
+			Error::<Test>::IncentiveTokenNotSet
+		);
+		assert_ok!(VFE::set_incentive_token(Origin::root(), 1));
+		assert_ok!(Currencies::mint_into(1, &user, 1));
 		assert_ok!(VFE::unbind_device(
 			Origin::signed(user.clone()),
 			1,
 			1,
 		));
+		System::assert_has_event(Event::VFE(crate::Event::UnbindFeePaid{who:user.clone(), asset_id:1, fee:1, destination:FeeDestination::Burn}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 0);
 		System::assert_has_event(Event::VFE(crate::Event::DeviceUnbound{owner:user.clone(), device_key:puk, brand_id:1, item_id:1}));
 		// assert!(!VFEBindDevices::<Test>::contains_key(1, 1));
 		let device = Devices::<Test>::get(puk).expect("can not find device");
@@ -425,6 +444,7 @@ fn bind_device_should_not_work() {
 		);
 
 		//unbind_device
+		assert_ok!(Currencies::mint_into(1, &user, 1));
 		assert_ok!(VFE::unbind_device(Origin::signed(user.clone()), 1, 1,));
 
 		assert_noop!(
@@ -454,206 +474,188 @@ fn bind_device_should_not_work() {
 }
 
 #[test]
-fn upload_training_report_unit_test() {
+fn unbind_settings_unit_test() {
 	new_test_ext().execute_with(|| {
 		let producer = ALICE;
 		let user = DANY;
 		let (key, pub_key) = generate_device_keypair();
 		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
-		Timestamp::set_timestamp(1668694716000);
-		// first report
-		let report = JumpRopeTrainingReport {
-			timestamp: 1668676716,
-			training_duration: 183,
-			total_jump_rope_count: 738,
-			average_speed: 140,
-			max_speed: 230,
-			max_jump_rope_count: 738,
-			interruptions: 0,
-			jump_rope_duration: 183,
-		};
-		let report_encode: Vec<u8> = report.into();
-		let report_sig = key.sign(&report_encode);
-		assert_ok!(VFE::upload_training_report(
-			Origin::none(),
-			pub_key,
-			BoundedVec::truncate_from(report_sig.to_vec()),
-			BoundedVec::truncate_from(report.into()),
-		));
-		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
-			owner: DANY,
-			brand_id: 1,
-			item_id: 1,
-			sport_type: SportType::JumpRope,
-			training_time: report.timestamp,
-			training_duration: report.training_duration,
-			training_count: report.total_jump_rope_count,
-			energy_used: 6,
-			asset_id: 1,
-			rewards: 9000000,
-		}));
-		System::assert_has_event(Event::Assets(pallet_assets::Event::Issued {
-			asset_id: 1,
-			owner: user.clone(),
-			total_supply: 9000000,
-		}));
-		let user_balance = <Currencies as fungibles::Inspect<AccountId>>::balance(1, &user);
-		assert_eq!(user_balance, 9000000);
-		let user_data = Users::<Test>::get(&user).expect("cannot find user");
-		// println!("user data: {:?}", user_data);
-		assert_eq!(user_data.energy, 2);
-		assert_eq!(user_data.earned, 9000000);
-		let vfe_data = VFEDetails::<Test>::get(1, 1).expect("cannot find vfe detail");
-		assert_eq!(vfe_data.remaining_battery, 94);
+		assert_ok!(Currencies::mint_into(0, &user, 1000));
 
-		// can not report same timestamp
+		let settings = UnbindSettings {
+			asset: Some(0),
+			fee: 100,
+			destination: FeeDestination::BrandOwner,
+			cooldown: 10,
+		};
 		assert_noop!(
-			VFE::upload_training_report(
-				Origin::none(),
-				pub_key,
-				BoundedVec::truncate_from(report_sig.to_vec()),
-				BoundedVec::truncate_from(report.into()),
-			),
-			Error::<Test>::ValueInvalid
+			VFE::set_unbind_settings(Origin::signed(BOB), settings),
+			DispatchError::BadOrigin
 		);
-		Timestamp::set_timestamp(1668847349000);
-		// second report
-		let mut report = JumpRopeTrainingReport {
-			timestamp: 1668827349,
-			training_duration: 1,
-			total_jump_rope_count: 738,
-			average_speed: 140,
-			max_speed: 230,
-			max_jump_rope_count: 738,
-			interruptions: 0,
-			jump_rope_duration: 1,
-		};
+		assert_ok!(VFE::set_unbind_settings(Origin::root(), settings));
+		System::assert_has_event(Event::VFE(crate::Event::UnbindSettingsUpdated { settings }));
 
-		// user insufficient training
-		let report_encode: Vec<u8> = report.into();
-		let report_sig = key.sign(&report_encode);
-		assert_noop!(
-			VFE::upload_training_report(
-				Origin::none(),
-				pub_key,
-				BoundedVec::truncate_from(report_sig.to_vec()),
-				BoundedVec::truncate_from(report.into()),
-			),
-			Error::<Test>::InsufficientTraining
+		// the fee is paid to the brand owner
+		let brand_owner_balance = <Currencies as MultiAssets<AccountId>>::balance(0, &CANDY);
+		assert_ok!(VFE::unbind_device(Origin::signed(user.clone()), 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::UnbindFeePaid {
+			who: user.clone(),
+			asset_id: 0,
+			fee: 100,
+			destination: FeeDestination::BrandOwner,
+		}));
+		assert_eq!(
+			<Currencies as MultiAssets<AccountId>>::balance(0, &CANDY),
+			brand_owner_balance + 100
 		);
+		assert_eq!(VFEBindCooldowns::<Test>::get(1, 1), Some(11));
+		assert_eq!(DeviceBindCooldowns::<Test>::get(pub_key), Some(11));
 
-		// user training report time is expired
-		Timestamp::set_timestamp(1668914749000);
-		let report_encode: Vec<u8> = report.into();
-		let report_sig = key.sign(&report_encode);
+		// the vfe and the device can not be bound again during the cooldown
+		let account_nonce = 2u32;
+		let account_rip160 = Ripemd::Hash::hash(user.encode().as_ref());
+		let mut msg: Vec<u8> = Vec::new();
+		msg.extend(account_nonce.to_le_bytes().to_vec());
+		msg.extend(account_rip160.to_vec());
+		let signature = key.sign(msg.as_ref());
 		assert_noop!(
-			VFE::upload_training_report(
+			VFE::bind_device(
 				Origin::none(),
+				user.clone(),
 				pub_key,
-				BoundedVec::truncate_from(report_sig.to_vec()),
-				BoundedVec::truncate_from(report.into()),
+				signature.to_vec().try_into().unwrap(),
+				account_nonce,
+				Some(1),
 			),
-			Error::<Test>::TrainingReportTimeExpired
+			Error::<Test>::BindCooldownNotExpired
 		);
 
-		// sufficient training
-		report.training_duration = 183;
-		report.jump_rope_duration = 183;
-		report.timestamp = 1668904749;
-		let report_encode: Vec<u8> = report.into();
-		let report_sig = key.sign(&report_encode);
-		assert_ok!(VFE::upload_training_report(
+		System::set_block_number(11);
+		assert_ok!(VFE::bind_device(
 			Origin::none(),
+			user.clone(),
 			pub_key,
-			BoundedVec::truncate_from(report_sig.to_vec()),
-			BoundedVec::truncate_from(report.into()),
+			signature.to_vec().try_into().unwrap(),
+			account_nonce,
+			Some(1),
 		));
-		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
-			owner: DANY,
+		assert_eq!(VFEBindCooldowns::<Test>::get(1, 1), None);
+		assert_eq!(DeviceBindCooldowns::<Test>::get(pub_key), None);
+
+		// the fee is paid to the treasury
+		assert_ok!(VFE::set_unbind_settings(
+			Origin::root(),
+			UnbindSettings { destination: FeeDestination::Treasury, cooldown: 0, ..settings }
+		));
+		let treasury_balance = <Currencies as MultiAssets<AccountId>>::balance(0, &TOM);
+		assert_ok!(VFE::unbind_device(Origin::signed(user.clone()), 1, 1));
+		assert_eq!(
+			<Currencies as MultiAssets<AccountId>>::balance(0, &TOM),
+			treasury_balance + 100
+		);
+		assert_eq!(VFEBindCooldowns::<Test>::get(1, 1), None);
+	});
+}
+
+#[test]
+fn void_device_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer.clone(), user.clone(), pub_key, key.clone());
+
+		// void a registered device, the minting fee is refunded to the producer owner
+		let (_, registered_key) = generate_device_keypair();
+		assert_ok!(VFE::register_device(Origin::signed(producer.clone()), registered_key, 1, 1));
+		let producer_balance = <Currencies as MultiAssets<AccountId>>::balance(0, &producer);
+		assert_noop!(
+			VFE::void_device(Origin::signed(BOB), registered_key),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(VFE::void_device(Origin::signed(CANDY), registered_key));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceVoided {
+			operator: Some(CANDY),
+			device_key: registered_key,
 			brand_id: 1,
-			item_id: 1,
-			sport_type: SportType::JumpRope,
-			training_time: report.timestamp,
-			training_duration: report.training_duration,
-			training_count: report.total_jump_rope_count,
-			energy_used: 2,
-			asset_id: 1,
-			rewards: 4200000,
+			item_id: None,
+			refund: Some((0, 10)),
 		}));
-		System::assert_has_event(Event::Assets(pallet_assets::Event::Issued {
-			asset_id: 1,
+		assert_eq!(
+			<Currencies as MultiAssets<AccountId>>::balance(0, &producer),
+			producer_balance + 10
+		);
+		let approve = VFEApprovals::<Test>::get(1, 1).expect("approve is nil");
+		assert_eq!(approve.remaining_mint, 9);
+		assert_eq!(approve.registered, 0);
+		assert_eq!(approve.locked_of_mint, 0);
+		let device = Devices::<Test>::get(registered_key).expect("device is nil");
+		assert_eq!(device.status, DeviceStatus::Voided);
+		assert_eq!(device.mint_cost, None);
+		assert_noop!(VFE::void_device(Origin::root(), registered_key), Error::<Test>::DeviceVoided);
+		assert_noop!(
+			VFE::register_device(Origin::signed(producer.clone()), registered_key, 1, 1),
+			Error::<Test>::DeviceExisted
+		);
+
+		// void a bound device, the vfe is unbound and the device can not earn rewards
+		assert_ok!(VFE::void_device(Origin::signed(producer.clone()), pub_key));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceUnbound {
 			owner: user.clone(),
-			total_supply: 4200000,
+			device_key: pub_key,
+			brand_id: 1,
+			item_id: 1,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceVoided {
+			operator: Some(producer),
+			device_key: pub_key,
+			brand_id: 1,
+			item_id: Some(1),
+			refund: None,
 		}));
+		let vfe = VFEDetails::<Test>::get(1, 1).expect("cannot find vfe detail");
+		assert_eq!(vfe.device_key, None);
+		let device = Devices::<Test>::get(pub_key).expect("device is nil");
+		assert_eq!(device.status, DeviceStatus::Voided);
+		assert_eq!(device.item_id, None);
 
-		// if user no energy, can not report training
-		report.timestamp = 1668905749;
-		let report_encode: Vec<u8> = report.into();
+		Timestamp::set_timestamp(1668694716000);
+		let report_encode: Vec<u8> = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		}
+		.into();
 		let report_sig = key.sign(&report_encode);
 		assert_noop!(
 			VFE::upload_training_report(
 				Origin::none(),
 				pub_key,
 				BoundedVec::truncate_from(report_sig.to_vec()),
-				BoundedVec::truncate_from(report.into()),
+				BoundedVec::truncate_from(report_encode),
 			),
-			Error::<Test>::EnergyExhausted
+			Error::<Test>::DeviceVoided
 		);
 	});
 }
 
 #[test]
-fn global_energy_recovery_and_daily_earned_reset_unit_test() {
-	new_test_ext().execute_with(|| {
-		assert_eq!(LastEnergyRecovery::<Test>::get(), 0);
-		assert_eq!(LastDailyEarnedReset::<Test>::get(), 0);
-		run_to_block(5);
-		assert_eq!(LastEnergyRecovery::<Test>::get(), 0);
-		assert_eq!(LastDailyEarnedReset::<Test>::get(), 0);
-		run_to_block(9);
-		assert_eq!(LastEnergyRecovery::<Test>::get(), 8);
-		assert_eq!(LastDailyEarnedReset::<Test>::get(), 0);
-		System::assert_has_event(Event::VFE(crate::Event::GlobalEnergyRecoveryOccurred {
-			block_number: 8,
-		}));
-		run_to_block(17);
-		assert_eq!(LastEnergyRecovery::<Test>::get(), 16);
-		assert_eq!(LastDailyEarnedReset::<Test>::get(), 0);
-		System::assert_has_event(Event::VFE(crate::Event::GlobalEnergyRecoveryOccurred {
-			block_number: 16,
-		}));
-		run_to_block(20);
-		assert_eq!(LastEnergyRecovery::<Test>::get(), 16);
-		run_to_block(25);
-		assert_eq!(LastEnergyRecovery::<Test>::get(), 24);
-		assert_eq!(LastDailyEarnedReset::<Test>::get(), 24);
-		System::assert_has_event(Event::VFE(crate::Event::GlobalDailyEarnedResetOccurred {
-			block_number: 24,
-		}));
-		run_to_block(9889);
-		let last_update =
-			9889u64.saturating_div(EnergyRecoveryDuration::get()) * EnergyRecoveryDuration::get();
-		assert_eq!(LastEnergyRecovery::<Test>::get(), last_update);
-		assert_eq!(LastDailyEarnedReset::<Test>::get(), last_update);
-		System::assert_has_event(Event::VFE(crate::Event::GlobalEnergyRecoveryOccurred {
-			block_number: 9888,
-		}));
-		System::assert_has_event(Event::VFE(crate::Event::GlobalDailyEarnedResetOccurred {
-			block_number: 9888,
-		}));
-	});
-}
-
-#[test]
-fn user_restore_unit_test() {
+fn void_device_discards_unpaid_rewards_unit_test() {
 	new_test_ext().execute_with(|| {
 		let producer = ALICE;
 		let user = DANY;
 		let (key, pub_key) = generate_device_keypair();
-		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
-
-		Timestamp::set_timestamp(1668686716000);
+		produce_device_bind_vfe(producer.clone(), user.clone(), pub_key, key.clone());
+		ChallengeWindow::set(10);
 
+		// the rewards of the first report are held in the quarantine
+		QuarantinedDevices::<Test>::insert(pub_key, 1);
+		Timestamp::set_timestamp(1668694716000);
 		let report = JumpRopeTrainingReport {
 			timestamp: 1668676716,
 			training_duration: 183,
@@ -664,331 +666,3382 @@ fn user_restore_unit_test() {
 			interruptions: 0,
 			jump_rope_duration: 183,
 		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		assert!(HeldRewards::<Test>::get(pub_key, report.timestamp).is_some());
 
+		// the rewards of the second report are pending in the challenge window
+		QuarantinedDevices::<Test>::remove(pub_key);
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
 		let report_encode: Vec<u8> = report.into();
 		let report_sig = key.sign(&report_encode);
 		assert_ok!(VFE::upload_training_report(
 			Origin::none(),
 			pub_key,
-			report_sig.to_vec().try_into().unwrap(),
-			report_encode.try_into().unwrap()
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
 		));
+		assert!(PendingReports::<Test>::get(pub_key, report.timestamp).is_some());
+		assert_eq!(Users::<Test>::get(&user).unwrap().earned, 9000000 + 4200000);
 
-		//after global energy recovery occurred
-		run_to_block(9);
-
-		// let call = Call::user_restore { who: user.clone() };
-		// assert_ok!(<VFE as ValidateUnsigned>::validate_unsigned(TransactionSource::Local,
-		// &call));
-		assert_ok!(VFE::user_restore(Origin::signed(user.clone())));
-		System::assert_has_event(Event::VFE(crate::Event::UserEnergyRestored {
-			who: user.clone(),
-			restored_amount: 2,
-		}));
-		let user_data = Users::<Test>::get(&user).expect("cannot find user");
-		// println!("user data: {:?}", user_data);
-		assert_eq!(user_data.energy, 4);
-		assert!(user_data.earned != 0);
-
-		//after repeatedly global energy recovery occurred
-		run_to_block(229);
-		assert_ok!(VFE::user_restore(Origin::signed(user.clone())));
-		System::assert_has_event(Event::VFE(crate::Event::UserEnergyRestored {
-			who: user.clone(),
-			restored_amount: 4,
-		}));
-		let user_data = Users::<Test>::get(&user).expect("cannot find user");
-		assert_eq!(user_data.energy, 8);
-		assert!(user_data.earned == 0);
-	});
-}
+		// the unpaid rewards are discarded when the device is voided
+		assert_ok!(VFE::void_device(Origin::signed(producer), pub_key));
+		assert_eq!(HeldRewards::<Test>::iter_prefix(pub_key).count(), 0);
+		assert_eq!(PendingReports::<Test>::iter_prefix(pub_key).count(), 0);
+		assert_eq!(Users::<Test>::get(&user).unwrap().earned, 0);
+		assert_eq!(VFE::current_emission().minted, 0);
+		System::set_block_number(12);
+		assert_noop!(
+			VFE::claim_report_rewards(Origin::signed(user.clone()), pub_key, report.timestamp),
+			Error::<Test>::ReportNotFound
+		);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 0);
+	});
+}
 
 #[test]
-fn restore_power_unit_test() {
+fn replace_device_unit_test() {
 	new_test_ext().execute_with(|| {
 		let producer = ALICE;
 		let user = DANY;
 		let (key, pub_key) = generate_device_keypair();
-		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		produce_device_bind_vfe(producer.clone(), user.clone(), pub_key, key.clone());
+		let (_, new_key) = generate_device_keypair();
 
-		Timestamp::set_timestamp(1668686716000);
+		assert_noop!(
+			VFE::replace_device(Origin::signed(BOB), pub_key, new_key, None),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_noop!(
+			VFE::replace_device(Origin::signed(user.clone()), pub_key, new_key, None),
+			Error::<Test>::DeviceSignatureInvalid
+		);
+		assert_noop!(
+			VFE::replace_device(Origin::signed(producer.clone()), pub_key, pub_key, None),
+			Error::<Test>::DeviceExisted
+		);
 
-		let report = JumpRopeTrainingReport {
-			timestamp: 1668676716,
-			training_duration: 183,
-			total_jump_rope_count: 738,
-			average_speed: 140,
-			max_speed: 230,
-			max_jump_rope_count: 738,
-			interruptions: 0,
-			jump_rope_duration: 183,
-		};
-		let report_encode: Vec<u8> = report.into();
-		let report_sig = key.sign(&report_encode);
-		assert_ok!(VFE::upload_training_report(
-			Origin::none(),
+		// the vfe owner replaces the device with the signature of the old device
+		let signature: Signature = key.sign(new_key.as_ref());
+		assert_ok!(VFE::replace_device(
+			Origin::signed(user.clone()),
 			pub_key,
-			report_sig.to_vec().try_into().unwrap(),
-			report_encode.try_into().unwrap()
+			new_key,
+			Some(signature.to_vec().try_into().unwrap())
 		));
-
-		assert_ok!(VFE::restore_power(Origin::signed(user.clone()), 1, 1, 3));
-		System::assert_has_event(Event::VFE(crate::Event::PowerRestored {
-			owner: user.clone(),
-			charge_amount: 3,
-			use_amount: 2100000,
+		System::assert_has_event(Event::VFE(crate::Event::DeviceReplaced {
+			operator: user.clone(),
+			old_device_key: pub_key,
+			new_device_key: new_key,
 			brand_id: 1,
-			item_id: 1,
+			item_id: Some(1),
 		}));
-
-		let vfe_data = VFEDetails::<Test>::get(1, 1).expect("cannot find vfe detail");
-		assert_eq!(vfe_data.remaining_battery, 97);
-
-		assert_noop!(
-			VFE::restore_power(Origin::signed(user.clone()), 1, 1, 4),
-			Error::<Test>::ValueOverflow
-		);
-
-		// user balance of asset is no enough to restore pow
-		assert_ok!(Currencies::transfer(Origin::signed(user.clone()), BOB, 1, 6400000, false));
+		let vfe = VFEDetails::<Test>::get(1, 1).expect("cannot find vfe detail");
+		assert_eq!(vfe.device_key, Some(new_key));
+		let new_device = Devices::<Test>::get(new_key).expect("device is nil");
+		assert_eq!(new_device.pk, new_key);
+		assert_eq!(new_device.item_id, Some(1));
+		assert_eq!(new_device.nonce, 1);
+		assert_eq!(new_device.status, DeviceStatus::Activated);
+		let old_device = Devices::<Test>::get(pub_key).expect("device is nil");
+		assert_eq!(old_device.status, DeviceStatus::Voided);
+		assert_eq!(old_device.item_id, None);
 		assert_noop!(
-			VFE::restore_power(Origin::signed(user.clone()), 1, 1, 3),
-			pallet_assets::Error::<Test>::BalanceLow
+			VFE::replace_device(Origin::signed(producer.clone()), pub_key, new_key, None),
+			Error::<Test>::DeviceExisted
 		);
 
-		// after transfer user balance of asset is enough to restore pow
-		assert_ok!(Currencies::transfer(Origin::signed(BOB), user.clone(), 1, 6400000, false));
-		assert_ok!(VFE::restore_power(Origin::signed(user.clone()), 1, 1, 3));
-		assert_noop!(
-			VFE::restore_power(Origin::signed(user.clone()), 1, 1, 3),
-			Error::<Test>::VFEFullyCharged
-		);
+		// the producer owner replaces the device directly
+		let (_, third_key) = generate_device_keypair();
+		assert_ok!(VFE::replace_device(Origin::signed(producer), new_key, third_key, None));
+		let vfe = VFEDetails::<Test>::get(1, 1).expect("cannot find vfe detail");
+		assert_eq!(vfe.device_key, Some(third_key));
+		assert_eq!(Devices::<Test>::get(new_key).unwrap().status, DeviceStatus::Voided);
 	});
 }
 
 #[test]
-fn level_up_unit_test() {
+fn bind_certified_device_unit_test() {
 	new_test_ext().execute_with(|| {
 		let producer = ALICE;
 		let user = DANY;
-		let (key, pub_key) = generate_device_keypair();
-		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		assert_ok!(VFE::set_incentive_token(Origin::root(), 1));
+		assert_ok!(VFE::producer_register(Origin::root(), producer.clone()));
+		assert_ok!(VFE::create_vfe_brand(
+			Origin::signed(CANDY),
+			bvec![0u8; 20],
+			SportType::JumpRope as SportId,
+			VFERarity::Common
+		));
+		assert_ok!(VFE::approve_mint(Origin::signed(CANDY), 1, 1, 10, Some((0, 10))));
 
-		assert_noop!(VFE::level_up(Origin::signed(user.clone()), 1, 2), Error::<Test>::VFENotExist);
+		// the producer registers the certificate key once
+		let (cert_key, cert_pub_key) = generate_device_keypair();
 		assert_noop!(
-			VFE::level_up(Origin::signed(BOB), 1, 1),
+			VFE::set_producer_cert_key(Origin::signed(BOB), 1, Some(cert_pub_key)),
 			Error::<Test>::OperationIsNotAllowed
 		);
-
-		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
-		let user_info = Users::<Test>::get(&user).unwrap();
-		let level_up_costs = VFE::calculate_level_up_costs(&vfe, &user_info);
-		// println!("level_up_costs: {}", level_up_costs);
-
-		//issue some asset to user, then level up VFE
-		assert_ok!(Currencies::mint_into(1, &user, 180000000));
-		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
-		System::assert_has_event(Event::VFE(crate::Event::VFELevelUp {
-			brand_id: 1,
-			item_id: 1,
-			level_up: 1,
-			cost: level_up_costs,
+		assert_ok!(VFE::set_producer_cert_key(
+			Origin::signed(producer.clone()),
+			1,
+			Some(cert_pub_key)
+		));
+		System::assert_has_event(Event::VFE(crate::Event::ProducerCertKeySet {
+			producer_id: 1,
+			cert_key: Some(cert_pub_key),
 		}));
-		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
-		assert_eq!(vfe.level, 1);
-		assert_eq!(vfe.available_points, 4);
 
-		let user_info = Users::<Test>::get(&user).unwrap();
-		assert_eq!(user_info.energy_total, 8);
-		assert_eq!(user_info.earning_cap, 1000 * 100000);
+		let (key, pub_key) = generate_device_keypair();
+		let account_nonce = 1u32;
+		let account_rip160 = Ripemd::Hash::hash(user.encode().as_ref());
+		let mut msg: Vec<u8> = Vec::new();
+		msg.extend(account_nonce.to_le_bytes().to_vec());
+		msg.extend(account_rip160.to_vec());
+		let signature: Signature = key.sign(msg.as_ref());
 
-		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
-		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+		// the certificate signed by other key is invalid
+		let (fake_key, _) = generate_device_keypair();
+		let fake_cert: Signature = fake_key.sign(&(pub_key, 1u32).encode());
+		assert_noop!(
+			VFE::bind_certified_device(
+				Origin::none(),
+				user.clone(),
+				pub_key,
+				signature.to_vec().try_into().unwrap(),
+				account_nonce,
+				DeviceCertificate {
+					producer_id: 1,
+					brand_id: 1,
+					signature: fake_cert.to_vec().try_into().unwrap(),
+				},
+				None
+			),
+			Error::<Test>::DeviceCertificateInvalid
+		);
 
-		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
-		let user_info = Users::<Test>::get(&user).unwrap();
-		let level_up_costs = VFE::calculate_level_up_costs(&vfe, &user_info);
-		// let user_balance = Currencies::balance(1, &user);
-		// println!("user_balance = {}, level_up_costs = {}", user_balance, level_up_costs);
-		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
-		System::assert_has_event(Event::VFE(crate::Event::VFELevelUp {
+		// the device is registered lazily at the first binding
+		let producer_balance = <Currencies as MultiAssets<AccountId>>::balance(0, &producer);
+		let cert: Signature = cert_key.sign(&(pub_key, 1u32).encode());
+		let certificate = DeviceCertificate {
+			producer_id: 1,
+			brand_id: 1,
+			signature: cert.to_vec().try_into().unwrap(),
+		};
+		assert_ok!(VFE::bind_certified_device(
+			Origin::none(),
+			user.clone(),
+			pub_key,
+			signature.to_vec().try_into().unwrap(),
+			account_nonce,
+			certificate.clone(),
+			None
+		));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceRegistered {
+			operator: producer.clone(),
+			producer_id: 1,
+			device_key: pub_key,
+			brand_id: 1,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceBound {
+			owner: user.clone(),
+			device_key: pub_key,
 			brand_id: 1,
 			item_id: 1,
-			level_up: 4,
-			cost: level_up_costs,
 		}));
-		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
-		assert_eq!(vfe.level, 4);
-		assert_eq!(vfe.available_points, 16);
-
-		let user_info = Users::<Test>::get(&user).unwrap();
-		assert_eq!(user_info.energy_total, 16);
-		assert_eq!(user_info.earning_cap, 2500 * 100000);
+		assert_eq!(
+			<Currencies as MultiAssets<AccountId>>::balance(0, &producer),
+			producer_balance - 10
+		);
+		let device = Devices::<Test>::get(pub_key).expect("device is nil");
+		assert_eq!(device.status, DeviceStatus::Activated);
+		assert_eq!(device.item_id, Some(1));
+		let approve = VFEApprovals::<Test>::get(1, 1).expect("approve is nil");
+		assert_eq!(approve.remaining_mint, 9);
+		assert_eq!(approve.activated, 1);
 
-		// let level_up_costs = VFE::calculate_level_up_costs(&vfe, &user_info);
-		// let user_balance = Currencies::balance(1, &user);
-		// println!("user_balance = {}, level_up_costs = {}", user_balance, level_up_costs);
+		// the signature can not be replayed
 		assert_noop!(
-			VFE::level_up(Origin::signed(user.clone()), 1, 1),
-			pallet_assets::Error::<Test>::BalanceLow
+			VFE::bind_certified_device(
+				Origin::none(),
+				user.clone(),
+				pub_key,
+				signature.to_vec().try_into().unwrap(),
+				account_nonce,
+				certificate,
+				None
+			),
+			Error::<Test>::NonceMustGreatThanBefore
 		);
 	});
 }
 
+// hash the sorted pair of nodes of the device batch
+fn hash_device_batch_pair(a: H256, b: H256) -> H256 {
+	let (left, right) = if a <= b { (a, b) } else { (b, a) };
+	<Test as frame_system::Config>::Hashing::hash_of(&(left, right))
+}
+
 #[test]
-fn increase_ability_unit_test() {
+fn device_batch_unit_test() {
 	new_test_ext().execute_with(|| {
 		let producer = ALICE;
 		let user = DANY;
+		assert_ok!(VFE::set_incentive_token(Origin::root(), 1));
+		assert_ok!(VFE::producer_register(Origin::root(), producer.clone()));
+		assert_ok!(VFE::create_vfe_brand(
+			Origin::signed(CANDY),
+			bvec![0u8; 20],
+			SportType::JumpRope as SportId,
+			VFERarity::Common
+		));
+		assert_ok!(VFE::approve_mint(Origin::signed(CANDY), 1, 1, 10, Some((0, 10))));
+
+		// build the Merkle tree of 3 devices
 		let (key, pub_key) = generate_device_keypair();
-		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		let (_, second_key) = generate_device_keypair();
+		let (_, third_key) = generate_device_keypair();
+		let leaves: Vec<H256> = [pub_key, second_key, third_key]
+			.iter()
+			.map(|k| <Test as frame_system::Config>::Hashing::hash(k.as_ref()))
+			.collect();
+		let first_pair = hash_device_batch_pair(leaves[0], leaves[1]);
+		let root = hash_device_batch_pair(first_pair, leaves[2]);
 
-		assert_noop!(VFE::level_up(Origin::signed(user.clone()), 1, 2), Error::<Test>::VFENotExist);
 		assert_noop!(
-			VFE::level_up(Origin::signed(BOB), 1, 1),
+			VFE::commit_device_batch(Origin::signed(BOB), 1, 1, root, 3),
 			Error::<Test>::OperationIsNotAllowed
 		);
+		assert_ok!(VFE::commit_device_batch(Origin::signed(producer.clone()), 1, 1, root, 3));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceBatchCommitted {
+			operator: producer.clone(),
+			producer_id: 1,
+			brand_id: 1,
+			root,
+			count: 3,
+		}));
+		assert_noop!(
+			VFE::commit_device_batch(Origin::signed(producer.clone()), 1, 1, root, 3),
+			Error::<Test>::DeviceBatchExisted
+		);
+		let approve = VFEApprovals::<Test>::get(1, 1).expect("approve is nil");
+		assert_eq!(approve.remaining_mint, 7);
+		assert_eq!(approve.registered, 3);
+		assert_eq!(approve.locked_of_mint, 30);
+		assert_eq!(Devices::<Test>::get(pub_key), None);
 
-		//issue some asset to user, then level up VFE
-		assert_ok!(Currencies::mint_into(1, &user, 180000000));
-		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
-		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
-		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
-		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
-		let origin_vfe = VFEDetails::<Test>::get(1, 1).unwrap();
-		assert_eq!(origin_vfe.level, 4);
-		assert_eq!(origin_vfe.available_points, 16);
+		let account_nonce = 1u32;
+		let account_rip160 = Ripemd::Hash::hash(user.encode().as_ref());
+		let mut msg: Vec<u8> = Vec::new();
+		msg.extend(account_nonce.to_le_bytes().to_vec());
+		msg.extend(account_rip160.to_vec());
+		let signature: Signature = key.sign(msg.as_ref());
 
-		let add_point = VFEAbility { efficiency: 10, skill: 10, luck: 10, durable: 10 };
+		// the proof of other device is invalid
 		assert_noop!(
-			VFE::increase_ability(Origin::signed(user.clone()), 1, 1, add_point),
-			Error::<Test>::ValueInvalid
+			VFE::bind_batch_device(
+				Origin::none(),
+				user.clone(),
+				pub_key,
+				signature.to_vec().try_into().unwrap(),
+				account_nonce,
+				DeviceBatchProof { brand_id: 1, root, proof: bvec![first_pair] },
+				None
+			),
+			Error::<Test>::MerkleProofInvalid
 		);
 
-		let add_point = VFEAbility { efficiency: 3, skill: 4, luck: 3, durable: 4 };
-		assert_ok!(VFE::increase_ability(Origin::signed(user.clone()), 1, 1, add_point));
-		System::assert_has_event(Event::VFE(crate::Event::VFEAbilityIncreased {
+		// the device is claimed at the first binding
+		assert_ok!(VFE::bind_batch_device(
+			Origin::none(),
+			user.clone(),
+			pub_key,
+			signature.to_vec().try_into().unwrap(),
+			account_nonce,
+			DeviceBatchProof { brand_id: 1, root, proof: bvec![leaves[1], leaves[2]] },
+			None
+		));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceBatchClaimed {
+			device_key: pub_key,
+			brand_id: 1,
+			root,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceBound {
+			owner: user.clone(),
+			device_key: pub_key,
 			brand_id: 1,
 			item_id: 1,
 		}));
+		assert_eq!(DeviceBatches::<Test>::get(1, root).unwrap().claimed, 1);
+		let device = Devices::<Test>::get(pub_key).expect("device is nil");
+		assert_eq!(device.status, DeviceStatus::Activated);
+		let approve = VFEApprovals::<Test>::get(1, 1).expect("approve is nil");
+		assert_eq!(approve.registered, 2);
+		assert_eq!(approve.activated, 1);
+		assert_eq!(approve.locked_of_mint, 20);
 
-		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
-		assert_eq!(vfe.level, 4);
-		assert_eq!(vfe.available_points, 2);
+		// close the batch, the unclaimed devices are refunded
+		let producer_balance = <Currencies as MultiAssets<AccountId>>::balance(0, &producer);
+		assert_ok!(VFE::close_device_batch(Origin::signed(producer.clone()), 1, root));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceBatchClosed {
+			operator: producer.clone(),
+			producer_id: 1,
+			brand_id: 1,
+			root,
+			unclaimed: 2,
+		}));
 		assert_eq!(
-			vfe.current_ability.efficiency,
-			origin_vfe.current_ability.efficiency + add_point.efficiency
+			<Currencies as MultiAssets<AccountId>>::balance(0, &producer),
+			producer_balance + 20
 		);
-		assert_eq!(vfe.current_ability.skill, origin_vfe.current_ability.skill + add_point.skill);
-		assert_eq!(vfe.current_ability.luck, origin_vfe.current_ability.luck + add_point.luck);
-		assert_eq!(
-			vfe.current_ability.durable,
-			origin_vfe.current_ability.durable + add_point.durable
+		let approve = VFEApprovals::<Test>::get(1, 1).expect("approve is nil");
+		assert_eq!(approve.remaining_mint, 9);
+		assert_eq!(approve.registered, 0);
+		assert_eq!(approve.locked_of_mint, 0);
+		assert_eq!(DeviceBatches::<Test>::get(1, root), None);
+		assert_noop!(
+			VFE::close_device_batch(Origin::signed(producer), 1, root),
+			Error::<Test>::DeviceBatchNotFound
 		);
 	});
 }
 
 #[test]
-fn verify_bind_device_message_unit_test() {
+fn upload_training_report_unit_test() {
 	new_test_ext().execute_with(|| {
-		// verify_bind_device_message
-		let x = hex!["0339d3e6e837d675ce77e85d708caf89ddcdbf53c8e510775c9cb9ec06282475a0"];
-		// let pubkey = VerifyingKey::from_sec1_bytes(x).unwrap();
-		let sig = Signature::from_bytes(&hex!["d851b2fcd63bf78a52008e043cb28c47523c2ee2c3c9425c8b0a005e2f6f53b101690e030e0b2c8c6ae99c3a40c02f58dffd1fded34f84953d0e6ea671d83930"]).unwrap();
-		let nonce = 0u32;
-		let account_id = AccountId::new(hex!["764f41f48346146c043c5d0c7948f4b24a7877c649d5b72973850ccbe0f86840"]) ;
-		let account_rip160 = Ripemd::Hash::hash(account_id.encode().as_ref());
-		//13a7c41c6fa23d80f586051c6ccce5eb60192a20
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+		// first report
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
+			owner: DANY,
+			brand_id: 1,
+			item_id: 1,
+			sport_type: SportType::JumpRope as SportId,
+			training_time: report.timestamp,
+			training_duration: report.training_duration,
+			training_count: report.total_jump_rope_count,
+			energy_used: 6,
+			asset_id: 1,
+			rewards: 9000000,
+		}));
+		System::assert_has_event(Event::Assets(pallet_assets::Event::Issued {
+			asset_id: 1,
+			owner: user.clone(),
+			total_supply: 9000000,
+		}));
+		let user_balance = <Currencies as fungibles::Inspect<AccountId>>::balance(1, &user);
+		assert_eq!(user_balance, 9000000);
+		let user_data = Users::<Test>::get(&user).expect("cannot find user");
+		// println!("user data: {:?}", user_data);
+		assert_eq!(user_data.energy, 2);
+		assert_eq!(user_data.earned, 9000000);
+		let vfe_data = VFEDetails::<Test>::get(1, 1).expect("cannot find vfe detail");
+		assert_eq!(vfe_data.remaining_battery, 94);
+
+		// can not report same timestamp
+		assert_noop!(
+			VFE::upload_training_report(
+				Origin::none(),
+				pub_key,
+				BoundedVec::truncate_from(report_sig.to_vec()),
+				BoundedVec::truncate_from(report.into()),
+			),
+			Error::<Test>::ValueInvalid
+		);
+		Timestamp::set_timestamp(1668847349000);
+		// second report
+		let mut report = JumpRopeTrainingReport {
+			timestamp: 1668827349,
+			training_duration: 1,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 1,
+		};
+
+		// user insufficient training
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_noop!(
+			VFE::upload_training_report(
+				Origin::none(),
+				pub_key,
+				BoundedVec::truncate_from(report_sig.to_vec()),
+				BoundedVec::truncate_from(report.into()),
+			),
+			Error::<Test>::InsufficientTraining
+		);
+
+		// user training report time is expired
+		Timestamp::set_timestamp(1668914749000);
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_noop!(
+			VFE::upload_training_report(
+				Origin::none(),
+				pub_key,
+				BoundedVec::truncate_from(report_sig.to_vec()),
+				BoundedVec::truncate_from(report.into()),
+			),
+			Error::<Test>::TrainingReportTimeExpired
+		);
+
+		// sufficient training
+		report.training_duration = 183;
+		report.jump_rope_duration = 183;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
+			owner: DANY,
+			brand_id: 1,
+			item_id: 1,
+			sport_type: SportType::JumpRope as SportId,
+			training_time: report.timestamp,
+			training_duration: report.training_duration,
+			training_count: report.total_jump_rope_count,
+			energy_used: 2,
+			asset_id: 1,
+			rewards: 4200000,
+		}));
+		System::assert_has_event(Event::Assets(pallet_assets::Event::Issued {
+			asset_id: 1,
+			owner: user.clone(),
+			total_supply: 4200000,
+		}));
+
+		// if user no energy, can not report training
+		report.timestamp = 1668905749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_noop!(
+			VFE::upload_training_report(
+				Origin::none(),
+				pub_key,
+				BoundedVec::truncate_from(report_sig.to_vec()),
+				BoundedVec::truncate_from(report.into()),
+			),
+			Error::<Test>::EnergyExhausted
+		);
+	});
+}
+
+#[test]
+fn upload_running_training_report_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_sport_device_bind_vfe(
+			producer,
+			user.clone(),
+			pub_key,
+			key.clone(),
+			SportType::Running as SportId,
+		);
+		Timestamp::set_timestamp(1668694716000);
+
+		// the pace does not match the distance and duration
+		let mut report = RunningTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 1800,
+			distance: 5000,
+			steps: 6600,
+			average_pace: 200,
+			average_cadence: 220,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_noop!(
+			VFE::upload_training_report(
+				Origin::none(),
+				pub_key,
+				BoundedVec::truncate_from(report_sig.to_vec()),
+				BoundedVec::truncate_from(report_encode),
+			),
+			Error::<Test>::TrainingReportOutOfNormalRange
+		);
+
+		// a jump rope report can not be uploaded by a running device
+		let jump_rope_report: Vec<u8> = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		}
+		.into();
+		let report_sig = key.sign(&jump_rope_report);
+		assert_noop!(
+			VFE::upload_training_report(
+				Origin::none(),
+				pub_key,
+				BoundedVec::truncate_from(report_sig.to_vec()),
+				BoundedVec::truncate_from(jump_rope_report),
+			),
+			Error::<Test>::ValueInvalid
+		);
+
+		report.average_pace = 360;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report_encode),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
+			owner: DANY,
+			brand_id: 1,
+			item_id: 1,
+			sport_type: SportType::Running as SportId,
+			training_time: report.timestamp,
+			training_duration: report.training_duration,
+			training_count: 6600,
+			energy_used: 8,
+			asset_id: 1,
+			rewards: 7200000,
+		}));
+		let user_data = Users::<Test>::get(&user).expect("cannot find user");
+		assert_eq!(user_data.energy, 0);
+		let vfe_data = VFEDetails::<Test>::get(1, 1).expect("cannot find vfe detail");
+		assert_eq!(vfe_data.remaining_battery, 92);
+	});
+}
+
+#[test]
+fn upload_riding_training_report_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_sport_device_bind_vfe(
+			producer,
+			user.clone(),
+			pub_key,
+			key.clone(),
+			SportType::Riding,
+		);
+		Timestamp::set_timestamp(1668694716000);
+
+		// the cadence is out of the normal range
+		let mut report = RidingTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 1800,
+			distance: 15000,
+			average_speed: 300,
+			average_cadence: 200,
+			average_power: 0,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_noop!(
+			VFE::upload_training_report(
+				Origin::none(),
+				pub_key,
+				BoundedVec::truncate_from(report_sig.to_vec()),
+				BoundedVec::truncate_from(report_encode),
+			),
+			Error::<Test>::TrainingReportOutOfNormalRange
+		);
+
+		// the speed does not match the distance and duration
+		report.average_cadence = 90;
+		report.average_speed = 600;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_noop!(
+			VFE::upload_training_report(
+				Origin::none(),
+				pub_key,
+				BoundedVec::truncate_from(report_sig.to_vec()),
+				BoundedVec::truncate_from(report_encode),
+			),
+			Error::<Test>::TrainingReportOutOfNormalRange
+		);
+
+		report.average_speed = 300;
+		report.average_power = 180;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report_encode),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
+			owner: DANY,
+			brand_id: 1,
+			item_id: 1,
+			sport_type: SportType::Riding as SportId,
+			training_time: report.timestamp,
+			training_duration: report.training_duration,
+			training_count: 2700,
+			energy_used: 8,
+			asset_id: 1,
+			rewards: 7200000,
+		}));
+		let vfe_data = VFEDetails::<Test>::get(1, 1).expect("cannot find vfe detail");
+		assert_eq!(vfe_data.remaining_battery, 92);
+	});
+}
+
+#[test]
+fn vfe_wear_and_repair_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+
+		// the rewards are halved when the condition is 25%
+		VFEWear::<Test>::insert(1, 1, Permill::from_percent(75));
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
+			owner: DANY,
+			brand_id: 1,
+			item_id: 1,
+			sport_type: SportType::JumpRope as SportId,
+			training_time: report.timestamp,
+			training_duration: report.training_duration,
+			training_count: report.total_jump_rope_count,
+			energy_used: 6,
+			asset_id: 1,
+			rewards: 4500000,
+		}));
+
+		// the training wears the vfe inversely by the durable
+		let vfe = VFEDetails::<Test>::get(1, 1).expect("cannot find vfe detail");
+		let wear = VFEWear::<Test>::get(1, 1);
+		assert_eq!(
+			wear,
+			Permill::from_parts(750000 + 10000 * 6 / vfe.current_ability.durable as u32)
+		);
+
+		// only the owner can repair the vfe
+		assert_noop!(
+			VFE::repair_vfe(Origin::signed(BOB), 1, 1),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(VFE::repair_vfe(Origin::signed(user.clone()), 1, 1));
+		let cost = 100 * wear.mul_ceil(1000u64);
+		System::assert_has_event(Event::VFE(crate::Event::VFERepaired {
+			owner: user.clone(),
+			brand_id: 1,
+			item_id: 1,
+			cost,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 4500000 - cost);
+		assert_eq!(VFEWear::<Test>::get(1, 1), Permill::zero());
+		assert_noop!(
+			VFE::repair_vfe(Origin::signed(user.clone()), 1, 1),
+			Error::<Test>::VFENotWorn
+		);
+
+		// the worn out vfe can not train
+		VFEWear::<Test>::insert(1, 1, Permill::one());
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_noop!(
+			VFE::upload_training_report(
+				Origin::none(),
+				pub_key,
+				BoundedVec::truncate_from(report_sig.to_vec()),
+				BoundedVec::truncate_from(report.into()),
+			),
+			Error::<Test>::VFEWornOut
+		);
+	});
+}
+
+#[test]
+fn brand_reward_pool_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+
+		let config =
+			BrandRewardConfig { asset_id: 2, mode: BrandRewardMode::Fallback, rate: One::one() };
+		assert_noop!(
+			VFE::fund_brand_rewards(Origin::signed(CANDY), 1, 5000000),
+			Error::<Test>::BrandRewardConfigNotSet
+		);
+		assert_noop!(
+			VFE::set_brand_reward_config(Origin::signed(BOB), 1, Some(config)),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_noop!(
+			VFE::set_brand_reward_config(
+				Origin::signed(CANDY),
+				1,
+				Some(BrandRewardConfig { rate: Zero::zero(), ..config })
+			),
+			Error::<Test>::ValueInvalid
+		);
+		assert_ok!(VFE::set_brand_reward_config(Origin::signed(CANDY), 1, Some(config)));
+		System::assert_has_event(Event::VFE(crate::Event::BrandRewardConfigSet {
+			brand_id: 1,
+			config: Some(config),
+		}));
+
+		// fund the brand pool
+		let pool = VFE::brand_pool_account(&1);
+		assert_ok!(Currencies::mint_into(2, &CANDY, 5000000));
+		assert_ok!(VFE::fund_brand_rewards(Origin::signed(CANDY), 1, 5000000));
+		System::assert_has_event(Event::VFE(crate::Event::BrandRewardsFunded {
+			who: CANDY,
+			brand_id: 1,
+			asset_id: 2,
+			amount: 5000000,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(2, &pool), 5000000);
+
+		// the pool pays what it has, and the rest is minted in the incentive token
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::BrandRewardsPaid {
+			owner: user.clone(),
+			brand_id: 1,
+			asset_id: 2,
+			amount: 5000000,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::BrandRewardPoolExhausted {
+			brand_id: 1,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
+			owner: DANY,
+			brand_id: 1,
+			item_id: 1,
+			sport_type: SportType::JumpRope as SportId,
+			training_time: report.timestamp,
+			training_duration: report.training_duration,
+			training_count: report.total_jump_rope_count,
+			energy_used: 6,
+			asset_id: 1,
+			rewards: 4000000,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(2, &user), 5000000);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 4000000);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(2, &pool), 0);
+		let user_data = Users::<Test>::get(&user).expect("cannot find user");
+		assert_eq!(user_data.earned, 9000000);
+
+		// the combined rewards are paid in the asset by the rate of brand
+		let combine = BrandRewardConfig {
+			asset_id: 2,
+			mode: BrandRewardMode::Combine,
+			rate: FixedU128::saturating_from_rational(1, 10),
+		};
+		assert_ok!(VFE::set_brand_reward_config(Origin::signed(CANDY), 1, Some(combine)));
+		assert_ok!(Currencies::mint_into(2, &CANDY, 1000000));
+		assert_ok!(VFE::fund_brand_rewards(Origin::signed(CANDY), 1, 1000000));
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::BrandRewardsPaid {
+			owner: user.clone(),
+			brand_id: 1,
+			asset_id: 2,
+			amount: 420000,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(2, &user), 5420000);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 8200000);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(2, &pool), 580000);
+
+		// the reward asset can not be changed until the pool is withdrawn
+		assert_noop!(
+			VFE::set_brand_reward_config(Origin::signed(CANDY), 1, None),
+			Error::<Test>::BrandRewardPoolNotEmpty
+		);
+		assert_noop!(
+			VFE::withdraw_brand_rewards(Origin::signed(BOB), 1, 580000),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(VFE::withdraw_brand_rewards(Origin::signed(CANDY), 1, 580000));
+		System::assert_has_event(Event::VFE(crate::Event::BrandRewardsWithdrawn {
+			who: CANDY,
+			brand_id: 1,
+			asset_id: 2,
+			amount: 580000,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(2, &CANDY), 580000);
+		assert_ok!(VFE::set_brand_reward_config(Origin::signed(CANDY), 1, None));
+		assert_eq!(BrandRewardConfigs::<Test>::get(1), None);
+	});
+}
+
+#[test]
+fn emission_schedule_unit_test() {
+	new_test_ext().execute_with(|| {
+		// the budget halves every 2 periods
+		assert_eq!(VFE::emission_budget(0), 1_000_000_000_000_000);
+		assert_eq!(VFE::emission_budget(1), 1_000_000_000_000_000);
+		assert_eq!(VFE::emission_budget(2), 500_000_000_000_000);
+		assert_eq!(VFE::emission_budget(5), 250_000_000_000_000);
+		assert_eq!(VFE::get_emission_state(), (0, 1_000_000_000_000_000, 0));
+
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+
+		// the rewards are limited by the remaining budget
+		Emission::<Test>::put(EmissionState { period: 0, budget: 4000000, minted: 0 });
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
+			owner: DANY,
+			brand_id: 1,
+			item_id: 1,
+			sport_type: SportType::JumpRope as SportId,
+			training_time: report.timestamp,
+			training_duration: report.training_duration,
+			training_count: report.total_jump_rope_count,
+			energy_used: 6,
+			asset_id: 1,
+			rewards: 4000000,
+		}));
+		assert_eq!(VFE::get_emission_state(), (0, 4000000, 4000000));
+
+		// the cost unit is halved when half of the budget is minted
+		Emission::<Test>::put(EmissionState { period: 0, budget: 8400000, minted: 4200000 });
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
+			owner: DANY,
+			brand_id: 1,
+			item_id: 1,
+			sport_type: SportType::JumpRope as SportId,
+			training_time: report.timestamp,
+			training_duration: report.training_duration,
+			training_count: report.total_jump_rope_count,
+			energy_used: 2,
+			asset_id: 1,
+			rewards: 2100000,
+		}));
+		assert_eq!(VFE::get_emission_state(), (0, 8400000, 6300000));
+
+		// the budget is reset in the next period
+		System::set_block_number(250);
+		assert_eq!(VFE::get_emission_state(), (2, 500_000_000_000_000, 0));
+	});
+}
+
+#[test]
+fn device_quarantine_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer.clone(), user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+
+		let rules = PlausibilityRules {
+			max_peak_percent: 200,
+			count_tolerance_percent: 10,
+			min_count_per_interruption: 0,
+			max_reports_per_day: 5,
+			violation_score: 10,
+			decay_score: 1,
+			quarantine_threshold: 10,
+		};
+		assert_noop!(
+			VFE::set_plausibility_rules(Origin::signed(BOB), Some(rules)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			VFE::set_plausibility_rules(
+				Origin::root(),
+				Some(PlausibilityRules { max_peak_percent: 50, ..rules })
+			),
+			Error::<Test>::ValueInvalid
+		);
+		assert_ok!(VFE::set_plausibility_rules(Origin::root(), Some(rules)));
+		System::assert_has_event(Event::VFE(crate::Event::PlausibilityRulesUpdated {
+			rules: Some(rules),
+		}));
+
+		// the jump count does not match the average speed and the duration
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceQuarantined {
+			device_key: pub_key,
+			score: 10,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingRewardsHeld {
+			owner: user.clone(),
+			device_key: pub_key,
+			amount: 9000000,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
+			owner: DANY,
+			brand_id: 1,
+			item_id: 1,
+			sport_type: SportType::JumpRope as SportId,
+			training_time: report.timestamp,
+			training_duration: report.training_duration,
+			training_count: report.total_jump_rope_count,
+			energy_used: 6,
+			asset_id: 1,
+			rewards: 0,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 0);
+		assert_eq!(HeldRewards::<Test>::get(pub_key, report.timestamp).unwrap().amount, 9000000);
+		assert_eq!(DeviceDailyReports::<Test>::get(pub_key), (1668676716 / 86400, 1));
+
+		// the producer clears the quarantine and releases the held rewards
+		assert_noop!(
+			VFE::clear_device_quarantine(Origin::signed(BOB), pub_key, true),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(VFE::clear_device_quarantine(Origin::signed(producer.clone()), pub_key, true));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceQuarantineCleared {
+			operator: Some(producer),
+			device_key: pub_key,
+			released: true,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 9000000);
+		assert_eq!(HeldRewards::<Test>::get(pub_key, report.timestamp), None);
+		assert_eq!(DeviceSuspicion::<Test>::get(pub_key), 0);
+		assert_noop!(
+			VFE::clear_device_quarantine(Origin::root(), pub_key, true),
+			Error::<Test>::DeviceNotQuarantined
+		);
+
+		// the auditor clears the quarantine and discards the held rewards, which are reverted
+		let earned = Users::<Test>::get(&user).unwrap().earned;
+		let minted = VFE::current_emission().minted;
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		assert!(QuarantinedDevices::<Test>::contains_key(pub_key));
+		assert_eq!(HeldRewards::<Test>::get(pub_key, report.timestamp).unwrap().amount, 4200000);
+		assert_eq!(Users::<Test>::get(&user).unwrap().earned, earned + 4200000);
+		assert_ok!(VFE::clear_device_quarantine(Origin::root(), pub_key, false));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceQuarantineCleared {
+			operator: None,
+			device_key: pub_key,
+			released: false,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 9000000);
+		assert_eq!(HeldRewards::<Test>::get(pub_key, report.timestamp), None);
+		assert!(!QuarantinedDevices::<Test>::contains_key(pub_key));
+		assert_eq!(Users::<Test>::get(&user).unwrap().earned, earned);
+		assert_eq!(VFE::current_emission().minted, minted);
+		let history = TrainingHistory::<Test>::get(&user, SportType::JumpRope as SportId);
+		assert_eq!(history.last().unwrap().rewards, 0);
+	});
+}
+
+#[test]
+fn replace_quarantined_device_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		let (_, new_pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer.clone(), user.clone(), pub_key, key);
+
+		// the quarantined device can not be replaced
+		QuarantinedDevices::<Test>::insert(pub_key, 1);
+		DeviceSuspicion::<Test>::insert(pub_key, 10);
+		assert_noop!(
+			VFE::replace_device(Origin::signed(producer.clone()), pub_key, new_pub_key, None),
+			Error::<Test>::DeviceQuarantined
+		);
+
+		// the suspicion score and the daily reports move to the new key
+		QuarantinedDevices::<Test>::remove(pub_key);
+		DeviceSuspicion::<Test>::insert(pub_key, 5);
+		DeviceDailyReports::<Test>::insert(pub_key, (19313, 2));
+		assert_ok!(VFE::replace_device(
+			Origin::signed(producer.clone()),
+			pub_key,
+			new_pub_key,
+			None
+		));
+		assert_eq!(DeviceSuspicion::<Test>::get(new_pub_key), 5);
+		assert_eq!(DeviceDailyReports::<Test>::get(new_pub_key), (19313, 2));
+		assert_eq!(DeviceSuspicion::<Test>::get(pub_key), 0);
+		assert_eq!(DeviceDailyReports::<Test>::get(pub_key), (0, 0));
+	});
+}
+
+#[test]
+fn upload_training_report_envelope_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		// newer firmware appends fields which are unknown to the runtime
+		let mut payload: Vec<u8> = report.into();
+		payload.extend([1u8, 2, 3]);
+
+		// unsupported version
+		let envelope: Vec<u8> = TrainingReportEnvelope {
+			version: 2,
+			sport_type: SportType::JumpRope,
+			payload: payload.clone(),
+		}
+		.into();
+		let report_sig = key.sign(&envelope);
+		assert_noop!(
+			VFE::upload_training_report(
+				Origin::none(),
+				pub_key,
+				BoundedVec::truncate_from(report_sig.to_vec()),
+				BoundedVec::truncate_from(envelope),
+			),
+			Error::<Test>::UnsupportedReportVersion
+		);
+
+		// the sport type is different from the device
+		let envelope: Vec<u8> = TrainingReportEnvelope {
+			version: TrainingReportEnvelope::VERSION_1,
+			sport_type: SportType::Running,
+			payload: payload.clone(),
+		}
+		.into();
+		let report_sig = key.sign(&envelope);
+		assert_noop!(
+			VFE::upload_training_report(
+				Origin::none(),
+				pub_key,
+				BoundedVec::truncate_from(report_sig.to_vec()),
+				BoundedVec::truncate_from(envelope),
+			),
+			Error::<Test>::ReportSportTypeMismatch
+		);
+
+		let envelope: Vec<u8> = TrainingReportEnvelope {
+			version: TrainingReportEnvelope::VERSION_1,
+			sport_type: SportType::JumpRope,
+			payload,
+		}
+		.into();
+		let report_sig = key.sign(&envelope);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(envelope),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
+			owner: DANY,
+			brand_id: 1,
+			item_id: 1,
+			sport_type: SportType::JumpRope as SportId,
+			training_time: report.timestamp,
+			training_duration: report.training_duration,
+			training_count: report.total_jump_rope_count,
+			energy_used: 6,
+			asset_id: 1,
+			rewards: 9000000,
+		}));
+	});
+}
+
+#[test]
+fn upload_legacy_training_report_with_magic_byte_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+
+		// the low byte of the timestamp is the magic of the envelope
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676862,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		assert_eq!(report_encode[0], TrainingReportEnvelope::MAGIC);
+		assert!(TrainingReportEnvelope::try_from(report_encode.clone()).is_err());
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report_encode),
+		));
+		assert_eq!(Devices::<Test>::get(pub_key).unwrap().timestamp, report.timestamp);
+	});
+}
+
+#[test]
+fn sport_type_registry_unit_test() {
+	new_test_ext().execute_with(|| {
+		let treadmill = SportTypeInfo {
+			report_type: SportType::Running,
+			training_unit_duration: 120,
+			frequency_standard: 160,
+			min_frequency: 100,
+			max_frequency: 240,
+		};
+		assert_noop!(
+			VFE::register_sport_type(Origin::signed(BOB), 10, treadmill),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			VFE::register_sport_type(Origin::root(), SportType::Running as SportId, treadmill),
+			Error::<Test>::SportTypeExisted
+		);
+		assert_noop!(
+			VFE::register_sport_type(
+				Origin::root(),
+				10,
+				SportTypeInfo { training_unit_duration: 0, ..treadmill }
+			),
+			Error::<Test>::ValueInvalid
+		);
+		assert_noop!(
+			VFE::create_vfe_brand(Origin::signed(CANDY), bvec![0u8; 20], 10, VFERarity::Common),
+			Error::<Test>::SportTypeNotFound
+		);
+
+		assert_ok!(VFE::register_sport_type(Origin::root(), 10, treadmill));
+		System::assert_has_event(Event::VFE(crate::Event::SportTypeRegistered {
+			sport_type: 10,
+			info: treadmill,
+		}));
+		assert_eq!(VFE::sport_type_info(10), Some(treadmill));
+
+		// the report type can not be changed
+		assert_noop!(
+			VFE::update_sport_type(
+				Origin::root(),
+				10,
+				SportTypeInfo { report_type: SportType::JumpRope, ..treadmill }
+			),
+			Error::<Test>::OperationIsNotAllowed
+		);
+
+		// the built-in sport type keeps its report type even if it is stored
+		SportTypes::<Test>::insert(
+			SportType::Riding as SportId,
+			SportTypeInfo { report_type: SportType::Running, ..treadmill },
+		);
+		assert_noop!(
+			VFE::update_sport_type(
+				Origin::root(),
+				SportType::Riding as SportId,
+				SportTypeInfo { report_type: SportType::Running, ..treadmill }
+			),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		SportTypes::<Test>::remove(SportType::Riding as SportId);
+		assert_noop!(
+			VFE::update_sport_type(
+				Origin::root(),
+				SportType::JumpRope as SportId,
+				SportTypeInfo { report_type: SportType::Running, ..treadmill }
+			),
+			Error::<Test>::OperationIsNotAllowed
+		);
+
+		// tune a built-in sport type
+		let jump_rope =
+			SportTypeInfo { min_frequency: 100, ..SportTypeInfo::from(SportType::JumpRope) };
+		assert_ok!(VFE::update_sport_type(
+			Origin::root(),
+			SportType::JumpRope as SportId,
+			jump_rope
+		));
+		System::assert_has_event(Event::VFE(crate::Event::SportTypeUpdated {
+			sport_type: SportType::JumpRope as SportId,
+			info: jump_rope,
+		}));
+		assert_eq!(VFE::sport_type_info(SportType::JumpRope as SportId), Some(jump_rope));
+
+		// the devices of the registered sport type consume energy by its parameters
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_sport_device_bind_vfe(ALICE, user.clone(), pub_key, key.clone(), 10);
+		Timestamp::set_timestamp(1668694716000);
+		let report = RunningTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 600,
+			distance: 2000,
+			steps: 2200,
+			average_pace: 300,
+			average_cadence: 220,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report_encode),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingReportsAndRewards {
+			owner: DANY,
+			brand_id: 1,
+			item_id: 1,
+			sport_type: 10,
+			training_time: report.timestamp,
+			training_duration: report.training_duration,
+			training_count: 2200,
+			energy_used: 5,
+			asset_id: 1,
+			rewards: 4500000,
+		}));
+	});
+}
+
+#[test]
+fn migrate_sport_type_to_v1_unit_test() {
+	use frame_support::{
+		storage::unhashed,
+		traits::{GetStorageVersion, StorageVersion},
+	};
+
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<VFE>();
+		let (_, pub_key) = generate_device_keypair();
+		let brand = migrations::v0::VFEBrand::<u32, StringLimit> {
+			brand_id: 1,
+			sport_type: SportType::Riding,
+			rarity: VFERarity::Rare,
+			approvals: 2,
+			uri: bvec![0u8; 20],
+		};
+		unhashed::put(&VFEBrands::<Test>::hashed_key_for(1), &brand);
+		let device = migrations::v0::Device::<u32, u32, u32, u32, u64> {
+			sport_type: SportType::Running,
+			brand_id: 1,
+			item_id: Some(3),
+			producer_id: 1,
+			status: DeviceStatus::Activated,
+			pk: pub_key,
+			nonce: 1,
+			timestamp: 1668676716,
+			mint_cost: Some((1, 10)),
+		};
+		unhashed::put(&Devices::<Test>::hashed_key_for(pub_key), &device);
+
+		migrations::migrate_to_v1::<Test>();
+		assert_eq!(VFE::on_chain_storage_version(), 1);
+		assert_eq!(
+			VFEBrands::<Test>::get(1),
+			Some(VFEBrand {
+				brand_id: 1,
+				sport_type: SportType::Riding as SportId,
+				rarity: VFERarity::Rare,
+				approvals: 2,
+				uri: bvec![0u8; 20],
+			})
+		);
+		let migrated = Devices::<Test>::get(pub_key).unwrap();
+		assert_eq!(migrated.sport_type, SportType::Running as SportId);
+		assert_eq!(migrated.item_id, Some(3));
+		assert_eq!(migrated.mint_cost, Some((1, 10)));
+		assert_eq!(
+			SportTypes::<Test>::get(SportType::JumpRope as SportId),
+			Some(SportTypeInfo::from(SportType::JumpRope))
+		);
+		assert_eq!(
+			SportTypes::<Test>::get(SportType::Riding as SportId),
+			Some(SportTypeInfo::from(SportType::Riding))
+		);
+
+		// the migration runs only once
+		migrations::migrate_to_v1::<Test>();
+		assert_eq!(Devices::<Test>::get(pub_key), Some(migrated));
+	});
+}
+
+#[test]
+fn global_energy_recovery_and_daily_earned_reset_unit_test() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(LastEnergyRecovery::<Test>::get(), 0);
+		assert_eq!(LastDailyEarnedReset::<Test>::get(), 0);
+		run_to_block(5);
+		assert_eq!(LastEnergyRecovery::<Test>::get(), 0);
+		assert_eq!(LastDailyEarnedReset::<Test>::get(), 0);
+		run_to_block(9);
+		assert_eq!(LastEnergyRecovery::<Test>::get(), 8);
+		assert_eq!(LastDailyEarnedReset::<Test>::get(), 0);
+		System::assert_has_event(Event::VFE(crate::Event::GlobalEnergyRecoveryOccurred {
+			block_number: 8,
+		}));
+		run_to_block(17);
+		assert_eq!(LastEnergyRecovery::<Test>::get(), 16);
+		assert_eq!(LastDailyEarnedReset::<Test>::get(), 0);
+		System::assert_has_event(Event::VFE(crate::Event::GlobalEnergyRecoveryOccurred {
+			block_number: 16,
+		}));
+		run_to_block(20);
+		assert_eq!(LastEnergyRecovery::<Test>::get(), 16);
+		run_to_block(25);
+		assert_eq!(LastEnergyRecovery::<Test>::get(), 24);
+		assert_eq!(LastDailyEarnedReset::<Test>::get(), 24);
+		System::assert_has_event(Event::VFE(crate::Event::GlobalDailyEarnedResetOccurred {
+			block_number: 24,
+		}));
+		run_to_block(9889);
+		let last_update =
+			9889u64.saturating_div(EnergyRecoveryDuration::get()) * EnergyRecoveryDuration::get();
+		assert_eq!(LastEnergyRecovery::<Test>::get(), last_update);
+		assert_eq!(LastDailyEarnedReset::<Test>::get(), last_update);
+		System::assert_has_event(Event::VFE(crate::Event::GlobalEnergyRecoveryOccurred {
+			block_number: 9888,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::GlobalDailyEarnedResetOccurred {
+			block_number: 9888,
+		}));
+	});
+}
+
+#[test]
+fn user_restore_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+
+		Timestamp::set_timestamp(1668686716000);
+
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			report_sig.to_vec().try_into().unwrap(),
+			report_encode.try_into().unwrap()
+		));
+
+		//after global energy recovery occurred
+		run_to_block(9);
+
+		// let call = Call::user_restore { who: user.clone() };
+		// assert_ok!(<VFE as ValidateUnsigned>::validate_unsigned(TransactionSource::Local,
+		// &call));
+		assert_ok!(VFE::user_restore(Origin::signed(user.clone())));
+		System::assert_has_event(Event::VFE(crate::Event::UserEnergyRestored {
+			who: user.clone(),
+			restored_amount: 2,
+		}));
+		let user_data = Users::<Test>::get(&user).expect("cannot find user");
+		// println!("user data: {:?}", user_data);
+		assert_eq!(user_data.energy, 4);
+		assert!(user_data.earned != 0);
+
+		//after repeatedly global energy recovery occurred
+		run_to_block(229);
+		assert_ok!(VFE::user_restore(Origin::signed(user.clone())));
+		System::assert_has_event(Event::VFE(crate::Event::UserEnergyRestored {
+			who: user.clone(),
+			restored_amount: 4,
+		}));
+		let user_data = Users::<Test>::get(&user).expect("cannot find user");
+		assert_eq!(user_data.energy, 8);
+		assert!(user_data.earned == 0);
+	});
+}
+
+#[test]
+fn restore_power_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+
+		Timestamp::set_timestamp(1668686716000);
+
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			report_sig.to_vec().try_into().unwrap(),
+			report_encode.try_into().unwrap()
+		));
+
+		assert_ok!(VFE::restore_power(Origin::signed(user.clone()), 1, 1, 3));
+		System::assert_has_event(Event::VFE(crate::Event::PowerRestored {
+			owner: user.clone(),
+			charge_amount: 3,
+			use_amount: 2100000,
+			brand_id: 1,
+			item_id: 1,
+		}));
+
+		let vfe_data = VFEDetails::<Test>::get(1, 1).expect("cannot find vfe detail");
+		assert_eq!(vfe_data.remaining_battery, 97);
+
+		assert_noop!(
+			VFE::restore_power(Origin::signed(user.clone()), 1, 1, 4),
+			Error::<Test>::ValueOverflow
+		);
+
+		// user balance of asset is no enough to restore pow
+		assert_ok!(Currencies::transfer(Origin::signed(user.clone()), BOB, 1, 6400000, false));
+		assert_noop!(
+			VFE::restore_power(Origin::signed(user.clone()), 1, 1, 3),
+			pallet_assets::Error::<Test>::BalanceLow
+		);
+
+		// after transfer user balance of asset is enough to restore pow
+		assert_ok!(Currencies::transfer(Origin::signed(BOB), user.clone(), 1, 6400000, false));
+		assert_ok!(VFE::restore_power(Origin::signed(user.clone()), 1, 1, 3));
+		assert_noop!(
+			VFE::restore_power(Origin::signed(user.clone()), 1, 1, 3),
+			Error::<Test>::VFEFullyCharged
+		);
+	});
+}
+
+#[test]
+fn level_up_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+
+		assert_noop!(VFE::level_up(Origin::signed(user.clone()), 1, 2), Error::<Test>::VFENotExist);
+		assert_noop!(
+			VFE::level_up(Origin::signed(BOB), 1, 1),
+			Error::<Test>::OperationIsNotAllowed
+		);
+
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		let user_info = Users::<Test>::get(&user).unwrap();
+		let level_up_costs = VFE::calculate_level_up_costs(&vfe, &user_info);
+		// println!("level_up_costs: {}", level_up_costs);
+
+		//issue some asset to user, then level up VFE
+		assert_ok!(Currencies::mint_into(1, &user, 180000000));
+		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::VFELevelUp {
+			brand_id: 1,
+			item_id: 1,
+			level_up: 1,
+			cost: level_up_costs,
+		}));
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		assert_eq!(vfe.level, 1);
+		assert_eq!(vfe.available_points, 4);
+
+		let user_info = Users::<Test>::get(&user).unwrap();
+		assert_eq!(user_info.energy_total, 8);
+		assert_eq!(user_info.earning_cap, 1000 * 100000);
+
+		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		let user_info = Users::<Test>::get(&user).unwrap();
+		let level_up_costs = VFE::calculate_level_up_costs(&vfe, &user_info);
+		// let user_balance = Currencies::balance(1, &user);
+		// println!("user_balance = {}, level_up_costs = {}", user_balance, level_up_costs);
+		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::VFELevelUp {
+			brand_id: 1,
+			item_id: 1,
+			level_up: 4,
+			cost: level_up_costs,
+		}));
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		assert_eq!(vfe.level, 4);
+		assert_eq!(vfe.available_points, 16);
+
+		let user_info = Users::<Test>::get(&user).unwrap();
+		assert_eq!(user_info.energy_total, 16);
+		assert_eq!(user_info.earning_cap, 2500 * 100000);
+
+		// let level_up_costs = VFE::calculate_level_up_costs(&vfe, &user_info);
+		// let user_balance = Currencies::balance(1, &user);
+		// println!("user_balance = {}, level_up_costs = {}", user_balance, level_up_costs);
+		assert_noop!(
+			VFE::level_up(Origin::signed(user.clone()), 1, 1),
+			pallet_assets::Error::<Test>::BalanceLow
+		);
+	});
+}
+
+#[test]
+fn economic_parameters_unit_test() {
+	new_test_ext().execute_with(|| {
+		// the defaults of config are used if it is not set
+		let default_params = VFE::economic_params();
+		assert_eq!(default_params.cost_unit, 100000);
+		assert_eq!(default_params.init_energy, 8);
+		assert_eq!(default_params.base_range_of_ability(VFERarity::Epic), (20, 30));
+
+		let params = EconomicParameters {
+			init_energy: 10,
+			base_range_of_ability: [(5, 5), (6, 12), (10, 18), (20, 30)],
+			growth_points: [6, 4, 4, 4],
+			..default_params
+		};
+		assert_noop!(
+			VFE::set_economic_parameters(Origin::signed(BOB), params),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			VFE::set_economic_parameters(
+				Origin::root(),
+				EconomicParameters { growth_points: [0, 4, 4, 4], ..params }
+			),
+			Error::<Test>::ValueInvalid
+		);
+		assert_noop!(
+			VFE::set_economic_parameters(
+				Origin::root(),
+				EconomicParameters { base_range_of_ability: [(8, 2); 4], ..params }
+			),
+			Error::<Test>::ValueInvalid
+		);
+		assert_ok!(VFE::set_economic_parameters(Origin::root(), params));
+		System::assert_has_event(Event::VFE(crate::Event::EconomicParametersUpdated { params }));
+		assert_eq!(VFE::economic_params(), params);
+
+		// new vfe and user are created by the new parameters
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(ALICE, user.clone(), pub_key, key.clone());
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		assert_eq!(vfe.base_ability, VFEAbility { efficiency: 5, skill: 5, luck: 5, durable: 5 });
+		let user_info = Users::<Test>::get(&user).unwrap();
+		assert_eq!(user_info.energy_total, 10);
+
+		assert_ok!(Currencies::mint_into(1, &user, 180000000));
+		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::VFELevelUp {
+			brand_id: 1,
+			item_id: 1,
+			level_up: 1,
+			cost: 5 * 7 * 100000,
+		}));
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		assert_eq!(vfe.available_points, 6);
+		let user_info = Users::<Test>::get(&user).unwrap();
+		assert_eq!(user_info.energy_total, 10);
+		assert_eq!(user_info.earning_cap, 1000 * 100000);
+	});
+}
+
+#[test]
+fn economic_parameters_boundary_unit_test() {
+	new_test_ext().execute_with(|| {
+		let default_params = VFE::economic_params();
+		let params = EconomicParameters {
+			init_energy: EconomicParameters::<u64>::MAX_INIT_ENERGY,
+			init_earning_cap: u16::MAX,
+			base_range_of_ability: [(
+				EconomicParameters::<u64>::MAX_BASE_ABILITY,
+				EconomicParameters::<u64>::MAX_BASE_ABILITY,
+			); 4],
+			growth_points: [u16::MAX; 4],
+			..default_params
+		};
+		assert_ok!(VFE::set_economic_parameters(Origin::root(), params));
+
+		// the caps of the max level do not overflow
+		assert_eq!(VFE::level_into_energy_cap(u16::MAX), u16::MAX);
+		assert_eq!(VFE::level_into_earning_cap(u16::MAX), 65535 * 65536 * params.cost_unit);
+
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(ALICE, user.clone(), pub_key, key.clone());
+		let mut vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		let mut user_info = Users::<Test>::get(&user).unwrap();
+		vfe.level = u16::MAX;
+		user_info.energy_total = u16::MAX;
+		assert_eq!(
+			VFE::calculate_level_up_costs(&vfe, &user_info),
+			(1000u64 + 65535 * 65534 * 65535)
+				.saturating_mul(params.level_up_cost_factor)
+				.saturating_mul(params.cost_unit)
+		);
+
+		// the abilities boosted to the max do not overflow the charging costs
+		let max_ability =
+			VFEAbility { efficiency: u16::MAX, skill: u16::MAX, luck: u16::MAX, durable: u16::MAX };
+		vfe.base_ability = max_ability;
+		vfe.current_ability = max_ability;
+		vfe.remaining_battery = 0;
+		assert_eq!(
+			VFE::calculate_charging_costs(vfe, u16::MAX),
+			(131070 + 65535) * 100 * params.cost_unit
+		);
+
+		// the growth points saturate at the max
+		vfe.available_points = u16::MAX;
+		VFEDetails::<Test>::insert(1, 1, vfe);
+		assert_ok!(VFE::do_complete_level_up(&1, &1, vfe));
+		assert_eq!(VFEDetails::<Test>::get(1, 1).unwrap().available_points, u16::MAX);
+	});
+}
+
+#[test]
+fn timed_level_up_unit_test() {
+	new_test_ext().execute_with(|| {
+		LevelUpDurationPerLevel::set(10);
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		assert_ok!(Currencies::mint_into(1, &user, 180000000));
+
+		// the level up enters the upgrading state
+		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::VFEUpgradeStarted {
+			brand_id: 1,
+			item_id: 1,
+			level_up: 1,
+			completion: 11,
+		}));
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		assert_eq!(vfe.level, 0);
+		assert!(vfe.is_upgrading);
+		assert_eq!(VFE::get_upgrade_completion(1, 1), Some(11));
+		assert_noop!(
+			VFE::level_up(Origin::signed(user.clone()), 1, 1),
+			Error::<Test>::VFEUpgrading
+		);
+		assert_noop!(
+			VFE::transfer(Origin::signed(user.clone()), 1, 1, BOB),
+			Error::<Test>::VFEUpgrading
+		);
+
+		// the upgrade completes lazily on next access
+		System::set_block_number(11);
+		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::VFEUpgradeCompleted {
+			brand_id: 1,
+			item_id: 1,
+			level: 1,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::VFEUpgradeStarted {
+			brand_id: 1,
+			item_id: 1,
+			level_up: 2,
+			completion: 31,
+		}));
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		assert_eq!(vfe.level, 1);
+		assert_eq!(vfe.available_points, 4);
+
+		// speed up the upgrade by burning tokens for the remaining blocks
+		let user_balance = <Currencies as MultiAssets<AccountId>>::balance(1, &user);
+		assert_noop!(
+			VFE::speed_up_level_up(Origin::signed(BOB), 1, 1),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(VFE::speed_up_level_up(Origin::signed(user.clone()), 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::VFEUpgradeSpedUp {
+			brand_id: 1,
+			item_id: 1,
+			cost: 20 * 10000,
+		}));
+		assert_eq!(
+			<Currencies as MultiAssets<AccountId>>::balance(1, &user),
+			user_balance - 20 * 10000
+		);
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		assert_eq!(vfe.level, 2);
+		assert_eq!(vfe.available_points, 8);
+		assert!(!vfe.is_upgrading);
+		assert_eq!(VFE::get_upgrade_completion(1, 1), None);
+		let user_info = Users::<Test>::get(&user).unwrap();
+		assert_eq!(user_info.energy_total, 12);
+		assert_noop!(
+			VFE::speed_up_level_up(Origin::signed(user.clone()), 1, 1),
+			Error::<Test>::VFENotUpgrading
+		);
+	});
+}
+
+#[test]
+fn fuse_vfe_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer.clone(), user.clone(), pub_key, key.clone());
+
+		// the user gets the second vfe by binding a new device
+		let (second_key, second_pub_key) = generate_device_keypair();
+		assert_ok!(VFE::register_device(Origin::signed(producer), second_pub_key, 1, 1));
+		let account_nonce = 1u32;
+		let account_rip160 = Ripemd::Hash::hash(user.encode().as_ref());
+		let mut msg: Vec<u8> = Vec::new();
+		msg.extend(account_nonce.to_le_bytes().to_vec());
+		msg.extend(account_rip160.to_vec());
+		let signature = second_key.sign(msg.as_ref());
+		assert_ok!(VFE::bind_device(
+			Origin::none(),
+			user.clone(),
+			second_pub_key,
+			signature.to_vec().try_into().unwrap(),
+			account_nonce,
+			None
+		));
+		assert_ok!(Currencies::mint_into(1, &user, 1000000));
+
+		assert_noop!(
+			VFE::fuse_vfe(Origin::signed(user.clone()), 1, 1, 1, 1),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_noop!(
+			VFE::fuse_vfe(Origin::signed(BOB), 1, 1, 1, 2),
+			Error::<Test>::OperationIsNotAllowed
+		);
+
+		// the bound parents can not be fused
+		assert_noop!(
+			VFE::fuse_vfe(Origin::signed(user.clone()), 1, 1, 1, 2),
+			Error::<Test>::VFEBond
+		);
+		assert_ok!(Currencies::mint_into(1, &user, 2));
+		assert_ok!(VFE::unbind_device(Origin::signed(user.clone()), 1, 1));
+		assert_ok!(VFE::unbind_device(Origin::signed(user.clone()), 1, 2));
+
+		// the parents with socketed gems can not be fused
+		VFESockets::<Test>::insert(1, 2, BoundedVec::truncate_from(vec![(1, 1)]));
+		assert_noop!(
+			VFE::fuse_vfe(Origin::signed(user.clone()), 1, 1, 1, 2),
+			Error::<Test>::VFEGemSocketed
+		);
+		VFESockets::<Test>::remove(1, 2);
+
+		// the fusion is limited by the allowance of the brand owner
+		assert_noop!(
+			VFE::fuse_vfe(Origin::signed(user.clone()), 1, 1, 1, 2),
+			Error::<Test>::FusionAllowanceExhausted
+		);
+		assert_noop!(
+			VFE::set_fusion_allowance(Origin::signed(user.clone()), 1, 3),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(VFE::set_fusion_allowance(Origin::signed(CANDY), 1, 3));
+		System::assert_has_event(Event::VFE(crate::Event::FusionAllowanceSet {
+			brand_id: 1,
+			amount: 3,
+		}));
+		assert_ok!(VFE::fuse_vfe(Origin::signed(user.clone()), 1, 1, 1, 2));
+		System::assert_has_event(Event::VFE(crate::Event::VFEFused {
+			owner: user.clone(),
+			brand_id: 1,
+			item_id: 3,
+			parents: [(1, 1), (1, 2)],
+			cost: 100000,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 900000);
+		assert_eq!(VFEBreeds::<Test>::get(1, 1), BreedInfo { count: 1, cooldown_until: 11 });
+		assert_eq!(VFEBreeds::<Test>::get(1, 2), BreedInfo { count: 1, cooldown_until: 11 });
+		assert_eq!(FusionAllowances::<Test>::get(1), 2);
+
+		// the rarity of the child is the brand rarity, and its base abilities are derived from
+		// the parents
+		let child = VFEDetails::<Test>::get(1, 3).expect("cannot find vfe detail");
+		assert_eq!(<VFE as Inspect<AccountId>>::owner(&1u32, &3u32), Some(user.clone()));
+		assert_eq!(child.rarity, VFEBrands::<Test>::get(1).unwrap().rarity);
+		let (min, max) = child.rarity.base_range_of_ability();
+		for ability in [
+			child.base_ability.efficiency,
+			child.base_ability.skill,
+			child.base_ability.luck,
+			child.base_ability.durable,
+		] {
+			assert!(ability >= min && ability <= max);
+		}
+		assert_eq!(child.current_ability, child.base_ability);
+		assert_eq!(child.level, 0);
+		assert_eq!(child.device_key, None);
+
+		// the parents are on cooldown
+		assert_noop!(
+			VFE::fuse_vfe(Origin::signed(user.clone()), 1, 1, 1, 2),
+			Error::<Test>::BreedCooldownNotExpired
+		);
+		System::set_block_number(11);
+		assert_ok!(VFE::fuse_vfe(Origin::signed(user.clone()), 1, 1, 1, 2));
+		System::assert_has_event(Event::VFE(crate::Event::VFEFused {
+			owner: user.clone(),
+			brand_id: 1,
+			item_id: 4,
+			parents: [(1, 1), (1, 2)],
+			cost: 300000,
+		}));
+
+		// the parents reach the maximum number of breeding times
+		System::set_block_number(21);
+		assert_noop!(
+			VFE::fuse_vfe(Origin::signed(user.clone()), 1, 1, 1, 2),
+			Error::<Test>::BreedCountExceeded
+		);
+		assert_ok!(VFE::fuse_vfe(Origin::signed(user.clone()), 1, 3, 1, 4));
+		assert_eq!(FusionAllowances::<Test>::get(1), 0);
+		System::set_block_number(31);
+		assert_noop!(
+			VFE::fuse_vfe(Origin::signed(user.clone()), 1, 3, 1, 4),
+			Error::<Test>::FusionAllowanceExhausted
+		);
+	});
+}
+
+#[test]
+fn upload_training_report_with_max_abilities_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+
+		// the abilities at the max do not overflow the rewards
+		let mut vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		vfe.current_ability =
+			VFEAbility { efficiency: u16::MAX, skill: u16::MAX, luck: u16::MAX, durable: u16::MAX };
+		VFEDetails::<Test>::insert(1, 1, vfe);
+
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report_encode),
+		));
+		assert_eq!(Devices::<Test>::get(pub_key).unwrap().timestamp, report.timestamp);
+		assert_eq!(VFEDetails::<Test>::get(1, 1).unwrap().remaining_battery, 94);
+	});
+}
+
+#[test]
+fn gem_socket_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key);
+
+		// create the gem collection and mint gems
+		assert_noop!(VFE::create_gem_collection(Origin::signed(BOB)), DispatchError::BadOrigin);
+		assert_ok!(VFE::create_gem_collection(Origin::signed(CANDY)));
+		System::assert_has_event(Event::VFE(crate::Event::GemCollectionCreated {
+			who: CANDY,
+			collection_id: 1,
+		}));
+		let gem = GemDetail {
+			ability: VFEAbility { efficiency: 2, skill: 0, luck: 0, durable: 3 },
+			permanent: false,
+		};
+		let permanent_gem = GemDetail {
+			ability: VFEAbility { efficiency: 0, skill: 0, luck: 1, durable: 0 },
+			permanent: true,
+		};
+		assert_noop!(
+			VFE::mint_gem(Origin::signed(BOB), 1, user.clone(), gem),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_noop!(
+			VFE::mint_gem(Origin::signed(CANDY), 1, user.clone(), GemDetail::default()),
+			Error::<Test>::ValueInvalid
+		);
+		assert_noop!(
+			VFE::mint_gem(
+				Origin::signed(CANDY),
+				1,
+				user.clone(),
+				GemDetail {
+					ability: VFEAbility { efficiency: 0, skill: 11, luck: 0, durable: 0 },
+					permanent: false
+				}
+			),
+			Error::<Test>::ValueInvalid
+		);
+		assert_ok!(VFE::mint_gem(Origin::signed(CANDY), 1, user.clone(), gem));
+		System::assert_has_event(Event::VFE(crate::Event::GemMinted {
+			owner: user.clone(),
+			collection_id: 1,
+			item_id: 1,
+			detail: gem,
+		}));
+		assert_ok!(VFE::mint_gem(Origin::signed(CANDY), 1, user.clone(), permanent_gem));
+		assert_ok!(VFE::mint_gem(Origin::signed(CANDY), 1, user.clone(), gem));
+		assert_eq!(<Gems as Inspect<AccountId>>::owner(&1, &3), Some(user.clone()));
+
+		// the sockets are locked at level 0
+		assert_noop!(
+			VFE::socket_gem(Origin::signed(user.clone()), 1, 1, 1, 1),
+			Error::<Test>::NoSocketAvailable
+		);
+		VFEDetails::<Test>::mutate(1, 1, |vfe| vfe.as_mut().unwrap().level = 3);
+		let ability = VFEDetails::<Test>::get(1, 1).unwrap().current_ability;
+		assert_noop!(
+			VFE::socket_gem(Origin::signed(BOB), 1, 1, 1, 1),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(VFE::socket_gem(Origin::signed(user.clone()), 1, 1, 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::GemSocketed {
+			owner: user.clone(),
+			brand_id: 1,
+			item_id: 1,
+			gem_collection_id: 1,
+			gem_item_id: 1,
+		}));
+		assert_ok!(VFE::socket_gem(Origin::signed(user.clone()), 1, 1, 1, 2));
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		assert_eq!(
+			vfe.current_ability,
+			VFEAbility {
+				efficiency: ability.efficiency + 2,
+				skill: ability.skill,
+				luck: ability.luck + 1,
+				durable: ability.durable + 3,
+			}
+		);
+		assert_eq!(<Gems as Inspect<AccountId>>::owner(&1, &1), Some(VFE::account_id()));
+		assert_eq!(VFESockets::<Test>::get(1, 1).into_inner(), vec![(1, 1), (1, 2)]);
+
+		// all of the sockets are used
+		assert_noop!(
+			VFE::socket_gem(Origin::signed(user.clone()), 1, 1, 1, 3),
+			Error::<Test>::NoSocketAvailable
+		);
+
+		// remove the gem by paying the fee
+		assert_noop!(
+			VFE::unsocket_gem(Origin::signed(user.clone()), 1, 1, 1, 2),
+			Error::<Test>::GemPermanent
+		);
+		assert_noop!(
+			VFE::unsocket_gem(Origin::signed(user.clone()), 1, 1, 1, 3),
+			Error::<Test>::GemNotSocketed
+		);
+		assert_ok!(Currencies::mint_into(1, &user, 1000));
+		assert_ok!(VFE::unsocket_gem(Origin::signed(user.clone()), 1, 1, 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::GemUnsocketed {
+			owner: user.clone(),
+			brand_id: 1,
+			item_id: 1,
+			gem_collection_id: 1,
+			gem_item_id: 1,
+			fee: 1000,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 0);
+		assert_eq!(<Gems as Inspect<AccountId>>::owner(&1, &1), Some(user.clone()));
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		assert_eq!(vfe.current_ability, VFEAbility { luck: ability.luck + 1, ..ability });
+		assert_eq!(VFESockets::<Test>::get(1, 1).into_inner(), vec![(1, 2)]);
+
+		// the gems of the lent or staked VFE can not be changed
+		VFELoans::<Test>::insert(
+			1,
+			1,
+			VFELoan {
+				owner: user.clone(),
+				duration: 10,
+				owner_share: Permill::zero(),
+				borrower: None,
+			},
+		);
+		assert_noop!(
+			VFE::socket_gem(Origin::signed(user.clone()), 1, 1, 1, 1),
+			Error::<Test>::VFELent
+		);
+		assert_noop!(
+			VFE::unsocket_gem(Origin::signed(user.clone()), 1, 1, 1, 2),
+			Error::<Test>::VFELent
+		);
+		VFELoans::<Test>::remove(1, 1);
+		StakedVFEs::<Test>::insert(
+			1,
+			1,
+			StakedVFE { owner: user.clone(), boost: Default::default(), unbonding_until: None },
+		);
+		assert_noop!(
+			VFE::socket_gem(Origin::signed(user.clone()), 1, 1, 1, 1),
+			Error::<Test>::VFEStaked
+		);
+		assert_noop!(
+			VFE::unsocket_gem(Origin::signed(user.clone()), 1, 1, 1, 2),
+			Error::<Test>::VFEStaked
+		);
+		StakedVFEs::<Test>::remove(1, 1);
+
+		// burning the VFE burns the permanent gems and clears its storage
+		VFEWear::<Test>::insert(1, 1, Permill::from_percent(10));
+		assert_ok!(VFE::do_burn(1, 1));
+		assert_eq!(<Gems as Inspect<AccountId>>::owner(&1, &2), None);
+		assert!(!VFESockets::<Test>::contains_key(1, 1));
+		assert!(!VFEWear::<Test>::contains_key(1, 1));
+	});
+}
+
+#[test]
+fn increase_ability_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+
+		assert_noop!(VFE::level_up(Origin::signed(user.clone()), 1, 2), Error::<Test>::VFENotExist);
+		assert_noop!(
+			VFE::level_up(Origin::signed(BOB), 1, 1),
+			Error::<Test>::OperationIsNotAllowed
+		);
+
+		//issue some asset to user, then level up VFE
+		assert_ok!(Currencies::mint_into(1, &user, 180000000));
+		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+		assert_ok!(VFE::level_up(Origin::signed(user.clone()), 1, 1));
+		let origin_vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		assert_eq!(origin_vfe.level, 4);
+		assert_eq!(origin_vfe.available_points, 16);
+
+		let add_point = VFEAbility { efficiency: 10, skill: 10, luck: 10, durable: 10 };
+		assert_noop!(
+			VFE::increase_ability(Origin::signed(user.clone()), 1, 1, add_point),
+			Error::<Test>::ValueInvalid
+		);
+
+		let add_point = VFEAbility { efficiency: 3, skill: 4, luck: 3, durable: 4 };
+		assert_ok!(VFE::increase_ability(Origin::signed(user.clone()), 1, 1, add_point));
+		System::assert_has_event(Event::VFE(crate::Event::VFEAbilityIncreased {
+			brand_id: 1,
+			item_id: 1,
+		}));
+
+		let vfe = VFEDetails::<Test>::get(1, 1).unwrap();
+		assert_eq!(vfe.level, 4);
+		assert_eq!(vfe.available_points, 2);
+		assert_eq!(
+			vfe.current_ability.efficiency,
+			origin_vfe.current_ability.efficiency + add_point.efficiency
+		);
+		assert_eq!(vfe.current_ability.skill, origin_vfe.current_ability.skill + add_point.skill);
+		assert_eq!(vfe.current_ability.luck, origin_vfe.current_ability.luck + add_point.luck);
+		assert_eq!(
+			vfe.current_ability.durable,
+			origin_vfe.current_ability.durable + add_point.durable
+		);
+	});
+}
+
+#[test]
+fn verify_bind_device_message_unit_test() {
+	new_test_ext().execute_with(|| {
+		// verify_bind_device_message
+		let x = hex!["0339d3e6e837d675ce77e85d708caf89ddcdbf53c8e510775c9cb9ec06282475a0"];
+		// let pubkey = VerifyingKey::from_sec1_bytes(x).unwrap();
+		let sig = Signature::from_bytes(&hex!["d851b2fcd63bf78a52008e043cb28c47523c2ee2c3c9425c8b0a005e2f6f53b101690e030e0b2c8c6ae99c3a40c02f58dffd1fded34f84953d0e6ea671d83930"]).unwrap();
+		let nonce = 0u32;
+		let account_id = AccountId::new(hex!["764f41f48346146c043c5d0c7948f4b24a7877c649d5b72973850ccbe0f86840"]) ;
+		let account_rip160 = Ripemd::Hash::hash(account_id.encode().as_ref());
+		//13a7c41c6fa23d80f586051c6ccce5eb60192a20
 		//13a7c41c6fa23d80f586051c6ccce5eb60192a20
 		println!("ripemd160: {}", hex::encode(account_rip160));
 
-		assert_ok!(VFE::verify_bind_device_message(account_id, nonce, DeviceKey::from_raw(x), sig.as_bytes()), true);
+		assert_ok!(VFE::verify_bind_device_message(account_id, nonce, DeviceKey::from_raw(x), sig.as_bytes()), true);
+	});
+}
+
+#[test]
+fn generate_bind_device_message_unit_test() {
+	new_test_ext().execute_with(|| {
+		let (key, pub_key) = generate_device_keypair();
+		let nonce = 1u32;
+		//5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y
+		let account_id =
+			AccountId::from_string("5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y").unwrap();
+		let account_rip160 = Ripemd::Hash::hash(account_id.encode().as_ref());
+		let mut msg: Vec<u8> = Vec::new();
+		msg.extend(nonce.to_le_bytes().to_vec());
+		msg.extend(account_rip160.to_vec());
+		let sig = key.sign(&msg);
+		println!("account_id: {}", hex::encode(account_id.clone()));
+		println!("device key: {}", hex::encode(pub_key));
+		println!("signature: {}", hex::encode(sig));
+
+		assert_ok!(
+			VFE::verify_bind_device_message(account_id, nonce, pub_key, sig.as_bytes()),
+			true
+		);
+	});
+}
+
+#[test]
+fn verify_training_data_signature() {
+	let x = &hex!["0339d3e6e837d675ce77e85d708caf89ddcdbf53c8e510775c9cb9ec06282475a0"];
+	let pubkey = VerifyingKey::from_sec1_bytes(x).unwrap();
+
+	let training_data = &hex!["68db756303001200680168011200000300"];
+	// let training_hash = Sha256::new_with_prefix(training_data);
+	let training_hash = sha2::Sha256::digest(training_data);
+	println!("training_hash = {}", hex::encode(training_hash));
+
+	let sig = Signature::from_bytes(&hex!["33bc624ff2ce52dfe013d0e8d8f2839ad350cfe56f5000f192c886b72ce0cd5864f7ab233d69e01568a988998967ee87e611b8455c0439dc69abf9b6eafad20d"]).unwrap();
+
+	assert!(pubkey.verify(training_data.as_ref(), &sig).is_ok());
+	// assert!(pubkey.verify_digest(training_hash, &sig).is_ok());
+
+	let training_report =
+		JumpRopeTrainingReport::try_from(training_data.to_vec()).expect("convert failed");
+	println!("training_report = {:?}", training_report);
+}
+
+#[test]
+fn training_report_encode_unit_test() {
+	let report = JumpRopeTrainingReport {
+		timestamp: 1668676716,
+		training_duration: 183,
+		total_jump_rope_count: 738,
+		average_speed: 140,
+		max_speed: 230,
+		max_jump_rope_count: 738,
+		interruptions: 0,
+		jump_rope_duration: 183,
+	};
+	let encode: Vec<u8> = report.into();
+	println!("encode = {}", hex::encode(&encode[..]));
+
+	let decode_report = JumpRopeTrainingReport::try_from(encode).expect("convert failed");
+	println!("decode = {:?}", decode_report);
+}
+
+#[test]
+fn running_training_report_encode_unit_test() {
+	let report = RunningTrainingReport {
+		timestamp: 1668676716,
+		training_duration: 1800,
+		distance: 5000,
+		steps: 6600,
+		average_pace: 360,
+		average_cadence: 220,
+	};
+	assert!(report.is_plausible());
+	let encode: Vec<u8> = report.into();
+	assert_eq!(encode.len(), 18);
+
+	let decode_report = RunningTrainingReport::try_from(encode).expect("convert failed");
+	assert_eq!(decode_report, report);
+
+	// stride is too long
+	let report = RunningTrainingReport { steps: 1000, average_cadence: 33, ..report };
+	assert!(!report.is_plausible());
+}
+
+#[test]
+fn training_report_envelope_encode_unit_test() {
+	let envelope = TrainingReportEnvelope {
+		version: TrainingReportEnvelope::VERSION_1,
+		sport_type: SportType::Riding,
+		payload: vec![1u8; 16],
+	};
+	let encode: Vec<u8> = envelope.clone().into();
+	assert_eq!(encode.len(), TrainingReportEnvelope::HEADER_LEN + 16);
+	let decode_envelope = TrainingReportEnvelope::try_from(encode.clone()).expect("convert failed");
+	assert_eq!(decode_envelope, envelope);
+
+	// the payload length is mismatched
+	assert!(TrainingReportEnvelope::try_from(encode[..encode.len() - 1].to_vec()).is_err());
+	// a legacy report is not an envelope
+	let report = JumpRopeTrainingReport {
+		timestamp: 1668676716,
+		training_duration: 183,
+		total_jump_rope_count: 738,
+		average_speed: 140,
+		max_speed: 230,
+		max_jump_rope_count: 738,
+		interruptions: 0,
+		jump_rope_duration: 183,
+	};
+	assert!(TrainingReportEnvelope::try_from(Vec::<u8>::from(report)).is_err());
+}
+
+#[test]
+fn transfer_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let to = BOB;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer.clone(), user.clone(), pub_key, key.clone());
+
+		assert_noop!(
+			VFE::transfer(Origin::signed(user.clone()), 1, 2, to.clone()),
+			Error::<Test>::VFENotExist
+		);
+		assert_noop!(
+			VFE::transfer(Origin::signed(producer.clone()), 1, 1, to.clone()),
+			Error::<Test>::OperationIsNotAllowed
+		);
+
+		Timestamp::set_timestamp(1668686716000);
+
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			report_sig.to_vec().try_into().unwrap(),
+			report_encode.try_into().unwrap()
+		));
+
+		assert_noop!(
+			VFE::transfer(Origin::signed(user.clone()), 1, 1, to.clone()),
+			Error::<Test>::VFENotFullyCharged
+		);
+		assert_ok!(VFE::restore_power(Origin::signed(user.clone()), 1, 1, 6));
+		assert_noop!(
+			VFE::transfer(Origin::signed(user.clone()), 1, 1, to.clone()),
+			Error::<Test>::VFEBond
+		);
+		//after unbind device, VFE can be transferred.
+		assert_ok!(VFE::unbind_device(Origin::signed(user.clone()), 1, 1,));
+		assert_ok!(VFE::transfer(Origin::signed(user.clone()), 1, 1, to.clone()));
+		System::assert_has_event(Event::VFE(crate::Event::Transferred {
+			brand_id: 1,
+			item_id: 1,
+			from: user.clone(),
+			to: to.clone(),
+		}));
+
+		let vfe_owner = <VFE as Inspect<AccountId>>::owner(&1u32, &1u32).expect("vfe can not find");
+		assert_eq!(vfe_owner, to);
+	});
+}
+
+#[test]
+fn report_challenge_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let auditor = BOB;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+		ChallengeWindow::set(10);
+		System::set_block_number(1);
+
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::TrainingRewardsPending {
+			owner: user.clone(),
+			device_key: pub_key,
+			timestamp: report.timestamp,
+			amount: 9000000,
+			deadline: 11,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 0);
+
+		// the rewards can not be claimed in the challenge window
+		assert_noop!(
+			VFE::claim_report_rewards(Origin::signed(user.clone()), pub_key, report.timestamp),
+			Error::<Test>::ChallengeWindowNotExpired
+		);
+		System::set_block_number(12);
+		assert_ok!(VFE::claim_report_rewards(Origin::signed(TOM), pub_key, report.timestamp));
+		System::assert_has_event(Event::VFE(crate::Event::ReportRewardsClaimed {
+			owner: user.clone(),
+			device_key: pub_key,
+			timestamp: report.timestamp,
+			amount: 9000000,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 9000000);
+		assert_noop!(
+			VFE::claim_report_rewards(Origin::signed(user.clone()), pub_key, report.timestamp),
+			Error::<Test>::ReportNotFound
+		);
+
+		// the auditor challenges the second report and rejects it
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		assert_noop!(
+			VFE::challenge_report(Origin::signed(user.clone()), pub_key, report.timestamp),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			VFE::resolve_challenge(Origin::signed(auditor), pub_key, report.timestamp, false),
+			Error::<Test>::ReportNotChallenged
+		);
+		assert_ok!(VFE::challenge_report(Origin::signed(auditor), pub_key, report.timestamp));
+		System::assert_has_event(Event::VFE(crate::Event::ReportChallenged {
+			challenger: auditor,
+			device_key: pub_key,
+			timestamp: report.timestamp,
+		}));
+		assert_noop!(
+			VFE::challenge_report(Origin::signed(auditor), pub_key, report.timestamp),
+			Error::<Test>::ReportChallenged
+		);
+		System::set_block_number(30);
+		assert_noop!(
+			VFE::claim_report_rewards(Origin::signed(user.clone()), pub_key, report.timestamp),
+			Error::<Test>::ReportChallenged
+		);
+		assert_ok!(VFE::resolve_challenge(
+			Origin::signed(auditor),
+			pub_key,
+			report.timestamp,
+			false
+		));
+		System::assert_has_event(Event::VFE(crate::Event::ChallengeResolved {
+			auditor,
+			device_key: pub_key,
+			timestamp: report.timestamp,
+			valid: false,
+		}));
+		assert!(QuarantinedDevices::<Test>::contains_key(pub_key));
+		assert!(PendingReports::<Test>::get(pub_key, report.timestamp).is_none());
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 9000000);
+
+		// the rejected rewards are not counted in the earned, the emission and the history
+		assert_eq!(Users::<Test>::get(&user).unwrap().earned, 9000000);
+		assert_eq!(VFE::current_emission().minted, 9000000);
+		let history = TrainingHistory::<Test>::get(&user, SportType::JumpRope as SportId);
+		assert_eq!(history.last().unwrap().day, report.timestamp / 86400);
+		assert_eq!(history.last().unwrap().rewards, 0);
+	});
+}
+
+#[test]
+fn training_history_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		let first_day = DailyTraining {
+			day: 1668676716 / 86400,
+			sport_type: SportType::JumpRope as SportId,
+			training_duration: 183,
+			training_count: 738,
+			energy_used: 6,
+			rewards: 9000000,
+			reports: 1,
+		};
+		assert_eq!(VFE::get_user_training_history(user.clone()), vec![first_day]);
+		assert_eq!(VFE::get_user_training_history(BOB), vec![]);
+
+		// the days older than `MaxHistoryDays` are pruned
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		let second_day = DailyTraining {
+			day: 1668904749 / 86400,
+			energy_used: 2,
+			rewards: 4200000,
+			..first_day
+		};
+		assert_eq!(VFE::get_user_training_history(user.clone()), vec![second_day]);
+		assert_eq!(
+			TrainingHistory::<Test>::get(&user, SportType::JumpRope as SportId).into_inner(),
+			vec![second_day]
+		);
+	});
+}
+
+#[test]
+fn leaderboard_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+
+		let sport_board = LeaderboardKey::Sport(SportType::JumpRope as SportId);
+		let brand_board = LeaderboardKey::Brand(1);
+		let shares =
+			BoundedVec::truncate_from(vec![Permill::from_percent(60), Permill::from_percent(40)]);
+		assert_noop!(
+			VFE::set_leaderboard(Origin::signed(BOB), sport_board, 1, 100, shares.clone()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			VFE::set_leaderboard(Origin::root(), sport_board, 1, 0, shares.clone()),
+			Error::<Test>::ValueInvalid
+		);
+		assert_noop!(
+			VFE::set_leaderboard(
+				Origin::root(),
+				sport_board,
+				1,
+				100,
+				BoundedVec::truncate_from(vec![
+					Permill::from_percent(70),
+					Permill::from_percent(40)
+				]),
+			),
+			Error::<Test>::ValueInvalid
+		);
+		assert_ok!(VFE::set_leaderboard(Origin::root(), sport_board, 1, 100, shares));
+		System::assert_has_event(Event::VFE(crate::Event::LeaderboardSet {
+			key: sport_board,
+			asset_id: 1,
+			period: 100,
+		}));
+		let brand_shares = BoundedVec::truncate_from(vec![Permill::one()]);
+		assert_noop!(
+			VFE::set_leaderboard(Origin::signed(BOB), brand_board, 2, 100, brand_shares.clone()),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(VFE::set_leaderboard(Origin::signed(CANDY), brand_board, 2, 100, brand_shares));
+
+		// fund the prize pools
+		assert_ok!(Currencies::mint_into(1, &ALICE, 1000));
+		assert_ok!(VFE::fund_leaderboard(Origin::signed(ALICE), sport_board, 1000));
+		System::assert_has_event(Event::VFE(crate::Event::LeaderboardFunded {
+			who: ALICE,
+			key: sport_board,
+			amount: 1000,
+		}));
+		assert_ok!(Currencies::mint_into(2, &CANDY, 5000));
+		assert_ok!(VFE::fund_leaderboard(Origin::signed(CANDY), brand_board, 5000));
+
+		// the training volume is ranked in both leaderboards
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		assert_eq!(VFE::get_leaderboard(sport_board), vec![(user.clone(), 90)]);
+		assert_eq!(VFE::get_leaderboard(brand_board), vec![(user.clone(), 90)]);
+
+		// only the top ranks are kept
+		VFE::update_leaderboard(&sport_board, &BOB, 100);
+		VFE::update_leaderboard(&sport_board, &TOM, 50);
+		assert_eq!(VFE::get_leaderboard(sport_board), vec![(BOB, 100), (user.clone(), 90)]);
+		VFE::update_leaderboard(&sport_board, &TOM, 60);
+		assert_eq!(VFE::get_leaderboard(sport_board), vec![(TOM, 110), (BOB, 100)]);
+
+		// the prizes are distributed at the end of the period
+		assert_noop!(
+			VFE::settle_leaderboard(Origin::signed(BOB), sport_board, 0),
+			Error::<Test>::LeaderboardPeriodNotEnded
+		);
+		System::set_block_number(100);
+		assert_eq!(VFE::get_leaderboard(sport_board), vec![]);
+		assert_noop!(
+			VFE::settle_leaderboard(Origin::signed(BOB), sport_board, 1),
+			Error::<Test>::LeaderboardPeriodNotEnded
+		);
+		assert_ok!(VFE::settle_leaderboard(Origin::signed(BOB), sport_board, 0));
+		System::assert_has_event(Event::VFE(crate::Event::LeaderboardPrizePaid {
+			key: sport_board,
+			period: 0,
+			rank: 1,
+			who: TOM,
+			amount: 600,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::LeaderboardSettled {
+			key: sport_board,
+			period: 0,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &TOM), 600);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &BOB), 400);
+		assert_noop!(
+			VFE::settle_leaderboard(Origin::signed(BOB), sport_board, 0),
+			Error::<Test>::LeaderboardStandingsNotFound
+		);
+		assert_ok!(VFE::remove_leaderboard(Origin::root(), sport_board));
+		System::assert_has_event(Event::VFE(crate::Event::LeaderboardRemoved { key: sport_board }));
+
+		// the next training does not pay the prizes, the ended period is kept to be settled
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(2, &user), 0);
+		assert_eq!(VFE::get_leaderboard(brand_board), vec![(user.clone(), 42)]);
+		assert_eq!(VFE::get_leaderboard(sport_board), vec![]);
+		assert_eq!(
+			VFE::get_ended_leaderboard_standings(brand_board, 0)
+				.map(|ended| (ended.ranks.into_inner(), ended.prizes)),
+			Some((vec![(user.clone(), 90)], 5000))
+		);
+		assert_ok!(VFE::settle_leaderboard(Origin::signed(BOB), brand_board, 0));
+		System::assert_has_event(Event::VFE(crate::Event::LeaderboardPrizePaid {
+			key: brand_board,
+			period: 0,
+			rank: 1,
+			who: user.clone(),
+			amount: 5000,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(2, &user), 5000);
+		assert_eq!(VFE::get_ended_leaderboard_standings(brand_board, 0), None);
+		assert_eq!(LeaderboardReservedPrizes::<Test>::get(brand_board), 0);
 	});
 }
 
 #[test]
-fn generate_bind_device_message_unit_test() {
+fn leaderboard_prize_reserved_unit_test() {
+	new_test_ext().execute_with(|| {
+		let sport_board = LeaderboardKey::Sport(SportType::JumpRope as SportId);
+		let shares =
+			BoundedVec::truncate_from(vec![Permill::from_percent(60), Permill::from_percent(40)]);
+		assert_ok!(VFE::set_leaderboard(Origin::root(), sport_board, 1, 100, shares.clone()));
+		assert_ok!(Currencies::mint_into(1, &ALICE, 2000));
+		assert_ok!(VFE::fund_leaderboard(Origin::signed(ALICE), sport_board, 1000));
+		VFE::update_leaderboard(&sport_board, &TOM, 100);
+
+		// the prizes of the period are reserved when the period ends
+		System::set_block_number(100);
+		VFE::update_leaderboard(&sport_board, &BOB, 50);
+		assert_eq!(VFE::get_ended_leaderboard_standings(sport_board, 0).unwrap().prizes, 1000);
+		assert_eq!(LeaderboardReservedPrizes::<Test>::get(sport_board), 1000);
+
+		// the funds after the end are for the later periods
+		assert_ok!(VFE::fund_leaderboard(Origin::signed(ALICE), sport_board, 1000));
+		System::set_block_number(200);
+		VFE::update_leaderboard(&sport_board, &TOM, 10);
+		assert_eq!(VFE::get_ended_leaderboard_standings(sport_board, 1).unwrap().prizes, 1000);
+		assert_eq!(LeaderboardReservedPrizes::<Test>::get(sport_board), 2000);
+
+		// the prizes of the period are not taken by settling the other periods
+		assert_ok!(VFE::settle_leaderboard(Origin::signed(BOB), sport_board, 1));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &BOB), 600);
+		assert_eq!(LeaderboardReservedPrizes::<Test>::get(sport_board), 1000);
+		assert_ok!(VFE::settle_leaderboard(Origin::signed(BOB), sport_board, 0));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &TOM), 600);
+		assert_eq!(LeaderboardReservedPrizes::<Test>::get(sport_board), 0);
+		let pool = VFE::leaderboard_pool_account(&sport_board);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &pool), 800);
+
+		// the scores are cleared with the new settings
+		assert!(LeaderboardScores::<Test>::contains_key(sport_board, TOM));
+		assert_ok!(VFE::set_leaderboard(Origin::root(), sport_board, 1, 50, shares));
+		assert!(!LeaderboardScores::<Test>::contains_key(sport_board, TOM));
+		assert_eq!(VFE::get_leaderboard(sport_board), vec![]);
+	});
+}
+
+#[test]
+fn leaderboard_prize_failed_unit_test() {
+	new_test_ext().execute_with(|| {
+		let sport_board = LeaderboardKey::Sport(SportType::JumpRope as SportId);
+		let shares =
+			BoundedVec::truncate_from(vec![Permill::from_percent(30), Permill::from_percent(20)]);
+		assert_ok!(VFE::set_leaderboard(Origin::root(), sport_board, 1, 100, shares));
+		assert_ok!(Currencies::mint_into(1, &ALICE, 2000));
+		assert_ok!(VFE::fund_leaderboard(Origin::signed(ALICE), sport_board, 2000));
+		VFE::update_leaderboard(&sport_board, &TOM, 100);
+		VFE::update_leaderboard(&sport_board, &BOB, 50);
+
+		// the prize of the second rank is below the min balance of the asset
+		assert_ok!(Assets::force_asset_status(
+			Origin::root(),
+			1,
+			ALICE,
+			ALICE,
+			ALICE,
+			ALICE,
+			500,
+			true,
+			false
+		));
+		System::set_block_number(100);
+		assert_ok!(VFE::settle_leaderboard(Origin::signed(BOB), sport_board, 0));
+		System::assert_has_event(Event::VFE(crate::Event::LeaderboardPrizePaid {
+			key: sport_board,
+			period: 0,
+			rank: 1,
+			who: TOM,
+			amount: 600,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::LeaderboardPrizeFailed {
+			key: sport_board,
+			period: 0,
+			rank: 2,
+			who: BOB,
+			amount: 400,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::LeaderboardSettled {
+			key: sport_board,
+			period: 0,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &TOM), 600);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &BOB), 0);
+		let pool = VFE::leaderboard_pool_account(&sport_board);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &pool), 1400);
+	});
+}
+
+#[test]
+fn brand_challenge_unit_test() {
 	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
 		let (key, pub_key) = generate_device_keypair();
-		let nonce = 1u32;
-		//5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y
-		let account_id =
-			AccountId::from_string("5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y").unwrap();
-		let account_rip160 = Ripemd::Hash::hash(account_id.encode().as_ref());
-		let mut msg: Vec<u8> = Vec::new();
-		msg.extend(nonce.to_le_bytes().to_vec());
-		msg.extend(account_rip160.to_vec());
-		let sig = key.sign(&msg);
-		println!("account_id: {}", hex::encode(account_id.clone()));
-		println!("device key: {}", hex::encode(pub_key));
-		println!("signature: {}", hex::encode(sig));
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
 
-		assert_ok!(
-			VFE::verify_bind_device_message(account_id, nonce, pub_key, sig.as_bytes()),
-			true
+		let terms = ChallengeTerms {
+			metric: ChallengeMetric::TrainingCount,
+			target: 1000,
+			start: 1,
+			end: 50,
+			asset_id: 2,
+			entry_fee: 100,
+		};
+		assert_noop!(
+			VFE::create_brand_challenge(Origin::signed(BOB), 1, terms, 0),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_noop!(
+			VFE::create_brand_challenge(
+				Origin::signed(CANDY),
+				1,
+				ChallengeTerms { target: 0, ..terms },
+				0
+			),
+			Error::<Test>::ValueInvalid
+		);
+		assert_ok!(Currencies::mint_into(2, &CANDY, 10000));
+		assert_ok!(VFE::create_brand_challenge(Origin::signed(CANDY), 1, terms, 10000));
+		System::assert_has_event(Event::VFE(crate::Event::BrandChallengeCreated {
+			challenge_id: 0,
+			brand_id: 1,
+			terms,
+			prize_pool: 10000,
+		}));
+		assert_ok!(VFE::create_brand_challenge(Origin::signed(CANDY), 1, terms, 0));
+		assert_noop!(
+			VFE::create_brand_challenge(Origin::signed(CANDY), 1, terms, 0),
+			Error::<Test>::TooManyBrandChallenges
+		);
+
+		// join with an owned VFE and pay the entry fee
+		assert_noop!(
+			VFE::join_brand_challenge(Origin::signed(BOB), 0, 1),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(Currencies::mint_into(2, &user, 100));
+		assert_ok!(VFE::join_brand_challenge(Origin::signed(user.clone()), 0, 1));
+		System::assert_has_event(Event::VFE(crate::Event::BrandChallengeJoined {
+			challenge_id: 0,
+			who: user.clone(),
+			item_id: 1,
+		}));
+		assert_noop!(
+			VFE::join_brand_challenge(Origin::signed(user.clone()), 0, 1),
+			Error::<Test>::BrandChallengeJoined
+		);
+		let pool = VFE::brand_challenge_account(0);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(2, &pool), 10100);
+
+		// the progress is accumulated from the training reports
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		assert_eq!(ChallengeEntries::<Test>::get(0, &user).unwrap().progress, 738);
+		assert_eq!(BrandChallenges::<Test>::get(0).unwrap().completed, 0);
+
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::BrandChallengeCompleted {
+			challenge_id: 0,
+			who: user.clone(),
+		}));
+		assert_eq!(ChallengeEntries::<Test>::get(0, &user).unwrap().progress, 1476);
+
+		// the prize pool is shared by the participants who complete the challenge
+		assert_noop!(
+			VFE::claim_brand_challenge_prize(Origin::signed(user.clone()), 0),
+			Error::<Test>::BrandChallengeNotEnded
+		);
+		assert_noop!(
+			VFE::settle_brand_challenge(Origin::signed(BOB), 0),
+			Error::<Test>::BrandChallengeNotEnded
+		);
+		System::set_block_number(51);
+		assert_noop!(
+			VFE::join_brand_challenge(Origin::signed(user.clone()), 1, 1),
+			Error::<Test>::BrandChallengeEnded
+		);
+		assert_ok!(VFE::settle_brand_challenge(Origin::signed(BOB), 0));
+		System::assert_has_event(Event::VFE(crate::Event::BrandChallengeSettled {
+			challenge_id: 0,
+			completed: 1,
+			prize: 10100,
+		}));
+		assert_noop!(
+			VFE::settle_brand_challenge(Origin::signed(BOB), 0),
+			Error::<Test>::BrandChallengeSettled
+		);
+		assert_eq!(UnsettledBrandChallenges::<Test>::get(1).into_inner(), vec![1]);
+		assert_noop!(
+			VFE::claim_brand_challenge_prize(Origin::signed(BOB), 0),
+			Error::<Test>::BrandChallengeNotCompleted
+		);
+		assert_ok!(VFE::claim_brand_challenge_prize(Origin::signed(user.clone()), 0));
+		System::assert_has_event(Event::VFE(crate::Event::BrandChallengePrizeClaimed {
+			challenge_id: 0,
+			who: user.clone(),
+			amount: 10100,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(2, &user), 10100);
+		assert_noop!(
+			VFE::claim_brand_challenge_prize(Origin::signed(user.clone()), 0),
+			Error::<Test>::BrandChallengePrizeClaimed
 		);
 	});
 }
 
 #[test]
-fn verify_training_data_signature() {
-	let x = &hex!["0339d3e6e837d675ce77e85d708caf89ddcdbf53c8e510775c9cb9ec06282475a0"];
-	let pubkey = VerifyingKey::from_sec1_bytes(x).unwrap();
+fn team_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
 
-	let training_data = &hex!["68db756303001200680168011200000300"];
-	// let training_hash = Sha256::new_with_prefix(training_data);
-	let training_hash = sha2::Sha256::digest(training_data);
-	println!("training_hash = {}", hex::encode(training_hash));
+		assert_ok!(VFE::create_team(Origin::signed(user.clone())));
+		System::assert_has_event(Event::VFE(crate::Event::TeamCreated {
+			team_id: 0,
+			captain: user.clone(),
+		}));
+		assert_noop!(VFE::create_team(Origin::signed(user.clone())), Error::<Test>::AlreadyInTeam);
+
+		// the captain manages the membership
+		assert_noop!(VFE::join_team(Origin::signed(BOB), 0), Error::<Test>::TeamInvitationNotFound);
+		assert_noop!(
+			VFE::invite_team_member(Origin::signed(BOB), 0, TOM),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(VFE::invite_team_member(Origin::signed(user.clone()), 0, BOB));
+		assert_ok!(VFE::invite_team_member(Origin::signed(user.clone()), 0, TOM));
+		assert_ok!(VFE::join_team(Origin::signed(BOB), 0));
+		System::assert_has_event(Event::VFE(crate::Event::TeamJoined { team_id: 0, who: BOB }));
+		assert_noop!(VFE::join_team(Origin::signed(TOM), 0), Error::<Test>::TeamFull);
+		assert_eq!(
+			VFE::get_user_team_summary(BOB),
+			Some(TeamSummary {
+				team_id: 0,
+				captain: user.clone(),
+				members: vec![user.clone(), BOB],
+				volume: 0,
+				goal: 200,
+				bonus_active: false,
+			})
+		);
+
+		// the team progress aggregates the member trainings
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		assert_eq!(TeamProgresses::<Test>::get(0).volume, 90);
+		VFE::update_team_progress(&BOB, 120);
+		System::assert_has_event(Event::VFE(crate::Event::TeamGoalReached {
+			team_id: 0,
+			period: 0,
+		}));
+
+		// the members earn the bonus in the next period
+		System::set_block_number(100);
+		assert!(VFE::get_team_summary(0).unwrap().bonus_active);
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 9000000 + 4620000);
+		assert_eq!(VFE::get_team_summary(0).unwrap().volume, 42);
+
+		// the captain leaves after handing over the team
+		assert_noop!(
+			VFE::leave_team(Origin::signed(user.clone())),
+			Error::<Test>::CaptainCannotLeave
+		);
+		assert_ok!(VFE::transfer_team_captain(Origin::signed(user.clone()), BOB));
+		System::assert_has_event(Event::VFE(crate::Event::TeamCaptainChanged {
+			team_id: 0,
+			captain: BOB,
+		}));
+		assert_ok!(VFE::leave_team(Origin::signed(user.clone())));
+		System::assert_has_event(Event::VFE(crate::Event::TeamLeft {
+			team_id: 0,
+			who: user.clone(),
+		}));
+		assert_eq!(VFE::get_user_team_summary(user.clone()), None);
+		assert_ok!(VFE::leave_team(Origin::signed(BOB)));
+		System::assert_has_event(Event::VFE(crate::Event::TeamDisbanded { team_id: 0 }));
+		assert!(Teams::<Test>::get(0).is_none());
+	});
+}
+
+#[test]
+fn referral_rewards_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let referrer = BOB;
+		let (key, pub_key) = generate_device_keypair();
+
+		// the referrer must be a user
+		assert_noop!(
+			VFE::set_referrer(Origin::signed(user.clone()), referrer.clone()),
+			Error::<Test>::ReferrerInvalid
+		);
+		assert_noop!(
+			VFE::set_referrer(Origin::signed(user.clone()), user.clone()),
+			Error::<Test>::ReferrerInvalid
+		);
+		VFE::find_user(&referrer);
+		assert_ok!(VFE::set_referrer(Origin::signed(user.clone()), referrer.clone()));
+		System::assert_has_event(Event::VFE(crate::Event::ReferrerSet {
+			who: user.clone(),
+			referrer: referrer.clone(),
+		}));
+		assert_noop!(
+			VFE::set_referrer(Origin::signed(user.clone()), referrer.clone()),
+			Error::<Test>::ReferrerAlreadySet
+		);
+
+		// the referrer can not be set after the first binding
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+		assert_noop!(
+			VFE::set_referrer(Origin::signed(user.clone()), referrer.clone()),
+			Error::<Test>::UserAlreadyCreated
+		);
 
-	let sig = Signature::from_bytes(&hex!["33bc624ff2ce52dfe013d0e8d8f2839ad350cfe56f5000f192c886b72ce0cd5864f7ab233d69e01568a988998967ee87e611b8455c0439dc69abf9b6eafad20d"]).unwrap();
+		// the share of the training rewards is paid to the referrer
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::ReferralRewardsPaid {
+			referrer: referrer.clone(),
+			referee: user.clone(),
+			asset_id: 1,
+			amount: 900000,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 9000000);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &referrer), 900000);
 
-	assert!(pubkey.verify(training_data.as_ref(), &sig).is_ok());
-	// assert!(pubkey.verify_digest(training_hash, &sig).is_ok());
+		// the referral rewards are limited for each referrer
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &referrer), 1000000);
+		assert_eq!(ReferralEarnings::<Test>::get(&referrer), 1000000);
 
-	let training_report =
-		JumpRopeTrainingReport::try_from(training_data.to_vec()).expect("convert failed");
-	println!("training_report = {:?}", training_report);
+		// no referral rewards after the referral period
+		assert!(VFE::in_referral_period(&user, 101));
+		assert!(!VFE::in_referral_period(&user, 102));
+	});
 }
 
 #[test]
-fn training_report_encode_unit_test() {
-	let report = JumpRopeTrainingReport {
-		timestamp: 1668676716,
-		training_duration: 183,
-		total_jump_rope_count: 738,
-		average_speed: 140,
-		max_speed: 230,
-		max_jump_rope_count: 738,
-		interruptions: 0,
-		jump_rope_duration: 183,
-	};
-	let encode: Vec<u8> = report.into();
-	println!("encode = {}", hex::encode(&encode[..]));
+fn pending_referral_rewards_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let referrer = BOB;
+		let (key, pub_key) = generate_device_keypair();
+		VFE::find_user(&referrer);
+		assert_ok!(VFE::set_referrer(Origin::signed(user.clone()), referrer.clone()));
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+		Timestamp::set_timestamp(1668694716000);
+		ChallengeWindow::set(10);
 
-	let decode_report = JumpRopeTrainingReport::try_from(encode).expect("convert failed");
-	println!("decode = {:?}", decode_report);
+		// the report in the referral period is shared after the period
+		let report = JumpRopeTrainingReport {
+			timestamp: 1668676716,
+			training_duration: 183,
+			total_jump_rope_count: 738,
+			average_speed: 140,
+			max_speed: 230,
+			max_jump_rope_count: 738,
+			interruptions: 0,
+			jump_rope_duration: 183,
+		};
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::set_block_number(150);
+		assert_ok!(VFE::claim_report_rewards(
+			Origin::signed(user.clone()),
+			pub_key,
+			report.timestamp
+		));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &referrer), 900000);
+
+		// the report after the referral period is not shared
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::set_block_number(161);
+		assert_ok!(VFE::claim_report_rewards(
+			Origin::signed(user.clone()),
+			pub_key,
+			report.timestamp
+		));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &user), 9000000 + 4200000);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &referrer), 900000);
+	});
 }
 
 #[test]
-fn transfer_unit_test() {
+fn vfe_lending_unit_test() {
 	new_test_ext().execute_with(|| {
 		let producer = ALICE;
-		let user = DANY;
-		let to = BOB;
+		let owner = DANY;
+		let borrower = BOB;
 		let (key, pub_key) = generate_device_keypair();
-		produce_device_bind_vfe(producer.clone(), user.clone(), pub_key, key.clone());
+		produce_device_bind_vfe(producer, owner.clone(), pub_key, key.clone());
 
+		// only the owner can list the unbound VFE
 		assert_noop!(
-			VFE::transfer(Origin::signed(user.clone()), 1, 2, to.clone()),
-			Error::<Test>::VFENotExist
+			VFE::list_vfe_for_lending(
+				Origin::signed(owner.clone()),
+				1,
+				1,
+				50,
+				Permill::from_percent(30)
+			),
+			Error::<Test>::VFEBond
 		);
+		assert_ok!(Currencies::mint_into(1, &owner, 1));
+		assert_ok!(VFE::unbind_device(Origin::signed(owner.clone()), 1, 1));
+		assert_ok!(VFE::set_unbind_settings(
+			Origin::root(),
+			UnbindSettings { cooldown: 10, ..VFE::unbind_settings() }
+		));
 		assert_noop!(
-			VFE::transfer(Origin::signed(producer.clone()), 1, 1, to.clone()),
+			VFE::list_vfe_for_lending(
+				Origin::signed(borrower.clone()),
+				1,
+				1,
+				50,
+				Permill::from_percent(30)
+			),
 			Error::<Test>::OperationIsNotAllowed
 		);
+		assert_ok!(VFE::list_vfe_for_lending(
+			Origin::signed(owner.clone()),
+			1,
+			1,
+			50,
+			Permill::from_percent(30)
+		));
+		System::assert_has_event(Event::VFE(crate::Event::VFEListedForLending {
+			owner: owner.clone(),
+			brand_id: 1,
+			item_id: 1,
+			duration: 50,
+			owner_share: Permill::from_percent(30),
+		}));
 
-		Timestamp::set_timestamp(1668686716000);
+		// the listed VFE can not be sold or upgraded
+		assert_noop!(
+			VFE::transfer(Origin::signed(owner.clone()), 1, 1, TOM),
+			Error::<Test>::VFELent
+		);
+		assert_noop!(VFE::level_up(Origin::signed(owner.clone()), 1, 1), Error::<Test>::VFELent);
+
+		// the VFE is bound to the device of the borrower
+		let account_nonce = 2u32;
+		let account_rip160 = Ripemd::Hash::hash(borrower.encode().as_ref());
+		let mut msg: Vec<u8> = Vec::new();
+		msg.extend(account_nonce.to_le_bytes().to_vec());
+		msg.extend(account_rip160.to_vec());
+		let signature = key.sign(msg.as_ref());
+		assert_noop!(
+			VFE::bind_device(
+				Origin::none(),
+				borrower.clone(),
+				pub_key,
+				signature.to_vec().try_into().unwrap(),
+				account_nonce,
+				Some(1),
+			),
+			Error::<Test>::VFELent
+		);
+		assert_noop!(
+			VFE::borrow_vfe(Origin::signed(owner.clone()), 1, 1),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(VFE::borrow_vfe(Origin::signed(borrower.clone()), 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::VFEBorrowed {
+			borrower: borrower.clone(),
+			brand_id: 1,
+			item_id: 1,
+			expiry: 51,
+		}));
+		assert_noop!(VFE::borrow_vfe(Origin::signed(TOM), 1, 1), Error::<Test>::VFELent);
+		assert_ok!(VFE::bind_device(
+			Origin::none(),
+			borrower.clone(),
+			pub_key,
+			signature.to_vec().try_into().unwrap(),
+			account_nonce,
+			Some(1),
+		));
+		assert_noop!(
+			VFE::unbind_device(Origin::signed(owner.clone()), 1, 1),
+			Error::<Test>::OperationIsNotAllowed
+		);
 
+		// the training rewards are split between the borrower and the owner
+		Timestamp::set_timestamp(1668694716000);
 		let report = JumpRopeTrainingReport {
 			timestamp: 1668676716,
 			training_duration: 183,
@@ -1004,30 +4057,197 @@ fn transfer_unit_test() {
 		assert_ok!(VFE::upload_training_report(
 			Origin::none(),
 			pub_key,
-			report_sig.to_vec().try_into().unwrap(),
-			report_encode.try_into().unwrap()
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
 		));
+		System::assert_has_event(Event::VFE(crate::Event::VFELoanRewardsPaid {
+			owner: owner.clone(),
+			borrower: borrower.clone(),
+			brand_id: 1,
+			item_id: 1,
+			amount: 2700000,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &owner), 2700000);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &borrower), 6300000);
+		assert_eq!(Users::<Test>::get(&borrower).unwrap().earned, 9000000);
 
+		// the borrower can restore the power of the lent VFE
 		assert_noop!(
-			VFE::transfer(Origin::signed(user.clone()), 1, 1, to.clone()),
-			Error::<Test>::VFENotFullyCharged
+			VFE::restore_power(Origin::signed(TOM), 1, 1, 3),
+			Error::<Test>::OperationIsNotAllowed
 		);
-		assert_ok!(VFE::restore_power(Origin::signed(user.clone()), 1, 1, 6));
+		assert_ok!(VFE::restore_power(Origin::signed(borrower.clone()), 1, 1, 3));
+		System::assert_has_event(Event::VFE(crate::Event::PowerRestored {
+			owner: borrower.clone(),
+			charge_amount: 3,
+			use_amount: 2100000,
+			brand_id: 1,
+			item_id: 1,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &owner), 2700000);
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &borrower), 4200000);
+
+		// the owner can not end the loan before the expiry
 		assert_noop!(
-			VFE::transfer(Origin::signed(user.clone()), 1, 1, to.clone()),
+			VFE::end_vfe_loan(Origin::signed(owner.clone()), 1, 1),
+			Error::<Test>::VFELoanNotExpired
+		);
+
+		// the expired loan is ended by the next report without the rewards, and the VFE is
+		// unbound with the bind cooldown
+		System::set_block_number(51);
+		Timestamp::set_timestamp(1668914749000);
+		let mut report = report;
+		report.timestamp = 1668904749;
+		let report_encode: Vec<u8> = report.into();
+		let report_sig = key.sign(&report_encode);
+		assert_noop!(
+			VFE::restore_power(Origin::signed(borrower.clone()), 1, 1, 3),
+			Error::<Test>::OperationIsNotAllowed
+		);
+		assert_ok!(VFE::upload_training_report(
+			Origin::none(),
+			pub_key,
+			BoundedVec::truncate_from(report_sig.to_vec()),
+			BoundedVec::truncate_from(report.into()),
+		));
+		System::assert_has_event(Event::VFE(crate::Event::DeviceUnbound {
+			owner: owner.clone(),
+			device_key: pub_key,
+			brand_id: 1,
+			item_id: 1,
+		}));
+		System::assert_has_event(Event::VFE(crate::Event::VFELoanEnded {
+			owner: owner.clone(),
+			brand_id: 1,
+			item_id: 1,
+		}));
+		assert_eq!(<Currencies as MultiAssets<AccountId>>::balance(1, &borrower), 4200000);
+		assert_eq!(VFELoans::<Test>::get(1, 1), None);
+		assert_eq!(VFEDetails::<Test>::get(1, 1).unwrap().device_key, None);
+		assert_eq!(Devices::<Test>::get(pub_key).unwrap().item_id, None);
+		assert_eq!(VFEBindCooldowns::<Test>::get(1, 1), Some(61));
+		assert_eq!(DeviceBindCooldowns::<Test>::get(pub_key), Some(61));
+		assert_noop!(
+			VFE::end_vfe_loan(Origin::signed(owner.clone()), 1, 1),
+			Error::<Test>::VFENotListed
+		);
+	});
+}
+
+#[test]
+fn vfe_staking_unit_test() {
+	new_test_ext().execute_with(|| {
+		let producer = ALICE;
+		let user = DANY;
+		let (key, pub_key) = generate_device_keypair();
+		produce_device_bind_vfe(producer, user.clone(), pub_key, key.clone());
+
+		// only the idle VFE can be staked by the owner
+		assert_noop!(
+			VFE::stake_vfe(Origin::signed(user.clone()), 1, 1, StakingTarget::Energy),
 			Error::<Test>::VFEBond
 		);
-		//after unbind device, VFE can be transferred.
-		assert_ok!(VFE::unbind_device(Origin::signed(user.clone()), 1, 1,));
-		assert_ok!(VFE::transfer(Origin::signed(user.clone()), 1, 1, to.clone()));
-		System::assert_has_event(Event::VFE(crate::Event::Transferred {
+		assert_ok!(Currencies::mint_into(1, &user, 1));
+		assert_ok!(VFE::unbind_device(Origin::signed(user.clone()), 1, 1));
+		assert_noop!(
+			VFE::stake_vfe(Origin::signed(BOB), 1, 1, StakingTarget::Energy),
+			Error::<Test>::OperationIsNotAllowed
+		);
+
+		let mut upgraded = VFEDetails::<Test>::get(1, 1).unwrap();
+		upgraded.level = 1;
+		let level_up_cost =
+			VFE::calculate_level_up_costs(&upgraded, &Users::<Test>::get(&user).unwrap());
+
+		// the energy cap of the common VFE at level 0 is boosted by 25% of 8
+		assert_ok!(VFE::stake_vfe(Origin::signed(user.clone()), 1, 1, StakingTarget::Energy));
+		let boost = StakingBoost { energy: 2, earning_cap: 0 };
+		System::assert_has_event(Event::VFE(crate::Event::VFEStaked {
+			owner: user.clone(),
 			brand_id: 1,
 			item_id: 1,
-			from: user.clone(),
-			to: to.clone(),
+			boost,
 		}));
+		assert_eq!(Users::<Test>::get(&user).unwrap().energy_total, 10);
+		assert_eq!(StakingBoosts::<Test>::get(&user), boost);
+		// the boost does not raise the level up cost
+		assert_eq!(
+			VFE::calculate_level_up_costs(&upgraded, &Users::<Test>::get(&user).unwrap()),
+			level_up_cost
+		);
+		assert_noop!(
+			VFE::stake_vfe(Origin::signed(user.clone()), 1, 1, StakingTarget::Energy),
+			Error::<Test>::VFEStaked
+		);
 
-		let vfe_owner = <VFE as Inspect<AccountId>>::owner(&1u32, &1u32).expect("vfe can not find");
-		assert_eq!(vfe_owner, to);
+		// the staked VFE can not be transferred, upgraded or bound
+		assert_noop!(
+			VFE::transfer(Origin::signed(user.clone()), 1, 1, BOB),
+			Error::<Test>::VFEStaked
+		);
+		assert_noop!(VFE::level_up(Origin::signed(user.clone()), 1, 1), Error::<Test>::VFEStaked);
+		let account_nonce = 2u32;
+		let account_rip160 = Ripemd::Hash::hash(user.encode().as_ref());
+		let mut msg: Vec<u8> = Vec::new();
+		msg.extend(account_nonce.to_le_bytes().to_vec());
+		msg.extend(account_rip160.to_vec());
+		let signature = key.sign(msg.as_ref());
+		assert_noop!(
+			VFE::bind_device(
+				Origin::none(),
+				user.clone(),
+				pub_key,
+				signature.to_vec().try_into().unwrap(),
+				account_nonce,
+				Some(1),
+			),
+			Error::<Test>::VFEStaked
+		);
+		assert_noop!(
+			VFE::withdraw_vfe(Origin::signed(user.clone()), 1, 1),
+			Error::<Test>::VFEStaked
+		);
+
+		// the boost is removed when unstaking, and the VFE is locked in the unbonding period
+		assert_ok!(VFE::unstake_vfe(Origin::signed(user.clone()), 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::VFEUnstaked {
+			owner: user.clone(),
+			brand_id: 1,
+			item_id: 1,
+			unbonding_until: 11,
+		}));
+		let user_info = Users::<Test>::get(&user).unwrap();
+		assert_eq!(user_info.energy_total, 8);
+		assert_eq!(user_info.energy, 8);
+		assert_eq!(StakingBoosts::<Test>::get(&user), StakingBoost::default());
+		assert_noop!(
+			VFE::unstake_vfe(Origin::signed(user.clone()), 1, 1),
+			Error::<Test>::VFEUnbonding
+		);
+		assert_noop!(
+			VFE::transfer(Origin::signed(user.clone()), 1, 1, BOB),
+			Error::<Test>::VFEStaked
+		);
+		assert_noop!(
+			VFE::withdraw_vfe(Origin::signed(user.clone()), 1, 1),
+			Error::<Test>::UnbondingNotExpired
+		);
+		System::set_block_number(11);
+		assert_ok!(VFE::withdraw_vfe(Origin::signed(user.clone()), 1, 1));
+		System::assert_has_event(Event::VFE(crate::Event::VFEWithdrawn {
+			owner: user.clone(),
+			brand_id: 1,
+			item_id: 1,
+		}));
+		assert_eq!(StakedVFEs::<Test>::get(1, 1), None);
+
+		// the earning cap is boosted by 25% of the cap of level 0
+		assert_ok!(VFE::stake_vfe(Origin::signed(user.clone()), 1, 1, StakingTarget::EarningCap));
+		assert_eq!(Users::<Test>::get(&user).unwrap().earning_cap, 62500000);
+		assert_eq!(
+			StakingBoosts::<Test>::get(&user),
+			StakingBoost { energy: 0, earning_cap: 12500000 }
+		);
 	});
 }
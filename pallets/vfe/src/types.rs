@@ -9,7 +9,10 @@ use serde::{Deserialize, Serialize};
 
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{dispatch::TypeInfo, RuntimeDebug};
-use sp_runtime::{traits::Get, BoundedVec};
+use sp_runtime::{
+	traits::{Get, Zero},
+	BoundedVec, FixedU128, Permill, SaturatedConversion,
+};
 use sp_std::vec::Vec;
 
 /// public key of device
@@ -48,6 +51,19 @@ impl Default for SportType {
 	}
 }
 
+impl TryFrom<u8> for SportType {
+	type Error = ();
+
+	fn try_from(value: u8) -> Result<Self, Self::Error> {
+		match value {
+			0 => Ok(SportType::JumpRope),
+			1 => Ok(SportType::Running),
+			2 => Ok(SportType::Riding),
+			_ => Err(()),
+		}
+	}
+}
+
 impl SportType {
 	pub fn training_unit_duration(&self) -> u16 {
 		match self {
@@ -60,21 +76,67 @@ impl SportType {
 	pub fn frequency_standard(&self) -> u16 {
 		match self {
 			SportType::JumpRope => 120, //120 jumps/minute
-			SportType::Running => 10,
-			SportType::Riding => 30,
+			SportType::Running => 160,  //160 steps/minute
+			SportType::Riding => 80,    //80 revolutions/minute
 		}
 	}
 
-	pub fn is_frequency_range(&self, frequency: u16) -> u16 {
+	pub fn frequency_range(&self) -> (u16, u16) {
 		match self {
-			SportType::JumpRope =>
-				if (80..=400).contains(&frequency) {
-					1
-				} else {
-					0
-				},
-			SportType::Running => 1,
-			SportType::Riding => 1,
+			SportType::JumpRope => (80, 400),
+			SportType::Running => (100, 240),
+			SportType::Riding => (30, 150),
+		}
+	}
+
+	pub fn is_frequency_range(&self, frequency: u16) -> u16 {
+		SportTypeInfo::from(*self).is_frequency_range(frequency)
+	}
+}
+
+/// The id of a sport type registered on chain.
+/// The ids of the built-in sport types are the values of `SportType`.
+pub type SportId = u16;
+
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SportTypeInfo {
+	/// the format of training reports uploaded by the devices
+	pub report_type: SportType,
+	/// how long to consume an energy, unit: seconds
+	pub training_unit_duration: u16,
+	/// the frequency used to evaluate skill score
+	pub frequency_standard: u16,
+	/// the normal range of average frequency
+	pub min_frequency: u16,
+	pub max_frequency: u16,
+}
+
+impl SportTypeInfo {
+	pub fn is_frequency_range(&self, frequency: u16) -> u16 {
+		if (self.min_frequency..=self.max_frequency).contains(&frequency) {
+			1
+		} else {
+			0
+		}
+	}
+
+	pub fn is_valid(&self) -> bool {
+		self.training_unit_duration > 0 &&
+			self.frequency_standard > 0 &&
+			self.min_frequency <= self.max_frequency
+	}
+}
+
+impl From<SportType> for SportTypeInfo {
+	fn from(sport_type: SportType) -> Self {
+		let (min_frequency, max_frequency) = sport_type.frequency_range();
+		SportTypeInfo {
+			report_type: sport_type,
+			training_unit_duration: sport_type.training_unit_duration(),
+			frequency_standard: sport_type.frequency_standard(),
+			min_frequency,
+			max_frequency,
 		}
 	}
 }
@@ -101,7 +163,7 @@ pub struct Producer<ObjectId, Account> {
 #[scale_info(skip_type_params(StringLimit))]
 pub struct VFEBrand<CollectionId, StringLimit: Get<u32>> {
 	pub brand_id: CollectionId,
-	pub sport_type: SportType,
+	pub sport_type: SportId,
 	pub rarity: VFERarity,
 	pub approvals: u32,
 	pub uri: BoundedVec<u8, StringLimit>,
@@ -116,9 +178,40 @@ pub struct VFEBrandApprove<AssetId, Balance> {
 	pub locked_of_mint: Balance,
 }
 
+/// The certificate of device signed by the certificate key of producer.
+#[derive(Eq, PartialEq, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct DeviceCertificate<ObjectId, CollectionId, Signature> {
+	pub producer_id: ObjectId,
+	pub brand_id: CollectionId,
+	/// the signature of the producer certificate key over `(puk, brand_id).encode()`
+	pub signature: Signature,
+}
+
+/// A batch of devices committed by the Merkle root of the device keys.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct DeviceBatch<ObjectId, AssetId, Balance> {
+	pub producer_id: ObjectId,
+	/// the number of devices reserved from the approval
+	pub count: u32,
+	/// the number of devices claimed at binding time
+	pub claimed: u32,
+	/// the minting fee of each device locked up front
+	pub mint_cost: Option<(AssetId, Balance)>,
+}
+
+/// The Merkle proof of a device key in the committed batch.
+#[derive(Eq, PartialEq, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct DeviceBatchProof<CollectionId, Hash, Proof> {
+	pub brand_id: CollectionId,
+	/// the Merkle root of the batch
+	pub root: Hash,
+	/// the sibling hashes from the leaf to the root
+	pub proof: Proof,
+}
+
 #[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
 pub struct Device<CollectionId, ItemId, ObjectId, AssetId, Balance> {
-	pub sport_type: SportType,
+	pub sport_type: SportId,
 	pub brand_id: CollectionId,
 	pub item_id: Option<ItemId>,
 	pub producer_id: ObjectId,
@@ -140,6 +233,47 @@ pub struct VFEAbility {
 	pub durable: u16,
 }
 
+impl VFEAbility {
+	/// The highest of all abilities.
+	pub fn highest(&self) -> u16 {
+		self.efficiency.max(self.skill).max(self.luck).max(self.durable)
+	}
+
+	/// The sum of all abilities, which does not overflow.
+	pub fn sum(&self) -> u64 {
+		self.efficiency as u64 + self.skill as u64 + self.luck as u64 + self.durable as u64
+	}
+
+	pub fn saturating_add(&self, other: &Self) -> Self {
+		VFEAbility {
+			efficiency: self.efficiency.saturating_add(other.efficiency),
+			skill: self.skill.saturating_add(other.skill),
+			luck: self.luck.saturating_add(other.luck),
+			durable: self.durable.saturating_add(other.durable),
+		}
+	}
+
+	pub fn saturating_sub(&self, other: &Self) -> Self {
+		VFEAbility {
+			efficiency: self.efficiency.saturating_sub(other.efficiency),
+			skill: self.skill.saturating_sub(other.skill),
+			luck: self.luck.saturating_sub(other.luck),
+			durable: self.durable.saturating_sub(other.durable),
+		}
+	}
+}
+
+/// The gem or equipment which can be socketed into VFE.
+#[derive(
+	Encode, Decode, Default, Copy, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+pub struct GemDetail {
+	/// the ability added to the VFE while socketed
+	pub ability: VFEAbility,
+	/// the permanent gem can not be removed once socketed
+	pub permanent: bool,
+}
+
 #[derive(
 	Encode, Decode, Copy, Clone, Default, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen,
 )]
@@ -198,6 +332,350 @@ impl VFERarity {
 	}
 }
 
+/// The breeding information of VFE.
+#[derive(
+	Encode, Decode, Copy, Clone, Default, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+pub struct BreedInfo<BlockNumber> {
+	/// the number of times the VFE has bred
+	pub count: u16,
+	/// the VFE can not breed again until this block
+	pub cooldown_until: BlockNumber,
+}
+
+/// Where the fee goes.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum FeeDestination {
+	/// Burn the fee
+	Burn,
+	/// Transfer the fee to the owner of VFE brand
+	BrandOwner,
+	/// Transfer the fee to the treasury
+	Treasury,
+}
+
+/// The settings of unbinding device, which can be tuned by governance.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct UnbindSettings<AssetId, Balance, BlockNumber> {
+	/// the asset of fee, `None` is the incentive token
+	pub asset: Option<AssetId>,
+	/// the fee of unbinding
+	pub fee: Balance,
+	/// where the fee goes
+	pub destination: FeeDestination,
+	/// how long the VFE and the device can not be bound again after unbinding
+	pub cooldown: BlockNumber,
+}
+
+/// The emission of the incentive token in a period.
+#[derive(
+	Encode, Decode, Copy, Clone, Default, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+pub struct EmissionState<Balance> {
+	/// the index of the period
+	pub period: u32,
+	/// the budget of the period
+	pub budget: Balance,
+	/// the incentive tokens minted in the period
+	pub minted: Balance,
+}
+
+/// The leaderboard ranks the users by the training volume in each period.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum LeaderboardKey<CollectionId> {
+	/// ranks the users of the sport type
+	Sport(SportId),
+	/// ranks the users training with the VFEs of the brand
+	Brand(CollectionId),
+}
+
+/// The settings of the leaderboard.
+#[derive(Eq, PartialEq, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxRanks))]
+pub struct LeaderboardInfo<AssetId, BlockNumber, MaxRanks: Get<u32>> {
+	/// the asset of the prize pool
+	pub asset_id: AssetId,
+	/// number of blocks of each period
+	pub period: BlockNumber,
+	/// the shares of the prize pool paid to the top ranks at the end of the period
+	pub prize_shares: BoundedVec<Permill, MaxRanks>,
+}
+
+/// The top ranks of the leaderboard in the period.
+#[derive(Eq, PartialEq, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxRanks))]
+pub struct LeaderboardRanks<AccountId, MaxRanks: Get<u32>> {
+	pub period: u32,
+	/// the users and their training volume, ordered from the highest
+	pub ranks: BoundedVec<(AccountId, u64), MaxRanks>,
+}
+
+/// The top ranks of the ended period of the leaderboard to be settled.
+#[derive(Eq, PartialEq, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxRanks))]
+pub struct EndedLeaderboardRanks<AccountId, Balance, MaxRanks: Get<u32>> {
+	/// the users and their training volume, ordered from the highest
+	pub ranks: BoundedVec<(AccountId, u64), MaxRanks>,
+	/// the prizes reserved from the prize pool when the period ends
+	pub prizes: Balance,
+}
+
+/// The metric measured by the brand challenge.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum ChallengeMetric {
+	/// number of jumps, steps or pedal strokes
+	TrainingCount,
+	/// training duration, unit: seconds
+	TrainingDuration,
+	/// number of the training reports
+	Trainings,
+}
+
+/// The terms of the brand challenge defined by the brand owner.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct ChallengeTerms<AssetId, Balance, BlockNumber> {
+	pub metric: ChallengeMetric,
+	/// the participant completes the challenge when the metric reaches the target
+	pub target: u64,
+	/// the trainings are counted from the start block to the end block
+	pub start: BlockNumber,
+	pub end: BlockNumber,
+	/// the asset of the entry fee and the prize pool
+	pub asset_id: AssetId,
+	pub entry_fee: Balance,
+}
+
+impl<AssetId, Balance, BlockNumber> ChallengeTerms<AssetId, Balance, BlockNumber> {
+	/// The progress made by the training.
+	pub fn progress_of(&self, training: &TrainingSummary) -> u64 {
+		match self.metric {
+			ChallengeMetric::TrainingCount => training.training_count as u64,
+			ChallengeMetric::TrainingDuration => training.training_duration as u64,
+			ChallengeMetric::Trainings => 1,
+		}
+	}
+}
+
+/// The time-boxed challenge run by the brand owner.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct BrandChallenge<CollectionId, AssetId, Balance, BlockNumber> {
+	pub brand_id: CollectionId,
+	pub terms: ChallengeTerms<AssetId, Balance, BlockNumber>,
+	pub participants: u32,
+	/// number of the participants who reach the target
+	pub completed: u32,
+	/// the prize of each participant who completes the challenge, set when it is settled
+	pub prize: Option<Balance>,
+}
+
+/// The participation of the user in the brand challenge.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct ChallengeEntry<ItemId> {
+	/// the VFE which the trainings are counted with
+	pub item_id: ItemId,
+	pub progress: u64,
+	pub claimed: bool,
+}
+
+/// The team of users who pool their training progress.
+#[derive(Eq, PartialEq, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxMembers))]
+pub struct Team<AccountId, MaxMembers: Get<u32>> {
+	/// the captain manages the membership
+	pub captain: AccountId,
+	/// the members including the captain
+	pub members: BoundedVec<AccountId, MaxMembers>,
+}
+
+/// The training progress of the team.
+#[derive(
+	Encode, Decode, Copy, Clone, Default, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+pub struct TeamProgress {
+	/// the latest period with training
+	pub period: u32,
+	/// the training volume of the members in the period
+	pub volume: u64,
+	/// the latest period when the team reached the goal
+	pub achieved: Option<u32>,
+}
+
+/// The team details for the runtime API.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TeamSummary<AccountId> {
+	pub team_id: u32,
+	pub captain: AccountId,
+	pub members: Vec<AccountId>,
+	/// the training volume of the members in the current period
+	pub volume: u64,
+	/// the training volume to reach in the current period
+	pub goal: u64,
+	/// whether the members earn the bonus in the current period
+	pub bonus_active: bool,
+}
+
+/// The VFE listed by the owner for lending.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct VFELoan<AccountId, BlockNumber> {
+	/// the VFE owner who lends the VFE
+	pub owner: AccountId,
+	/// the blocks of the loan
+	pub duration: BlockNumber,
+	/// the share of the training rewards paid to the owner
+	pub owner_share: Permill,
+	/// the borrower and the block the loan expires
+	pub borrower: Option<(AccountId, BlockNumber)>,
+}
+
+/// The daily cap of the user boosted by the staked VFE.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum StakingTarget {
+	/// Boost the energy cap
+	Energy,
+	/// Boost the earning cap
+	EarningCap,
+}
+
+/// The boosts of the daily caps of the user.
+#[derive(
+	Encode, Decode, Copy, Clone, Default, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+pub struct StakingBoost<Balance> {
+	pub energy: u16,
+	pub earning_cap: Balance,
+}
+
+/// The VFE staked by the owner.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct StakedVFE<AccountId, Balance, BlockNumber> {
+	/// the VFE owner who stakes the VFE
+	pub owner: AccountId,
+	/// the boost added to the owner when the VFE is staked
+	pub boost: StakingBoost<Balance>,
+	/// the VFE can be withdrawn after this block once it is unstaked
+	pub unbonding_until: Option<BlockNumber>,
+}
+
+/// The aggregated trainings of a user in a sport on one day.
+#[derive(
+	Encode, Decode, Copy, Clone, Default, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct DailyTraining<Balance> {
+	/// days since the unix epoch
+	pub day: u32,
+	pub sport_type: SportId,
+	/// total training duration, unit: seconds
+	pub training_duration: u32,
+	/// total number of jumps, steps or pedal strokes
+	pub training_count: u32,
+	pub energy_used: u32,
+	/// the training rewards earned, including the held rewards
+	pub rewards: Balance,
+	/// number of the training reports
+	pub reports: u16,
+}
+
+/// The training rewards of the report held while the device is quarantined.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct HeldReport<AccountId, Balance, BlockNumber> {
+	/// the VFE owner who receives the rewards
+	pub owner: AccountId,
+	/// the rewards in the incentive token
+	pub amount: Balance,
+	/// the block the report is uploaded
+	pub reported_at: BlockNumber,
+	/// whether the report is in the referral period of the owner
+	pub referral: bool,
+}
+
+/// The training rewards held during the challenge window of the report.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct PendingReport<AccountId, CollectionId, ItemId, Balance, BlockNumber> {
+	/// the VFE owner who receives the rewards
+	pub owner: AccountId,
+	pub brand_id: CollectionId,
+	pub item_id: ItemId,
+	/// the rewards in the incentive token
+	pub amount: Balance,
+	/// the block the report is uploaded
+	pub reported_at: BlockNumber,
+	/// the report can be challenged until this block
+	pub deadline: BlockNumber,
+	/// the auditor who challenges the report
+	pub challenger: Option<AccountId>,
+}
+
+/// How the brand rewards work with the incentive token.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum BrandRewardMode {
+	/// Pay from the brand pool, and mint the incentive token for the part the pool can not cover
+	Fallback,
+	/// Pay from the brand pool in addition to the incentive token
+	Combine,
+}
+
+/// The reward settings of VFE brand.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct BrandRewardConfig<AssetId> {
+	/// the asset paid from the brand pool
+	pub asset_id: AssetId,
+	/// how the brand rewards work with the incentive token
+	pub mode: BrandRewardMode,
+	/// the amount of the asset paid for one unit of the incentive token rewards
+	pub rate: FixedU128,
+}
+
+/// The economic parameters of the game, which can be tuned by governance.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct EconomicParameters<Balance> {
+	/// Units of Incentive Tokens Rewarded or Costed
+	pub cost_unit: Balance,
+	/// level up cost factor
+	pub level_up_cost_factor: Balance,
+	/// init energy when new user created
+	pub init_energy: u16,
+	/// init earning cap of daily when new user created
+	pub init_earning_cap: u16,
+	/// ratio of each energy recovery
+	pub energy_recovery_ratio: Permill,
+	/// Profit ratio of minting fee to VFE owner
+	pub user_vfe_minted_profit_ratio: Permill,
+	/// the range of base ability of each rarity, indexed by `VFERarity`
+	pub base_range_of_ability: [(u16, u16); 4],
+	/// the growth points of each rarity, indexed by `VFERarity`
+	pub growth_points: [u16; 4],
+}
+
+impl<Balance: Zero> EconomicParameters<Balance> {
+	/// The maximum of the base ability of the new VFE. The formulas of the game are calculated
+	/// in the wider integers, so the bound only keeps the abilities of each rarity comparable.
+	pub const MAX_BASE_ABILITY: u16 = 1000;
+	/// The maximum of init energy, the energy cap of high level saturates at `u16::MAX`.
+	pub const MAX_INIT_ENERGY: u16 = 1000;
+
+	pub fn base_range_of_ability(&self, rarity: VFERarity) -> (u16, u16) {
+		self.base_range_of_ability[rarity as usize]
+	}
+
+	pub fn growth_points(&self, rarity: VFERarity) -> u16 {
+		self.growth_points[rarity as usize]
+	}
+
+	pub fn is_valid(&self) -> bool {
+		!self.cost_unit.is_zero() &&
+			!self.level_up_cost_factor.is_zero() &&
+			(1..=Self::MAX_INIT_ENERGY).contains(&self.init_energy) &&
+			self.init_earning_cap > 0 &&
+			self.base_range_of_ability
+				.iter()
+				.all(|(min, max)| *min > 0 && min <= max && *max <= Self::MAX_BASE_ABILITY) &&
+			self.growth_points.iter().all(|g| *g > 0)
+	}
+}
+
 #[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
 pub struct JumpRopeTrainingReport {
 	pub timestamp: u32,
@@ -239,6 +717,21 @@ impl TryFrom<Vec<u8>> for JumpRopeTrainingReport {
 	}
 }
 
+impl From<JumpRopeTrainingReport> for TrainingSummary {
+	fn from(report: JumpRopeTrainingReport) -> Self {
+		TrainingSummary {
+			timestamp: report.timestamp,
+			training_duration: report.training_duration,
+			effective_duration: report.jump_rope_duration,
+			training_count: report.total_jump_rope_count,
+			average_frequency: report.average_speed,
+			peak_frequency: report.max_speed,
+			performance: report.max_jump_rope_count,
+			interruptions: report.interruptions,
+		}
+	}
+}
+
 // impl Into<Vec<u8>> for JumpRopeTrainingReport {
 // 	fn into(self) -> Vec<u8> {
 // 		let mut bytes: Vec<u8> = Vec::new();
@@ -268,3 +761,329 @@ impl From<JumpRopeTrainingReport> for Vec<u8> {
 		bytes
 	}
 }
+
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct RunningTrainingReport {
+	/// report time, unit: seconds
+	pub timestamp: u32,
+	/// unit: seconds
+	pub training_duration: u16,
+	/// unit: meters
+	pub distance: u32,
+	pub steps: u32,
+	/// unit: seconds/kilometer
+	pub average_pace: u16,
+	/// unit: steps/minute
+	pub average_cadence: u16,
+}
+
+impl RunningTrainingReport {
+	/// The fastest average pace accepted for a training, unit: seconds/kilometer
+	pub const MIN_PACE: u16 = 150;
+	/// The slowest average pace accepted for a training, unit: seconds/kilometer
+	pub const MAX_PACE: u16 = 1200;
+	/// The longest stride accepted for a training, unit: centimeters
+	pub const MAX_STRIDE: u32 = 300;
+
+	/// Check if the reported values are consistent with each other and with human running.
+	pub fn is_plausible(&self) -> bool {
+		if self.training_duration == 0 || self.distance == 0 || self.steps == 0 {
+			return false
+		}
+		if !(Self::MIN_PACE..=Self::MAX_PACE).contains(&self.average_pace) {
+			return false
+		}
+		// the pace and cadence must match the totals within 10%
+		let pace = self.training_duration as u32 * 1000 / self.distance;
+		let cadence = self.steps.saturating_mul(60) / self.training_duration as u32;
+		if pace.abs_diff(self.average_pace as u32) > self.average_pace as u32 / 10 + 1 {
+			return false
+		}
+		if cadence.abs_diff(self.average_cadence as u32) > self.average_cadence as u32 / 10 + 1 {
+			return false
+		}
+		self.distance.saturating_mul(100) / self.steps <= Self::MAX_STRIDE
+	}
+}
+
+impl TryFrom<Vec<u8>> for RunningTrainingReport {
+	type Error = ();
+
+	fn try_from(report_data: Vec<u8>) -> Result<Self, Self::Error> {
+		if report_data.len() < 18 {
+			return Result::Err(())
+		}
+		let timestamp_vec = report_data[0..4].try_into().map_err(|_| ())?;
+		let training_duration_vec: [u8; 2] = report_data[4..6].try_into().map_err(|_| ())?;
+		let distance_vec: [u8; 4] = report_data[6..10].try_into().map_err(|_| ())?;
+		let steps_vec: [u8; 4] = report_data[10..14].try_into().map_err(|_| ())?;
+		let average_pace_vec: [u8; 2] = report_data[14..16].try_into().map_err(|_| ())?;
+		let average_cadence_vec: [u8; 2] = report_data[16..18].try_into().map_err(|_| ())?;
+
+		Ok(RunningTrainingReport {
+			timestamp: u32::from_le_bytes(timestamp_vec),
+			training_duration: u16::from_le_bytes(training_duration_vec),
+			distance: u32::from_le_bytes(distance_vec),
+			steps: u32::from_le_bytes(steps_vec),
+			average_pace: u16::from_le_bytes(average_pace_vec),
+			average_cadence: u16::from_le_bytes(average_cadence_vec),
+		})
+	}
+}
+
+impl From<RunningTrainingReport> for Vec<u8> {
+	fn from(report: RunningTrainingReport) -> Self {
+		let mut bytes: Vec<u8> = Vec::new();
+		bytes.extend(report.timestamp.to_le_bytes());
+		bytes.extend(report.training_duration.to_le_bytes());
+		bytes.extend(report.distance.to_le_bytes());
+		bytes.extend(report.steps.to_le_bytes());
+		bytes.extend(report.average_pace.to_le_bytes());
+		bytes.extend(report.average_cadence.to_le_bytes());
+		bytes
+	}
+}
+
+impl From<RunningTrainingReport> for TrainingSummary {
+	fn from(report: RunningTrainingReport) -> Self {
+		TrainingSummary {
+			timestamp: report.timestamp,
+			training_duration: report.training_duration,
+			effective_duration: report.training_duration,
+			training_count: report.steps.saturated_into(),
+			average_frequency: report.average_cadence,
+			peak_frequency: report.average_cadence,
+			performance: report.average_cadence,
+			interruptions: 0,
+		}
+	}
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct RidingTrainingReport {
+	/// report time, unit: seconds
+	pub timestamp: u32,
+	/// unit: seconds
+	pub training_duration: u16,
+	/// unit: meters
+	pub distance: u32,
+	/// unit: 0.1 kilometers/hour
+	pub average_speed: u16,
+	/// unit: revolutions/minute
+	pub average_cadence: u16,
+	/// unit: watts, 0 if the device has no power meter
+	pub average_power: u16,
+}
+
+impl RidingTrainingReport {
+	/// The highest average speed accepted for a training, unit: 0.1 kilometers/hour
+	pub const MAX_SPEED: u16 = 800;
+	/// The highest average power accepted for a training, unit: watts
+	pub const MAX_POWER: u16 = 2000;
+
+	/// Check if the reported values are consistent with each other and with human riding.
+	pub fn is_plausible(&self) -> bool {
+		if self.training_duration == 0 || self.distance == 0 {
+			return false
+		}
+		if self.average_speed > Self::MAX_SPEED || self.average_power > Self::MAX_POWER {
+			return false
+		}
+		// the speed must match the distance and duration within 10%
+		let speed = self.distance.saturating_mul(36) / self.training_duration as u32;
+		speed.abs_diff(self.average_speed as u32) <= self.average_speed as u32 / 10 + 1
+	}
+}
+
+impl TryFrom<Vec<u8>> for RidingTrainingReport {
+	type Error = ();
+
+	fn try_from(report_data: Vec<u8>) -> Result<Self, Self::Error> {
+		if report_data.len() < 16 {
+			return Result::Err(())
+		}
+		let timestamp_vec = report_data[0..4].try_into().map_err(|_| ())?;
+		let training_duration_vec: [u8; 2] = report_data[4..6].try_into().map_err(|_| ())?;
+		let distance_vec: [u8; 4] = report_data[6..10].try_into().map_err(|_| ())?;
+		let average_speed_vec: [u8; 2] = report_data[10..12].try_into().map_err(|_| ())?;
+		let average_cadence_vec: [u8; 2] = report_data[12..14].try_into().map_err(|_| ())?;
+		let average_power_vec: [u8; 2] = report_data[14..16].try_into().map_err(|_| ())?;
+
+		Ok(RidingTrainingReport {
+			timestamp: u32::from_le_bytes(timestamp_vec),
+			training_duration: u16::from_le_bytes(training_duration_vec),
+			distance: u32::from_le_bytes(distance_vec),
+			average_speed: u16::from_le_bytes(average_speed_vec),
+			average_cadence: u16::from_le_bytes(average_cadence_vec),
+			average_power: u16::from_le_bytes(average_power_vec),
+		})
+	}
+}
+
+impl From<RidingTrainingReport> for Vec<u8> {
+	fn from(report: RidingTrainingReport) -> Self {
+		let mut bytes: Vec<u8> = Vec::new();
+		bytes.extend(report.timestamp.to_le_bytes());
+		bytes.extend(report.training_duration.to_le_bytes());
+		bytes.extend(report.distance.to_le_bytes());
+		bytes.extend(report.average_speed.to_le_bytes());
+		bytes.extend(report.average_cadence.to_le_bytes());
+		bytes.extend(report.average_power.to_le_bytes());
+		bytes
+	}
+}
+
+impl From<RidingTrainingReport> for TrainingSummary {
+	fn from(report: RidingTrainingReport) -> Self {
+		let strokes = report.average_cadence as u32 * report.training_duration as u32 / 60;
+		TrainingSummary {
+			timestamp: report.timestamp,
+			training_duration: report.training_duration,
+			effective_duration: report.training_duration,
+			training_count: strokes.saturated_into(),
+			average_frequency: report.average_cadence,
+			peak_frequency: report.average_cadence,
+			performance: report.average_cadence,
+			interruptions: 0,
+		}
+	}
+}
+
+/// A self-describing training report, encoded as
+/// `magic(u8) | version(u8) | sport_type(u8) | payload_length(u16) | payload`.
+///
+/// The payload format is defined by the sport type and the version. Bytes appended to the
+/// payload by newer firmware are ignored by the runtime which does not know them yet.
+#[derive(Eq, PartialEq, Clone, RuntimeDebug)]
+pub struct TrainingReportEnvelope {
+	pub version: u8,
+	pub sport_type: SportType,
+	pub payload: Vec<u8>,
+}
+
+impl TrainingReportEnvelope {
+	/// The first byte of an envelope. The legacy reports start with a little-endian timestamp,
+	/// whose low byte equals the magic for 1 in 256 reports, so a report is only taken as an
+	/// envelope if the sport type and the payload length in the header are also consistent.
+	pub const MAGIC: u8 = 0xFE;
+	/// The length of the envelope header.
+	pub const HEADER_LEN: usize = 5;
+	/// The version of the fixed little-endian payload formats.
+	pub const VERSION_1: u8 = 1;
+}
+
+impl TryFrom<Vec<u8>> for TrainingReportEnvelope {
+	type Error = ();
+
+	fn try_from(report_data: Vec<u8>) -> Result<Self, Self::Error> {
+		if report_data.len() < Self::HEADER_LEN || report_data[0] != Self::MAGIC {
+			return Result::Err(())
+		}
+		let version = report_data[1];
+		let sport_type = SportType::try_from(report_data[2])?;
+		let payload_len_vec: [u8; 2] = report_data[3..5].try_into().map_err(|_| ())?;
+		let payload_len = u16::from_le_bytes(payload_len_vec) as usize;
+		if report_data.len() != Self::HEADER_LEN + payload_len {
+			return Result::Err(())
+		}
+
+		Ok(TrainingReportEnvelope {
+			version,
+			sport_type,
+			payload: report_data[Self::HEADER_LEN..].to_vec(),
+		})
+	}
+}
+
+impl From<TrainingReportEnvelope> for Vec<u8> {
+	fn from(envelope: TrainingReportEnvelope) -> Self {
+		let mut bytes: Vec<u8> = Vec::new();
+		bytes.push(TrainingReportEnvelope::MAGIC);
+		bytes.push(envelope.version);
+		bytes.push(envelope.sport_type as u8);
+		bytes.extend((envelope.payload.len() as u16).to_le_bytes());
+		bytes.extend(envelope.payload);
+		bytes
+	}
+}
+
+/// The sport independent result of a training report, used to calculate the rewards.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct TrainingSummary {
+	/// report time, unit: seconds
+	pub timestamp: u32,
+	/// total training duration, unit: seconds
+	pub training_duration: u16,
+	/// training duration which consumes energy, unit: seconds
+	pub effective_duration: u16,
+	/// number of jumps, steps or pedal strokes
+	pub training_count: u16,
+	/// average frequency which must be in the normal range of the sport
+	pub average_frequency: u16,
+	/// the highest frequency during the training
+	pub peak_frequency: u16,
+	/// the best performance of the training, used to calculate skill score
+	pub performance: u16,
+	pub interruptions: u8,
+}
+
+/// The statistical rules to check the plausibility of training reports, the violations raise the
+/// suspicion score of the device.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct PlausibilityRules {
+	/// the maximum of the peak frequency, in percent of the average frequency
+	pub max_peak_percent: u16,
+	/// the tolerance between the training count and the count derived from the average
+	/// frequency and the effective duration, in percent
+	pub count_tolerance_percent: u16,
+	/// the minimum training count between two interruptions
+	pub min_count_per_interruption: u16,
+	/// the maximum number of reports of a device per day
+	pub max_reports_per_day: u16,
+	/// the suspicion score added by each violated rule
+	pub violation_score: u32,
+	/// the suspicion score reduced by each plausible report
+	pub decay_score: u32,
+	/// the device is quarantined when its suspicion score reaches it
+	pub quarantine_threshold: u32,
+}
+
+impl PlausibilityRules {
+	pub fn is_valid(&self) -> bool {
+		self.max_peak_percent >= 100 &&
+			self.max_reports_per_day > 0 &&
+			self.quarantine_threshold > 0
+	}
+
+	/// Count the rules violated by the training and the number of reports of the day.
+	pub fn count_violations(&self, training: &TrainingSummary, reports_today: u16) -> u32 {
+		let mut violations = 0;
+
+		// the peak frequency can not be lower than the average or too much higher
+		let average = training.average_frequency as u32;
+		let peak = training.peak_frequency as u32;
+		if peak < average || peak * 100 > average * self.max_peak_percent as u32 {
+			violations += 1;
+		}
+
+		// the training count must match the average frequency and the effective duration
+		let expected = average * training.effective_duration as u32 / 60;
+		let count = training.training_count as u32;
+		if count.abs_diff(expected) * 100 >
+			expected.saturating_mul(self.count_tolerance_percent as u32)
+		{
+			violations += 1;
+		}
+
+		// too many interruptions for the training count
+		if training.interruptions as u32 * self.min_count_per_interruption as u32 > count {
+			violations += 1;
+		}
+
+		if reports_today > self.max_reports_per_day {
+			violations += 1;
+		}
+
+		violations
+	}
+}
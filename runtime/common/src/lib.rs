@@ -6,7 +6,7 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use polket_primitives::{ObjectId, BlockNumber, Hash};
+use polket_primitives::{AccountId, Balance, ObjectId, BlockNumber, Hash};
 
 pub mod origin;
 
@@ -19,5 +19,8 @@ static_assertions::assert_eq_size!(polket_primitives::Balance, u128);
 
 pub type VFEInstance = pallet_uniques::Instance1;
 pub type CouponsInstance = pallet_uniques::Instance2;
+pub type GemsInstance = pallet_uniques::Instance3;
 
 pub type VFEDetail = pallet_vfe::types::VFEDetail<ObjectId, ObjectId, Hash, BlockNumber>;
+pub type DailyTraining = pallet_vfe::types::DailyTraining<Balance>;
+pub type TeamSummary = pallet_vfe::types::TeamSummary<AccountId>;
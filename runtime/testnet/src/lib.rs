@@ -18,7 +18,11 @@ use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
 // use pallet_support::identity::IdentityRoleProducer;
-pub use runtime_common::{origin::EnsureIdentity, CurrencyToVote, VFEDetail, VFEInstance};
+use pallet_support::identity::IdentityRoleAudit;
+pub use runtime_common::{
+	origin::EnsureIdentity, CurrencyToVote, DailyTraining, GemsInstance, TeamSummary, VFEDetail,
+	VFEInstance,
+};
 use sp_api::impl_runtime_apis;
 use sp_core::{crypto::KeyTypeId, Hasher, OpaqueMetadata};
 use sp_runtime::{
@@ -74,8 +78,8 @@ pub mod constants;
 
 use crate::constants::currency::MILLICENTS;
 use constants::{
-	currency::DOLLARS,
-	id::{ASSET_ID, BUYBACK_PLAN_ID, OFFER_ID, ORDER_ID, PRODUCER_ID, VFE_BRAND_ID},
+	currency::{CENTS, DOLLARS},
+	id::{ASSET_ID, BUYBACK_PLAN_ID, GEM_ID, OFFER_ID, ORDER_ID, PRODUCER_ID, VFE_BRAND_ID},
 	time::*,
 };
 
@@ -111,7 +115,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("polket"),
 	impl_name: create_runtime_str!("polket"),
 	authoring_version: 1,
-	spec_version: 25,
+	spec_version: 26,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -351,6 +355,25 @@ impl pallet_uniques::Config<VFEInstance> for Runtime {
 	type Locker = ();
 }
 
+impl pallet_uniques::Config<GemsInstance> for Runtime {
+	type Event = Event;
+	type CollectionId = ObjectId;
+	type ItemId = ObjectId;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type CollectionDeposit = ClassDeposit;
+	type ItemDeposit = InstanceDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type AttributeDepositBase = AttributeDepositBase;
+	type DepositPerByte = MetadataDepositPerByte;
+	type StringLimit = StringLimit;
+	type KeyLimit = KeyLimit;
+	type ValueLimit = ValueLimit;
+	type WeightInfo = ();
+	type Locker = ();
+}
+
 pub struct TestFreezer;
 
 impl pallet_assets::FrozenBalance<ObjectId, AccountId, Balance> for TestFreezer {
@@ -673,6 +696,7 @@ impl pallet_multisig::Config for Runtime {
 parameter_types! {
 	pub ProducerId: Hash = BlakeTwo256::hash(PRODUCER_ID);
 	pub VFEBrandId: Hash = BlakeTwo256::hash(VFE_BRAND_ID);
+	pub GemId: Hash = BlakeTwo256::hash(GEM_ID);
 
 	pub OrderId: Hash = BlakeTwo256::hash(ORDER_ID);
 	pub OfferId: Hash = BlakeTwo256::hash(OFFER_ID);
@@ -690,6 +714,36 @@ parameter_types! {
 	pub const EnergyRecoveryRatio: Permill = Permill::from_percent(25);
 	pub const ReportValidityPeriod: u32 = 24 * 60 * 60 * 1000; // 24 hours
 	pub const UserVFEMintedProfitRatio: Permill = Permill::from_percent(30); //30%
+	pub const MaxMerkleProofLength: u32 = 20;
+	pub const EmissionPeriod: BlockNumber = DAYS;
+	pub const InitialEmission: Balance = 100_000 * DOLLARS;
+	pub const HalvingPeriods: u32 = 365;
+	pub const ChallengeWindow: BlockNumber = HOURS * 6;
+	pub const MaxHistoryDays: u32 = 30;
+	pub const LeaderboardSize: u32 = 10;
+	pub const MaxBrandChallenges: u32 = 10;
+	pub const MaxTeamMembers: u32 = 20;
+	pub const TeamGoalPeriod: BlockNumber = DAYS * 7;
+	pub const TeamGoalPerMember: u64 = 500;
+	pub const TeamBonus: Permill = Permill::from_percent(10);
+	pub const ReferralShare: Permill = Permill::from_percent(5);
+	pub const ReferralPeriod: BlockNumber = DAYS * 30;
+	pub const MaxReferralRewards: Balance = 1000 * DOLLARS;
+	pub const StakingBoostRatio: Permill = Permill::from_percent(25);
+	pub const StakingUnbondingPeriod: BlockNumber = DAYS * 7;
+	pub VFETreasuryAccount: AccountId = Treasury::account_id();
+	pub const LevelUpDurationPerLevel: BlockNumber = MINUTES * 10;
+	pub const LevelUpSpeedUpCost: Balance = CENTS;
+	pub const BreedCooldown: BlockNumber = HOURS * 24;
+	pub const MaxBreedCount: u16 = 7;
+	pub const FusionCost: Balance = DOLLARS;
+	pub const WearPerEnergy: Permill = Permill::from_percent(1);
+	pub const WearPenaltyThreshold: Permill = Permill::from_percent(50);
+	pub const RepairCost: Balance = CENTS;
+	pub const MaxSockets: u32 = 4;
+	pub const LevelsPerSocket: u16 = 5;
+	pub const MaxGemAbility: u16 = 20;
+	pub const UnsocketFee: Balance = DOLLARS;
 }
 
 impl pallet_vfe::Config for Runtime {
@@ -715,6 +769,41 @@ impl pallet_vfe::Config for Runtime {
 	type UnixTime = Timestamp;
 	type ReportValidityPeriod = ReportValidityPeriod;
 	type UserVFEMintedProfitRatio = UserVFEMintedProfitRatio;
+	type GovernanceOrigin = MoreThanHalfCouncil;
+	type AuditOrigin = MoreThanHalfCouncil;
+	type ChallengeOrigin = EnsureIdentity<AccountId, IdentityRoleAudit, IdentityExtra>;
+	type ChallengeWindow = ChallengeWindow;
+	type MaxHistoryDays = MaxHistoryDays;
+	type LeaderboardSize = LeaderboardSize;
+	type MaxBrandChallenges = MaxBrandChallenges;
+	type MaxTeamMembers = MaxTeamMembers;
+	type TeamGoalPeriod = TeamGoalPeriod;
+	type TeamGoalPerMember = TeamGoalPerMember;
+	type TeamBonus = TeamBonus;
+	type ReferralShare = ReferralShare;
+	type ReferralPeriod = ReferralPeriod;
+	type MaxReferralRewards = MaxReferralRewards;
+	type StakingBoostRatio = StakingBoostRatio;
+	type StakingUnbondingPeriod = StakingUnbondingPeriod;
+	type LevelUpDurationPerLevel = LevelUpDurationPerLevel;
+	type LevelUpSpeedUpCost = LevelUpSpeedUpCost;
+	type BreedCooldown = BreedCooldown;
+	type MaxBreedCount = MaxBreedCount;
+	type FusionCost = FusionCost;
+	type WearPerEnergy = WearPerEnergy;
+	type WearPenaltyThreshold = WearPenaltyThreshold;
+	type RepairCost = RepairCost;
+	type Gems = Gems;
+	type GemId = GemId;
+	type MaxSockets = MaxSockets;
+	type LevelsPerSocket = LevelsPerSocket;
+	type MaxGemAbility = MaxGemAbility;
+	type UnsocketFee = UnsocketFee;
+	type TreasuryAccount = VFETreasuryAccount;
+	type MaxMerkleProofLength = MaxMerkleProofLength;
+	type EmissionPeriod = EmissionPeriod;
+	type InitialEmission = InitialEmission;
+	type HalvingPeriods = HalvingPeriods;
 }
 
 impl pallet_vfe_order::Config for Runtime {
@@ -779,6 +868,7 @@ construct_runtime!(
 		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>, Config<T>},
 		Identity: pallet_identity::{Pallet, Call, Storage, Event<T>},
 		VFEUniques: pallet_uniques::<Instance1>::{Pallet, Storage, Event<T>},
+		Gems: pallet_uniques::<Instance3>::{Pallet, Storage, Event<T>},
 		IdentityExtra: pallet_identity_extra::{Pallet, Call, Storage, Event<T>},
 		UniqueId: pallet_unique_id::{Pallet, Storage},
 		Currencies: pallet_currencies::{Pallet, Call, Storage, Event<T>},
@@ -1048,7 +1138,7 @@ impl_runtime_apis! {
 	}
 
 	//custom runtime-api
-	impl pallet_vfe_rpc_runtime_api::VfeApi<Block, AccountId, ObjectId, ObjectId, VFEDetail, Balance> for Runtime {
+	impl pallet_vfe_rpc_runtime_api::VfeApi<Block, AccountId, ObjectId, ObjectId, VFEDetail, DailyTraining, TeamSummary, Balance, BlockNumber> for Runtime {
 		fn get_vfe_details_by_address(account: AccountId, brand_id: ObjectId) -> Vec<VFEDetail> {
 			VFE::get_vfe_details_by_address(account, brand_id)
 		}
@@ -1060,6 +1150,34 @@ impl_runtime_apis! {
 		 fn get_level_up_costs(who: AccountId, brand_id: ObjectId, item: ObjectId) -> Balance {
 			VFE::get_level_up_costs(who, brand_id, item)
 		}
+
+		fn get_upgrade_completion(brand_id: ObjectId, item: ObjectId) -> Option<BlockNumber> {
+			VFE::get_upgrade_completion(brand_id, item)
+		}
+
+		fn get_emission_state() -> (u32, Balance, Balance) {
+			VFE::get_emission_state()
+		}
+
+		fn get_training_history(who: AccountId) -> Vec<DailyTraining> {
+			VFE::get_user_training_history(who)
+		}
+
+		fn get_sport_leaderboard(sport_type: u16) -> Vec<(AccountId, u64)> {
+			VFE::get_leaderboard(pallet_vfe::types::LeaderboardKey::Sport(sport_type))
+		}
+
+		fn get_brand_leaderboard(brand_id: ObjectId) -> Vec<(AccountId, u64)> {
+			VFE::get_leaderboard(pallet_vfe::types::LeaderboardKey::Brand(brand_id))
+		}
+
+		fn get_team(team_id: u32) -> Option<TeamSummary> {
+			VFE::get_team_summary(team_id)
+		}
+
+		fn get_user_team(who: AccountId) -> Option<TeamSummary> {
+			VFE::get_user_team_summary(who)
+		}
 	}
 
 	impl pallet_currencies_rpc_runtime_api::CurrenciesApi<Block, AccountId, ObjectId, Balance> for Runtime {